@@ -5,23 +5,36 @@ use std::{
 
 use dashmap::*;
 
+use oathc_borrowck::*;
+use oathc_comptime::*;
 use oathc_diagnostics::*;
 use oathc_file::*;
 use oathc_interner::*;
+use oathc_mono::*;
 use oathc_res::*;
 use oathc_tokens::*;
+use oathc_verifier::*;
 
-pub use oathc_diagnostics::{Diagnostic, Error, IdentCase, Warning};
+pub use oathc_diagnostics::{Counterexample, Diagnostic, Error, IdentCase, Label, Warning};
 pub use oathc_highlighting::{Highlight, HighlightColor};
 pub use oathc_span::{ConnectSpan, OptionSpanned, Position, Span, Spanned};
 pub use oathc_tokens::KEYWORDS;
+pub use oathc_verifier::{Assume, InferredContract};
 
 #[derive(Debug)]
 pub struct OathCompiler {
     interner: Arc<Interner>,
     file_interner: Arc<FileInterner>,
     diagnostics: Diagnostics,
-    libs: DashMap<LibId, DiskMod>,
+    libs: DashMap<LibId, Lib>,
+}
+
+#[derive(Debug)]
+struct Lib {
+    mod_: DiskMod,
+    checks: Vec<DiagnosticHandle>,
+    assumes: Vec<Assume>,
+    inferred: Vec<InferredContract>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -53,14 +66,24 @@ impl OathCompiler {
 
         let mod_ = DiskMod::new(dir_path, name, &self.interner, &self.file_interner, &self.diagnostics);
 
-        self.libs.insert(id, mod_);
+        let mut lib = Lib {
+            mod_,
+            checks: Vec::new(),
+            assumes: Vec::new(),
+            inferred: Vec::new(),
+        };
+        lib.check(&self.interner, &self.diagnostics);
+
+        self.libs.insert(id, lib);
 
         id
     }
 
     pub fn check_lib_changes(&self) {
         for mut lib in self.libs.iter_mut() {
-            lib.check(&self.interner, &self.file_interner, &self.diagnostics);
+            if lib.mod_.check(&self.interner, &self.file_interner, &self.diagnostics) {
+                lib.check(&self.interner, &self.diagnostics);
+            }
         }
     }
 
@@ -78,11 +101,21 @@ impl OathCompiler {
             .map(|(file_id, diagnostics)| (self.file_interner.unintern(file_id), diagnostics))
     }
 
+    /// The `assume` statements of a lib, which reviewers have to check since the verifier trusts them.
+    pub fn assumes(&self, lib: LibId) -> Vec<Assume> {
+        self.libs.get(&lib).map_or_else(Vec::new, |lib| lib.assumes.clone())
+    }
+
+    /// The contracts inferred for the functions of a lib without `pub`, which their calls in the lib rely on.
+    pub fn inferred_contracts(&self, lib: LibId) -> Vec<InferredContract> {
+        self.libs.get(&lib).map_or_else(Vec::new, |lib| lib.inferred.clone())
+    }
+
     pub fn file_highligts(&self, file: impl AsRef<Path>) -> impl Iterator<Item = Highlight> {
         let path = file.as_ref();
 
         for lib in self.libs.iter() {
-            if let Some(mod_) = lib.find(path) {
+            if let Some(mod_) = lib.mod_.find(path) {
                 return mod_.get_highlights().iter().copied().collect::<Vec<_>>().into_iter();
             }
         }
@@ -94,7 +127,39 @@ impl OathCompiler {
         diagnostic.to_string_interned(&self.interner)
     }
 
+    /// The file a span is in.
+    pub fn span_path(&self, span: Span) -> PathBuf {
+        self.file_interner.unintern(span.file())
+    }
+
     pub fn format(&self, text: impl AsRef<str>) -> String {
         text.as_ref().to_string()
     }
 }
+
+impl Lib {
+    /// Re-runs the semantic passes, replacing the diagnostics of the previous run.
+    fn check(&mut self, interner: &Interner, diagnostics: &Diagnostics) {
+        self.checks.clear();
+        self.assumes.clear();
+        self.inferred.clear();
+
+        let Some(content) = self.mod_.content() else {
+            return;
+        };
+
+        let namespace = Namespace::new(content, diagnostics, &mut self.checks);
+        self.assumes = assumes(&namespace, interner);
+        namespace.check_impls(interner, diagnostics, &mut self.checks);
+        let mut comptime = Comptime::new(&namespace, interner, diagnostics);
+        comptime.check_items(&mut self.checks);
+        let mut mono = Mono::new(&mut comptime, interner, diagnostics);
+        mono.check_items(&mut self.checks);
+        let instances = mono.generic_instances();
+
+        let mut verifier = Verifier::new(&mut comptime, interner, diagnostics);
+        verifier.check_items(&instances, &mut self.checks);
+        self.inferred = verifier.inferred_contracts();
+        BorrowChecker::new(&namespace, interner, diagnostics).check_items(&mut self.checks);
+    }
+}
@@ -0,0 +1,88 @@
+use std::{
+    env,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use oathc::*;
+
+const USAGE: &str = "usage: oathc <check | audit> <lib dir>";
+
+fn main() -> ExitCode {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let [command, dir] = args.as_slice() else {
+        eprintln!("{USAGE}");
+        return ExitCode::FAILURE;
+    };
+    let dir = PathBuf::from(dir);
+
+    let compiler = OathCompiler::new();
+    let lib = compiler.create_lib(dir.clone(), dir.join("oath.oh"));
+
+    match command.as_str() {
+        "check" => check(&compiler),
+        "audit" => audit(&compiler, lib),
+        _ => {
+            eprintln!("{USAGE}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Prints the diagnostics of the lib, and fails if any of them is an error.
+fn check(compiler: &OathCompiler) -> ExitCode {
+    let mut failed = false;
+
+    for (path, diagnostics) in compiler.diagnostics() {
+        for diagnostic in diagnostics {
+            let kind = match diagnostic {
+                Diagnostic::Error(_) => {
+                    failed = true;
+                    "error"
+                }
+                Diagnostic::Warning(_) => "warning",
+            };
+            println!(
+                "{}: {kind}: {}",
+                location(&path, diagnostic.span()),
+                compiler.format_diagnostic(&diagnostic)
+            );
+
+            for label in diagnostic.labels() {
+                println!(
+                    "    {}: {}",
+                    location(&compiler.span_path(label.span), label.span),
+                    label.message
+                );
+            }
+        }
+    }
+
+    if failed { ExitCode::FAILURE } else { ExitCode::SUCCESS }
+}
+
+/// Prints every `assume` of the lib, which the verifier trusts without proving, so they can be reviewed.
+fn audit(compiler: &OathCompiler, lib: LibId) -> ExitCode {
+    let assumes = compiler.assumes(lib);
+
+    for assume in &assumes {
+        let path = compiler.span_path(assume.span);
+        println!(
+            "{}: in `{}`: assume {}",
+            location(&path, assume.span),
+            assume.item,
+            assume.fact
+        );
+    }
+    match assumes.len() {
+        1 => println!("1 unchecked assumption"),
+        len => println!("{len} unchecked assumptions"),
+    }
+
+    ExitCode::SUCCESS
+}
+
+/// `path:line:char`, counting from 1 like editors do.
+fn location(path: &Path, span: Span) -> String {
+    format!("{}:{}:{}", path.display(), span.start().line + 1, span.start().char + 1)
+}
@@ -0,0 +1,309 @@
+//! Checks the libs in `tests/verifier` and compares their diagnostics with the `expected.txt` next to them,
+//! which is empty for libs that verify.
+
+use std::{fs, path::PathBuf};
+
+use oathc::*;
+
+fn check(name: &str) {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/verifier").join(name);
+
+    let compiler = OathCompiler::new();
+    compiler.create_lib(dir.clone(), dir.join("oath.oh"));
+
+    let mut output = Vec::new();
+    for (_, diagnostics) in compiler.diagnostics() {
+        for diagnostic in diagnostics {
+            let kind = match diagnostic {
+                Diagnostic::Error(_) => "error",
+                Diagnostic::Warning(_) => "warning",
+            };
+            let start = diagnostic.span().start();
+            output.push(format!(
+                "{}:{}: {kind}: {}",
+                start.line + 1,
+                start.char + 1,
+                compiler.format_diagnostic(&diagnostic)
+            ));
+        }
+    }
+    output.sort_by_key(|line| {
+        line.split(':')
+            .take(2)
+            .map(|n| n.parse::<u32>().unwrap_or(0))
+            .collect::<Vec<_>>()
+    });
+
+    let expected = fs::read_to_string(dir.join("expected.txt")).unwrap();
+    assert_eq!(output, expected.lines().collect::<Vec<_>>(), "diagnostics of `{name}`");
+}
+
+#[test]
+fn arithmetic() {
+    check("arithmetic");
+}
+
+#[test]
+fn arithmetic_fail() {
+    check("arithmetic_fail");
+}
+
+#[test]
+fn array_patterns() {
+    check("array_patterns");
+}
+
+#[test]
+fn array_patterns_fail() {
+    check("array_patterns_fail");
+}
+
+#[test]
+fn assume() {
+    check("assume");
+}
+
+#[test]
+fn assume_fail() {
+    check("assume_fail");
+}
+
+#[test]
+fn borrows() {
+    check("borrows");
+}
+
+#[test]
+fn borrows_fail() {
+    check("borrows_fail");
+}
+
+#[test]
+fn branches() {
+    check("branches");
+}
+
+#[test]
+fn branches_fail() {
+    check("branches_fail");
+}
+
+#[test]
+fn closures() {
+    check("closures");
+}
+
+#[test]
+fn closures_fail() {
+    check("closures_fail");
+}
+
+#[test]
+fn comptime() {
+    check("comptime");
+}
+
+#[test]
+fn comptime_fail() {
+    check("comptime_fail");
+}
+
+#[test]
+fn constraints() {
+    check("constraints");
+}
+
+#[test]
+fn constraints_fail() {
+    check("constraints_fail");
+}
+
+#[test]
+fn counterexamples_fail() {
+    check("counterexamples_fail");
+}
+
+#[test]
+fn generics() {
+    check("generics");
+}
+
+#[test]
+fn generics_fail() {
+    check("generics_fail");
+}
+
+#[test]
+fn impls() {
+    check("impls");
+}
+
+#[test]
+fn impls_fail() {
+    check("impls_fail");
+}
+
+#[test]
+fn index() {
+    check("index");
+}
+
+#[test]
+fn index_fail() {
+    check("index_fail");
+}
+
+#[test]
+fn inference() {
+    check("inference");
+}
+
+#[test]
+fn inference_fail() {
+    check("inference_fail");
+}
+
+#[test]
+fn int_ranges_fail() {
+    check("int_ranges_fail");
+}
+
+#[test]
+fn labels() {
+    check("labels");
+}
+
+#[test]
+fn labels_fail() {
+    check("labels_fail");
+}
+
+#[test]
+fn lifetimes() {
+    check("lifetimes");
+}
+
+#[test]
+fn lifetimes_fail() {
+    check("lifetimes_fail");
+}
+
+#[test]
+fn literal_overflow_fail() {
+    check("literal_overflow_fail");
+}
+
+#[test]
+fn loop_contracts() {
+    check("loop_contracts");
+}
+
+#[test]
+fn loop_contracts_fail() {
+    check("loop_contracts_fail");
+}
+
+#[test]
+fn matches() {
+    check("matches");
+}
+
+#[test]
+fn matches_fail() {
+    check("matches_fail");
+}
+
+#[test]
+fn patterns() {
+    check("patterns");
+}
+
+#[test]
+fn patterns_fail() {
+    check("patterns_fail");
+}
+
+#[test]
+fn predicates() {
+    check("predicates");
+}
+
+#[test]
+fn predicates_fail() {
+    check("predicates_fail");
+}
+
+#[test]
+fn quantifiers() {
+    check("quantifiers");
+}
+
+#[test]
+fn quantifiers_fail() {
+    check("quantifiers_fail");
+}
+
+#[test]
+fn ranges() {
+    check("ranges");
+}
+
+#[test]
+fn ranges_fail() {
+    check("ranges_fail");
+}
+
+#[test]
+fn references() {
+    check("references");
+}
+
+#[test]
+fn references_fail() {
+    check("references_fail");
+}
+
+#[test]
+fn structs_fail() {
+    check("structs_fail");
+}
+
+#[test]
+fn traits() {
+    check("traits");
+}
+
+#[test]
+fn traits_fail() {
+    check("traits_fail");
+}
+
+#[test]
+fn try_blocks() {
+    check("try_blocks");
+}
+
+#[test]
+fn try_blocks_fail() {
+    check("try_blocks_fail");
+}
+
+#[test]
+fn untagged() {
+    check("untagged");
+}
+
+#[test]
+fn untagged_fail() {
+    check("untagged_fail");
+}
+
+#[test]
+fn unverifiable_fail() {
+    check("unverifiable_fail");
+}
+
+#[test]
+fn wrapping_fail() {
+    check("wrapping_fail");
+}
@@ -27,6 +27,23 @@ pub struct AttrBody {
 #[derive(Debug, OptionParse)]
 #[desc = "a meta value"]
 pub enum AttrInput {
-    Fn(Tuple),
-    Assign(Assign),
+    Fn(Box<AttrArgs>),
+    Assign(Box<Assign>),
+}
+
+/// The arguments of an attribute like `#[deny(assume)]`, which can be keywords.
+#[derive(Debug, Spanned, OptionParse)]
+#[desc = "`( )`"]
+#[framed]
+pub struct AttrArgs {
+    pub frame: Frame<delims!("( )")>,
+    #[parse_as(Trailing<AttrArg, punct!(",")>)]
+    pub items: Vec<AttrArg>,
+}
+
+#[derive(Debug, OptionParse)]
+#[desc = "an attribute argument"]
+pub enum AttrArg {
+    Keyword(Keyword),
+    Expr(Box<Expr>),
 }
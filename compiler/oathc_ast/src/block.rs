@@ -11,18 +11,110 @@ pub struct Block {
     pub stmts: Vec<Stmt>,
 }
 
-#[derive(Debug, OptionParse)]
-#[desc = "a statement"]
+/// Attributes go on either an item or an expression statement, like `#[invariant(i: <= n)]` on a loop.
+#[derive(Debug)]
 pub enum Stmt {
     Item(Item),
     Let(LetStmt),
     Eval(ControlStmt<keyword!("eval")>),
     Return(ControlStmt<keyword!("return")>),
-    Break(ControlStmt<keyword!("break")>),
-    Continue(ControlStmt<keyword!("continue")>),
+    Break(BreakStmt),
+    Continue(ContinueStmt),
+    Assume(AssumeStmt),
     Expr(ExprStmt),
 }
 
+impl OptionParse for Stmt {
+    fn option_parse(parser: &mut impl Tokenizer, output: &mut Option<Self>) -> ParseExit {
+        // Which of an item and an expression the attributes are for only shows after them.
+        let mut attrs = Repeated::<Attr>::parse_error();
+        let attrs_exit = Repeated::parse(parser, &mut attrs);
+        let mut attrs: Vec<Attr> = attrs.into();
+
+        let is_item = (ItemModifier::detect(parser) | ItemCore::detect(parser)) == Detection::Detected;
+        if attrs_exit == ParseExit::Complete && !attrs.is_empty() && !is_item && ExprStmt::detect(parser) == Detection::Detected {
+            let mut stmt = None;
+            let exit = ExprStmt::option_parse(parser, &mut stmt);
+
+            *output = stmt.map(|mut stmt| {
+                attrs.append(&mut stmt.expr.attrs);
+                stmt.expr.attrs = attrs;
+
+                Self::Expr(stmt)
+            });
+
+            return exit;
+        }
+
+        if attrs_exit == ParseExit::Cut || !attrs.is_empty() || is_item {
+            let mut item = Item {
+                attrs,
+                modifiers: Vec::new(),
+                core: Try::parse_error(),
+            };
+            let exit = match attrs_exit {
+                ParseExit::Cut => ParseExit::Cut,
+                ParseExit::Complete => parse_item_rest(parser, &mut item),
+            };
+
+            *output = Some(Self::Item(item));
+
+            return exit;
+        }
+
+        option_parse_variant(parser, output, Self::Let)
+            .or_else(|| option_parse_variant(parser, output, Self::Eval))
+            .or_else(|| option_parse_variant(parser, output, Self::Return))
+            .or_else(|| option_parse_variant(parser, output, Self::Break))
+            .or_else(|| option_parse_variant(parser, output, Self::Continue))
+            .or_else(|| option_parse_variant(parser, output, Self::Assume))
+            .or_else(|| option_parse_variant(parser, output, Self::Expr))
+            .unwrap_or(ParseExit::Complete)
+    }
+
+    fn detect(parser: &impl Tokenizer) -> Detection {
+        Item::detect(parser)
+            | LetStmt::detect(parser)
+            | <ControlStmt<keyword!("eval")>>::detect(parser)
+            | <ControlStmt<keyword!("return")>>::detect(parser)
+            | BreakStmt::detect(parser)
+            | ContinueStmt::detect(parser)
+            | AssumeStmt::detect(parser)
+            | ExprStmt::detect(parser)
+    }
+}
+impl ParseDesc for Stmt {
+    fn desc() -> &'static str {
+        "a statement"
+    }
+}
+
+/// Parses the modifiers and the core of an item whose attributes were already parsed.
+fn parse_item_rest(parser: &mut impl Tokenizer, item: &mut Item) -> ParseExit {
+    let mut modifiers = Repeated::<ItemModifier>::parse_error();
+    let exit = Repeated::parse(parser, &mut modifiers);
+    item.modifiers = modifiers.into();
+
+    match exit {
+        ParseExit::Complete => Try::parse(parser, &mut item.core),
+        ParseExit::Cut => ParseExit::Cut,
+    }
+}
+
+/// Parses a statement of one kind, returning `None` if it isn't one.
+fn option_parse_variant<T: OptionParse>(
+    parser: &mut impl Tokenizer,
+    output: &mut Option<Stmt>,
+    variant: fn(T) -> Stmt,
+) -> Option<ParseExit> {
+    let mut value = None;
+    let exit = T::option_parse(parser, &mut value);
+
+    *output = Some(variant(value?));
+
+    Some(exit)
+}
+
 #[derive(Debug, OptionParse)]
 #[desc = "a statement"]
 pub struct LetStmt {
@@ -45,6 +137,32 @@ pub struct ExprStmt {
 #[desc = "a statement"]
 pub struct ControlStmt<K: OptionParse> {
     pub keyword: K,
+    pub value: Option<Expr>,
+    pub semi: Try<punct!(";")>,
+}
+
+/// Leaves the innermost loop, or the loop or block with the label.
+#[derive(Debug, OptionParse)]
+#[desc = "a statement"]
+pub struct BreakStmt {
+    pub keyword: keyword!("break"),
+    pub label: Option<Lifetime>,
+    pub value: Option<Expr>,
+    pub semi: Try<punct!(";")>,
+}
+
+#[derive(Debug, OptionParse)]
+#[desc = "a statement"]
+pub struct ContinueStmt {
+    pub keyword: keyword!("continue"),
+    pub label: Option<Lifetime>,
+    pub semi: Try<punct!(";")>,
+}
+
+#[derive(Debug, OptionParse)]
+#[desc = "a statement"]
+pub struct AssumeStmt {
+    pub keyword: keyword!("assume"),
     pub value: Try<Expr>,
     pub semi: Try<punct!(";")>,
 }
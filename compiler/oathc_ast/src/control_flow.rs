@@ -105,3 +105,104 @@ pub struct For {
     #[option_spanned]
     pub block: Try<Block>,
 }
+
+// Quantifiers
+
+/// `all item in iter { }` or `any item in iter { }`,
+/// which is whether the block evaluates to `true` for all or for any of the items.
+#[derive(Debug, Spanned, OptionParse)]
+#[desc = "a quantifier"]
+pub struct Quantifier {
+    pub keyword: QuantifierKeyword,
+    #[highlight(HighlightColor::Cyan)]
+    #[option_spanned]
+    pub item: Try<Box<Param>>,
+    #[option_spanned]
+    pub in_: Try<keyword!("in")>,
+    #[option_spanned]
+    pub iter: Try<Box<BraceExpr>>,
+    #[option_spanned]
+    pub block: Try<Block>,
+}
+
+#[derive(Debug, Clone, Copy, Spanned, OptionParse)]
+#[desc = "`all` or `any`"]
+pub enum QuantifierKeyword {
+    All(keyword!("all")),
+    Any(keyword!("any")),
+}
+
+// Try
+
+/// `try { }`, where `?` propagates errors to the end of the block instead of out of the function.
+#[derive(Debug, Spanned, OptionParse)]
+#[desc = "a try block"]
+pub struct TryBlock {
+    pub keyword: keyword!("try"),
+    #[option_spanned]
+    pub block: Try<Block>,
+}
+
+// Match
+
+#[derive(Debug, Spanned, OptionParse)]
+#[desc = "`match`"]
+pub struct Match {
+    pub keyword: keyword!("match"),
+    #[option_spanned]
+    pub value: Try<Box<BraceExpr>>,
+    #[option_spanned]
+    pub body: Try<MatchBody>,
+}
+
+#[derive(Debug, Spanned, OptionParse)]
+#[desc = "`{ }`"]
+#[framed]
+pub struct MatchBody {
+    pub frame: Frame<delims!("{ }")>,
+    #[parse_as(Trailing<_, punct!(",")>)]
+    pub arms: Vec<MatchArm>,
+}
+
+/// `pattern | pattern if guard => value`.
+#[derive(Debug, Spanned, OptionParse)]
+#[desc = "a match arm"]
+pub struct MatchArm {
+    pub pattern: Pattern,
+    #[option_spanned]
+    #[parse_as(Repeated<MatchAlternative>)]
+    pub alternatives: Vec<MatchAlternative>,
+    #[option_spanned]
+    pub guard: Option<MatchGuard>,
+    #[option_spanned]
+    pub arrow: Try<punct!("=>")>,
+    #[option_spanned]
+    pub value: Try<Box<Expr>>,
+}
+
+#[derive(Debug, Spanned, OptionParse)]
+#[desc = "`|`"]
+pub struct MatchAlternative {
+    pub bar: punct!("|"),
+    #[option_spanned]
+    pub pattern: Try<Pattern>,
+}
+
+#[derive(Debug, Spanned, OptionParse)]
+#[desc = "`if`"]
+pub struct MatchGuard {
+    pub keyword: keyword!("if"),
+    #[option_spanned]
+    pub condition: Try<Box<Expr>>,
+}
+
+impl MatchArm {
+    /// The patterns of the arm, any of which it matches.
+    pub fn patterns(&self) -> impl Iterator<Item = &Pattern> {
+        [&self.pattern].into_iter().chain(
+            self.alternatives
+                .iter()
+                .filter_map(|alternative| alternative.pattern.success_ref()),
+        )
+    }
+}
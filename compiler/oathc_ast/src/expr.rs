@@ -56,6 +56,9 @@ pub enum ExprCore {
     While(While),
     Until(Until),
     For(For),
+    Quantifier(Quantifier),
+    Match(Match),
+    Try(TryBlock),
 }
 
 #[derive(Debug, Spanned, OptionParse)]
@@ -66,6 +69,8 @@ pub enum UnaryExprExt {
     Index(Array),
     Generics(GenericArgs),
     Construct(Construct),
+    /// `value?`, which is the `Ok` payload of the value, and propagates its `Err` payload otherwise.
+    Question(punct!("?")),
 }
 
 // Keyword
@@ -73,11 +78,29 @@ pub enum UnaryExprExt {
 #[derive(Debug, Spanned, OptionParse)]
 #[desc = "an expression"]
 pub enum ExprKeyword {
-    Fn(keyword!("fn")),
+    Fn(Box<FnExpr>),
     Out(keyword!("out")),
     Type(keyword!("type")),
 }
 
+/// A closure like `fn(x i32) i32 { eval x + 1; }`, or a function type like `fn(x i32) i32` when it has no body.
+///
+/// The output of a function type that's itself the output of a function has to be in parens,
+/// since the function's body would be read as a closure body.
+#[derive(Debug, Spanned, OptionParse)]
+#[desc = "`fn`"]
+pub struct FnExpr {
+    pub keyword: keyword!("fn"),
+    #[option_spanned]
+    pub input: Try<FramedParams<delims!("( )")>>,
+    #[option_spanned]
+    pub output: Option<Box<BraceExpr>>,
+    #[not_spanned]
+    pub contract: Contract,
+    #[option_spanned]
+    pub body: Option<Block>,
+}
+
 // Member
 
 #[derive(Debug, Spanned, OptionParse)]
@@ -104,6 +127,15 @@ pub struct Array {
     pub frame: Frame<delims!("[ ]")>,
     #[parse_as(Trailing<Expr, punct!(",")>)]
     pub items: Vec<Expr>,
+    pub len: Option<ArrayLen>,
+}
+
+#[derive(Debug, Spanned, OptionParse)]
+#[desc = "`;`"]
+pub struct ArrayLen {
+    pub semi: punct!(";"),
+    #[option_spanned]
+    pub len: Try<Box<Expr>>,
 }
 
 #[derive(Debug, Spanned, OptionParse)]
@@ -148,37 +180,43 @@ pub type AngleExpr = GenericExpr<AngleUnaryExprPrefix, ExprCore, UnaryExprExt>;
 pub type AngleUnaryExpr = GenericUnaryExpr<AngleUnaryExprPrefix, ExprCore, UnaryExprExt>;
 pub type AngleExprBinOpExt = GenericExprBinOpExt<AngleUnaryExprPrefix, ExprCore, UnaryExprExt>;
 
-#[derive(Debug, Spanned, OptionParse)]
-#[desc = "an expression prefix"]
-pub enum AngleUnaryExprPrefix {
-    Neg(punct!("-")),
-    Not(punct!("!")),
-    Deref(punct!("*")),
-    Ref(Ref),
-    Eq(punct!("==")),
-    NotEq(punct!("!=")),
-    Less(punct!("<")),
-    MoreEq(punct!(">=")),
-    LessEq(punct!("<=")),
-    Question(punct!("?")),
-    Lifetime(Lifetime),
+/// An expression prefix that isn't `>`, `..` or `..=`, so it can't be confused with the end of `< >`.
+#[derive(Debug, Spanned)]
+pub struct AngleUnaryExprPrefix(pub UnOp);
+
+impl OptionParse for AngleUnaryExprPrefix {
+    fn option_parse(parser: &mut impl Tokenizer, output: &mut Option<Self>) -> ParseExit {
+        if Self::detect(parser) != Detection::Detected {
+            return ParseExit::Complete;
+        }
+
+        let mut value = None;
+        let exit = UnOp::option_parse(parser, &mut value);
+
+        *output = value.map(Self);
+
+        exit
+    }
+
+    fn detect(parser: &impl Tokenizer) -> Detection {
+        let excluded = <punct!(">")>::detect(parser) | <punct!("..")>::detect(parser) | <punct!("..=")>::detect(parser);
+
+        if excluded == Detection::Detected {
+            Detection::NotDetected
+        } else {
+            UnOp::detect(parser)
+        }
+    }
+}
+impl ParseDesc for AngleUnaryExprPrefix {
+    fn desc() -> &'static str {
+        "an expression prefix"
+    }
 }
 
 impl From<AngleUnaryExprPrefix> for UnOp {
     fn from(value: AngleUnaryExprPrefix) -> Self {
-        match value {
-            AngleUnaryExprPrefix::Neg(value) => Self::Neg(value.into()),
-            AngleUnaryExprPrefix::Not(value) => Self::Not(value.into()),
-            AngleUnaryExprPrefix::Deref(value) => Self::Deref(value.into()),
-            AngleUnaryExprPrefix::Ref(value) => Self::Ref(value.into()),
-            AngleUnaryExprPrefix::Eq(value) => Self::Eq(value.into()),
-            AngleUnaryExprPrefix::NotEq(value) => Self::NotEq(value.into()),
-            AngleUnaryExprPrefix::Less(value) => Self::Less(value.into()),
-            AngleUnaryExprPrefix::MoreEq(value) => Self::MoreEq(value.into()),
-            AngleUnaryExprPrefix::LessEq(value) => Self::LessEq(value.into()),
-            AngleUnaryExprPrefix::Question(value) => Self::Question(value.into()),
-            AngleUnaryExprPrefix::Lifetime(value) => Self::Lifetime(value.into()),
-        }
+        value.0
     }
 }
 
@@ -213,56 +251,115 @@ impl From<AngleExprBinOpExt> for ExprBinOpExt {
 
 pub type BraceExpr = GenericExpr<UnOp, BraceExprCore, BraceUnaryExprExt>;
 pub type BraceUnaryExpr = GenericUnaryExpr<UnOp, BraceExprCore, BraceUnaryExprExt>;
-pub type BraceExprBinOpExt = GenericExprBinOpExt<AngleUnaryExprPrefix, ExprCore, UnaryExprExt>;
+pub type BraceExprBinOpExt = GenericExprBinOpExt<UnOp, BraceExprCore, BraceUnaryExprExt>;
 
-#[derive(Debug, Spanned, OptionParse)]
-#[desc = "an expression"]
-pub enum BraceExprCore {
-    Ident(Ident),
-    Keyword(ExprKeyword),
-    Literal(Literal),
-    Array(Array),
-    Tuple(Tuple),
-    If(If),
-    Loop(Loop),
-    While(While),
-    Until(Until),
-    For(For),
+/// An expression core that isn't a block, so it can't be confused with the `{ }` that follows it.
+#[derive(Debug, Spanned)]
+pub struct BraceExprCore(pub ExprCore);
+
+impl OptionParse for BraceExprCore {
+    fn option_parse(parser: &mut impl Tokenizer, output: &mut Option<Self>) -> ParseExit {
+        if Self::detect(parser) != Detection::Detected {
+            return ParseExit::Complete;
+        }
+
+        let mut value = None;
+        let exit = ExprCore::option_parse(parser, &mut value);
+
+        *output = value.map(Self);
+
+        exit
+    }
+
+    fn detect(parser: &impl Tokenizer) -> Detection {
+        if Block::detect(parser) == Detection::Detected {
+            Detection::NotDetected
+        } else {
+            ExprCore::detect(parser)
+        }
+    }
+}
+impl ParseDesc for BraceExprCore {
+    fn desc() -> &'static str {
+        "an expression"
+    }
 }
 
-#[derive(Debug, Spanned, OptionParse)]
-#[desc = "an expression postfix"]
-pub enum BraceUnaryExprExt {
-    Member(UnaryExprMemberExt),
-    Call(Tuple),
-    Index(Array),
-    Generics(GenericArgs),
+/// An expression postfix that isn't a construct, so it can't be confused with the `{ }` that follows it.
+#[derive(Debug, Spanned)]
+pub struct BraceUnaryExprExt(pub UnaryExprExt);
+
+impl OptionParse for BraceUnaryExprExt {
+    fn option_parse(parser: &mut impl Tokenizer, output: &mut Option<Self>) -> ParseExit {
+        if Self::detect(parser) != Detection::Detected {
+            return ParseExit::Complete;
+        }
+
+        let mut value = None;
+        let exit = UnaryExprExt::option_parse(parser, &mut value);
+
+        *output = value.map(Self);
+
+        exit
+    }
+
+    fn detect(parser: &impl Tokenizer) -> Detection {
+        if Construct::detect(parser) == Detection::Detected {
+            Detection::NotDetected
+        } else {
+            UnaryExprExt::detect(parser)
+        }
+    }
+}
+impl ParseDesc for BraceUnaryExprExt {
+    fn desc() -> &'static str {
+        "an expression postfix"
+    }
 }
 
 impl From<BraceExprCore> for ExprCore {
     fn from(value: BraceExprCore) -> Self {
-        match value {
-            BraceExprCore::Ident(value) => Self::Ident(value.into()),
-            BraceExprCore::Keyword(value) => Self::Keyword(value.into()),
-            BraceExprCore::Literal(value) => Self::Literal(value.into()),
-            BraceExprCore::Array(value) => Self::Array(value.into()),
-            BraceExprCore::Tuple(value) => Self::Tuple(value.into()),
-            BraceExprCore::If(value) => Self::If(value.into()),
-            BraceExprCore::Loop(value) => Self::Loop(value.into()),
-            BraceExprCore::While(value) => Self::While(value.into()),
-            BraceExprCore::Until(value) => Self::Until(value.into()),
-            BraceExprCore::For(value) => Self::For(value.into()),
-        }
+        value.0
     }
 }
 
 impl From<BraceUnaryExprExt> for UnaryExprExt {
     fn from(value: BraceUnaryExprExt) -> Self {
-        match value {
-            BraceUnaryExprExt::Member(value) => Self::Member(value.into()),
-            BraceUnaryExprExt::Call(value) => Self::Call(value.into()),
-            BraceUnaryExprExt::Index(value) => Self::Index(value.into()),
-            BraceUnaryExprExt::Generics(value) => Self::Generics(value.into()),
-        }
+        value.0
+    }
+}
+
+// As Ref
+
+impl AsRef<UnOp> for UnOp {
+    fn as_ref(&self) -> &UnOp {
+        self
+    }
+}
+impl AsRef<UnOp> for AngleUnaryExprPrefix {
+    fn as_ref(&self) -> &UnOp {
+        &self.0
+    }
+}
+
+impl AsRef<ExprCore> for ExprCore {
+    fn as_ref(&self) -> &ExprCore {
+        self
+    }
+}
+impl AsRef<ExprCore> for BraceExprCore {
+    fn as_ref(&self) -> &ExprCore {
+        &self.0
+    }
+}
+
+impl AsRef<UnaryExprExt> for UnaryExprExt {
+    fn as_ref(&self) -> &UnaryExprExt {
+        self
+    }
+}
+impl AsRef<UnaryExprExt> for BraceUnaryExprExt {
+    fn as_ref(&self) -> &UnaryExprExt {
+        &self.0
     }
 }
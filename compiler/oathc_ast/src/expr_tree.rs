@@ -0,0 +1,325 @@
+use std::collections::HashMap;
+
+use super::*;
+
+/// An expression with its binary operators grouped by precedence.
+///
+/// The parser keeps binary operators as a flat chain, and the restricted expression kinds (`BraceExpr`, `AngleExpr`) wrap the
+/// general nodes, so passes that need the structure of an expression walk this instead.
+#[derive(Debug, Clone, Spanned)]
+pub enum ExprTree<'e> {
+    Unary(UnaryExprRef<'e>),
+    BinOp(Box<ExprTree<'e>>, BinOp, Box<ExprTree<'e>>),
+}
+
+#[derive(Debug, Clone, Spanned)]
+pub struct UnaryExprRef<'e> {
+    #[span]
+    pub span: Span,
+    /// The attributes of the expression, which its first operand has, like `#[invariant(..)]` on a loop.
+    pub attrs: &'e [Attr],
+    /// The label of the loop or block, which is its last prefix if that's a lifetime followed by `:`.
+    pub label: Option<&'e Lifetime>,
+    pub prefixes: Vec<&'e UnOp>,
+    pub core: &'e ExprCore,
+    pub exts: Vec<&'e UnaryExprExt>,
+}
+
+pub trait ToExprTree {
+    /// Returns `None` if any part of the expression failed to parse.
+    fn tree(&self) -> Option<ExprTree<'_>>;
+}
+
+impl<P, C, E> ToExprTree for GenericExpr<P, C, E>
+where
+    P: OptionParse + Into<UnOp> + AsRef<UnOp> + Spanned,
+    C: ParseDesc + Into<ExprCore> + AsRef<ExprCore> + Spanned,
+    E: OptionParse + Into<UnaryExprExt> + AsRef<UnaryExprExt> + Spanned,
+{
+    fn tree(&self) -> Option<ExprTree<'_>> {
+        let mut first = self.first_unary.success_ref()?.as_ref_expr()?;
+        first.attrs = &self.attrs;
+
+        let mut operands = vec![ExprTree::Unary(first)];
+        let mut ops = Vec::<BinOp>::new();
+
+        for ext in &self.bin_op_exts {
+            while ops.last().is_some_and(|last| last.precedence() >= ext.op.precedence()) {
+                reduce(&mut operands, &mut ops);
+            }
+
+            ops.push(ext.op);
+            operands.push(ExprTree::Unary(ext.rhs.success_ref()?.as_ref_expr()?));
+        }
+
+        while !ops.is_empty() {
+            reduce(&mut operands, &mut ops);
+        }
+
+        operands.pop()
+    }
+}
+
+impl<T: ToExprTree> ToExprTree for Box<T> {
+    fn tree(&self) -> Option<ExprTree<'_>> {
+        T::tree(self)
+    }
+}
+
+impl<T: ToExprTree> ToExprTree for Try<T> {
+    fn tree(&self) -> Option<ExprTree<'_>> {
+        self.success_ref().and_then(T::tree)
+    }
+}
+
+impl<P, C, E> GenericUnaryExpr<P, C, E>
+where
+    P: OptionParse + Into<UnOp> + AsRef<UnOp> + Spanned,
+    C: ParseDesc + Into<ExprCore> + AsRef<ExprCore> + Spanned,
+    E: OptionParse + Into<UnaryExprExt> + AsRef<UnaryExprExt> + Spanned,
+{
+    /// Returns `None` if the core of the expression failed to parse.
+    pub fn as_ref_expr(&self) -> Option<UnaryExprRef<'_>> {
+        let mut prefixes = self.prefixes.iter().map(AsRef::as_ref).collect::<Vec<_>>();
+        let label = match prefixes.last() {
+            Some(UnOp::Lifetime(LifetimePrefix {
+                lifetime,
+                colon: Some(_),
+            })) => {
+                prefixes.pop();
+                Some(lifetime)
+            }
+            _ => None,
+        };
+
+        Some(UnaryExprRef {
+            span: self.option_span()?,
+            attrs: &[],
+            label,
+            prefixes,
+            core: self.core.success_ref()?.as_ref(),
+            exts: self.exts.iter().map(AsRef::as_ref).collect(),
+        })
+    }
+}
+
+impl<'e> ExprTree<'e> {
+    pub fn as_unary(&self) -> Option<&UnaryExprRef<'e>> {
+        match self {
+            Self::Unary(unary) => Some(unary),
+            Self::BinOp(..) => None,
+        }
+    }
+}
+
+impl<'e> UnaryExprRef<'e> {
+    /// Returns the identifier if this expression is a plain identifier.
+    pub fn as_ident(&self) -> Option<Ident> {
+        match self.core {
+            ExprCore::Ident(ident) if self.prefixes.is_empty() && self.exts.is_empty() => Some(*ident),
+            _ => None,
+        }
+    }
+}
+
+// Display
+
+/// An expression displayed with some of its identifiers replaced, like generic parameters with their arguments.
+pub struct Substituted<'a, 'e>(pub &'a ExprTree<'e>, pub &'a HashMap<StrId, String>);
+
+type Substitutions<'a> = Option<&'a HashMap<StrId, String>>;
+
+impl InternedDisplay for ExprTree<'_> {
+    fn interned_fmt(&self, f: &mut std::fmt::Formatter, interner: &Interner) -> std::fmt::Result {
+        fmt_tree(self, f, interner, None)
+    }
+}
+
+impl InternedDisplay for UnaryExprRef<'_> {
+    fn interned_fmt(&self, f: &mut std::fmt::Formatter, interner: &Interner) -> std::fmt::Result {
+        fmt_unary(self, f, interner, None)
+    }
+}
+
+impl InternedDisplay for Substituted<'_, '_> {
+    fn interned_fmt(&self, f: &mut std::fmt::Formatter, interner: &Interner) -> std::fmt::Result {
+        fmt_tree(self.0, f, interner, Some(self.1))
+    }
+}
+
+fn fmt_tree(tree: &ExprTree, f: &mut std::fmt::Formatter, interner: &Interner, subs: Substitutions) -> std::fmt::Result {
+    match tree {
+        ExprTree::Unary(unary) => fmt_unary(unary, f, interner, subs),
+        ExprTree::BinOp(lhs, op, rhs) => {
+            fmt_operand(lhs, op.precedence(), f, interner, subs)?;
+            match op {
+                BinOp::Bound(_) => write!(f, ": ")?,
+                _ => write!(f, " {} ", Interned(op, interner))?,
+            }
+            fmt_operand(rhs, op.precedence() + 1, f, interner, subs)
+        }
+    }
+}
+
+fn fmt_unary(unary: &UnaryExprRef, f: &mut std::fmt::Formatter, interner: &Interner, subs: Substitutions) -> std::fmt::Result {
+    for prefix in &unary.prefixes {
+        match prefix {
+            UnOp::Not(_) => write!(f, "!")?,
+            UnOp::Neg(_) => write!(f, "-")?,
+            UnOp::Ref(ref_) => match &ref_.bounds {
+                None => write!(f, "&")?,
+                Some(RefModifier::Mut(_)) => write!(f, "&mut ")?,
+                Some(RefModifier::Sole(_)) => write!(f, "&sole ")?,
+                Some(RefModifier::SoleMut(_)) => write!(f, "&smut ")?,
+                Some(RefModifier::Lifetime(lifetime)) => {
+                    write!(f, "&")?;
+                    fmt_lifetime(lifetime, f, interner)?;
+                    write!(f, " ")?;
+                }
+            },
+            UnOp::Deref(_) => write!(f, "*")?,
+            UnOp::Lifetime(prefix) => {
+                fmt_lifetime(&prefix.lifetime, f, interner)?;
+                write!(f, "{} ", if prefix.colon.is_some() { ":" } else { "" })?;
+            }
+            UnOp::Eq(_) => write!(f, "== ")?,
+            UnOp::NotEq(_) => write!(f, "!= ")?,
+            UnOp::More(_) => write!(f, "> ")?,
+            UnOp::Less(_) => write!(f, "< ")?,
+            UnOp::MoreEq(_) => write!(f, ">= ")?,
+            UnOp::LessEq(_) => write!(f, "<= ")?,
+            UnOp::RangeExclusive(_) => write!(f, "..")?,
+            UnOp::RangeInclusive(_) => write!(f, "..=")?,
+        }
+    }
+
+    if let Some(label) = unary.label {
+        fmt_lifetime(label, f, interner)?;
+        write!(f, ": ")?;
+    }
+
+    match unary.core {
+        ExprCore::Ident(ident) => match subs.and_then(|subs| subs.get(&ident.str_id())) {
+            Some(replacement) => write!(f, "{replacement}")?,
+            None => ident.interned_fmt(f, interner)?,
+        },
+        ExprCore::Keyword(ExprKeyword::Fn(fn_)) => {
+            write!(f, "fn(..)")?;
+            if let Some(output) = &fn_.output {
+                write!(f, " ")?;
+                fmt_expr(&**output, f, interner, subs)?;
+            }
+            if fn_.body.is_some() {
+                write!(f, " {{ .. }}")?;
+            }
+        }
+        ExprCore::Keyword(ExprKeyword::Out(_)) => write!(f, "out")?,
+        ExprCore::Keyword(ExprKeyword::Type(_)) => write!(f, "type")?,
+        ExprCore::Literal(literal) => literal.interned_fmt(f, interner)?,
+        ExprCore::Tuple(tuple) => {
+            write!(f, "(")?;
+            fmt_exprs(&tuple.items, f, interner, subs)?;
+            write!(f, ")")?;
+        }
+        ExprCore::Array(array) => {
+            write!(f, "[")?;
+            fmt_exprs(&array.items, f, interner, subs)?;
+            if let Some(len) = &array.len {
+                write!(f, "; ")?;
+                fmt_expr(&len.len, f, interner, subs)?;
+            }
+            write!(f, "]")?;
+        }
+        ExprCore::Block(_) => write!(f, "{{ .. }}")?,
+        ExprCore::If(_) => write!(f, "if ..")?,
+        ExprCore::Loop(_) => write!(f, "loop ..")?,
+        ExprCore::While(_) => write!(f, "while ..")?,
+        ExprCore::Until(_) => write!(f, "until ..")?,
+        ExprCore::For(_) => write!(f, "for ..")?,
+        ExprCore::Quantifier(quantifier) => match quantifier.keyword {
+            QuantifierKeyword::All(_) => write!(f, "all ..")?,
+            QuantifierKeyword::Any(_) => write!(f, "any ..")?,
+        },
+        ExprCore::Match(_) => write!(f, "match ..")?,
+        ExprCore::Try(_) => write!(f, "try ..")?,
+    }
+
+    for ext in &unary.exts {
+        match ext {
+            UnaryExprExt::Member(member) => match &member.member {
+                Try::Success(Member::Named(ident)) => write!(f, ".{}", Interned(ident, interner))?,
+                Try::Success(Member::Unnamed(index)) => write!(f, ".{}", Interned(index, interner))?,
+                Try::Failure(_) => write!(f, ".?")?,
+            },
+            UnaryExprExt::Call(tuple) => {
+                write!(f, "(")?;
+                fmt_exprs(&tuple.items, f, interner, subs)?;
+                write!(f, ")")?;
+            }
+            UnaryExprExt::Index(array) => {
+                write!(f, "[")?;
+                fmt_exprs(&array.items, f, interner, subs)?;
+                write!(f, "]")?;
+            }
+            UnaryExprExt::Generics(generics) => {
+                write!(f, "<")?;
+                fmt_exprs(&generics.args, f, interner, subs)?;
+                write!(f, ">")?;
+            }
+            UnaryExprExt::Construct(_) => write!(f, " {{ .. }}")?,
+            UnaryExprExt::Question(_) => write!(f, "?")?,
+        }
+    }
+
+    Ok(())
+}
+
+fn fmt_operand(
+    tree: &ExprTree,
+    min_precedence: u8,
+    f: &mut std::fmt::Formatter,
+    interner: &Interner,
+    subs: Substitutions,
+) -> std::fmt::Result {
+    match tree {
+        ExprTree::BinOp(_, op, _) if op.precedence() < min_precedence => {
+            write!(f, "(")?;
+            fmt_tree(tree, f, interner, subs)?;
+            write!(f, ")")
+        }
+        _ => fmt_tree(tree, f, interner, subs),
+    }
+}
+
+fn fmt_expr(expr: &impl ToExprTree, f: &mut std::fmt::Formatter, interner: &Interner, subs: Substitutions) -> std::fmt::Result {
+    match expr.tree() {
+        Some(tree) => fmt_tree(&tree, f, interner, subs),
+        None => write!(f, "?"),
+    }
+}
+
+fn fmt_exprs(exprs: &[Expr], f: &mut std::fmt::Formatter, interner: &Interner, subs: Substitutions) -> std::fmt::Result {
+    for (index, expr) in exprs.iter().enumerate() {
+        if index > 0 {
+            write!(f, ", ")?;
+        }
+        fmt_expr(expr, f, interner, subs)?;
+    }
+
+    Ok(())
+}
+
+fn fmt_lifetime(lifetime: &Lifetime, f: &mut std::fmt::Formatter, interner: &Interner) -> std::fmt::Result {
+    match &lifetime.ident {
+        Try::Success(ident) => write!(f, "'{}", Interned(ident, interner)),
+        Try::Failure(_) => write!(f, "'?"),
+    }
+}
+
+fn reduce(operands: &mut Vec<ExprTree>, ops: &mut Vec<BinOp>) {
+    let op = ops.pop().unwrap();
+    let rhs = operands.pop().unwrap();
+    let lhs = operands.pop().unwrap();
+
+    operands.push(ExprTree::BinOp(Box::new(lhs), op, Box::new(rhs)));
+}
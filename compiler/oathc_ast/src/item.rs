@@ -19,6 +19,7 @@ pub enum ItemModifier {
     Comptime(keyword!("comptime")),
     Raw(keyword!("raw")),
     Con(keyword!("con")),
+    Untagged(keyword!("untagged")),
 }
 
 #[derive(Debug, OptionParse)]
@@ -33,6 +34,40 @@ pub enum ItemCore {
     Sys(Sys),
     Static(Static),
     Trait(Trait),
+    Impl(Impl),
+}
+
+impl Item {
+    pub fn is_pub(&self) -> bool {
+        self.modifiers.iter().any(|modifier| matches!(modifier, ItemModifier::Pub(_)))
+    }
+
+    pub fn is_comptime(&self) -> bool {
+        self.modifiers
+            .iter()
+            .any(|modifier| matches!(modifier, ItemModifier::Comptime(_)))
+    }
+
+    pub fn is_untagged(&self) -> bool {
+        self.modifiers
+            .iter()
+            .any(|modifier| matches!(modifier, ItemModifier::Untagged(_)))
+    }
+}
+
+impl ItemCore {
+    pub fn ident(&self) -> Option<Ident> {
+        match self {
+            Self::Attr(_) | Self::Use(_) | Self::Impl(_) => None,
+            Self::Mod(core) => core.ident.success_ref().copied(),
+            Self::Fn(core) => core.ident.success_ref().copied(),
+            Self::Struct(core) => core.ident.success_ref().copied(),
+            Self::Enum(core) => core.ident.success_ref().copied(),
+            Self::Sys(core) => core.ident.success_ref().copied(),
+            Self::Static(core) => core.param.success_ref()?.body.success_ref()?.as_ident(),
+            Self::Trait(core) => core.ident.success_ref().copied(),
+        }
+    }
 }
 
 // Mod
@@ -172,3 +207,54 @@ pub struct TraitTarget {
     pub frame: Frame<delims!("( )")>,
     pub target: Try<Expr>,
 }
+
+// Impl
+
+/// Either `impl Type { }` for inherent impls, or `impl Trait for Type { }` for trait impls.
+#[derive(Debug, OptionParse)]
+#[desc = "an impl"]
+pub struct Impl {
+    pub keyword: keyword!("impl"),
+    pub generics: Option<FramedParams<Angles>>,
+    /// The trait of trait impls, and the target type of inherent impls.
+    pub first: Try<BraceExpr>,
+    pub for_: Option<ImplFor>,
+    pub contract: Contract,
+    pub body: Try<ModBlock>,
+}
+
+#[derive(Debug, OptionParse)]
+#[desc = "`for`"]
+pub struct ImplFor {
+    pub keyword: keyword!("for"),
+    pub target: Try<BraceExpr>,
+}
+
+impl Impl {
+    pub fn trait_(&self) -> Option<&Try<BraceExpr>> {
+        self.for_.as_ref().map(|_| &self.first)
+    }
+
+    pub fn target(&self) -> &Try<BraceExpr> {
+        match &self.for_ {
+            Some(for_) => &for_.target,
+            None => &self.first,
+        }
+    }
+
+    pub fn items(&self) -> &[Item] {
+        match &self.body {
+            Try::Success(body) => &body.items,
+            Try::Failure(_) => &[],
+        }
+    }
+}
+
+impl Trait {
+    pub fn items(&self) -> &[Item] {
+        match &self.body {
+            Try::Success(ModBody::Block(body)) => &body.items,
+            Try::Success(ModBody::Semi(_)) | Try::Failure(_) => &[],
+        }
+    }
+}
@@ -13,20 +13,24 @@ mod block;
 mod contract;
 mod control_flow;
 mod expr;
+mod expr_tree;
 mod generics;
 mod item;
 mod ops;
 mod param;
+mod pattern;
 mod type_;
 pub use attr::*;
 pub use block::*;
 pub use contract::*;
 pub use control_flow::*;
 pub use expr::*;
+pub use expr_tree::*;
 pub use generics::*;
 pub use item::*;
 pub use ops::*;
 pub use param::*;
+pub use pattern::*;
 pub use type_::*;
 
 #[derive(Debug, Default)]
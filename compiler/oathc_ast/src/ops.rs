@@ -8,7 +8,7 @@ pub enum UnOp {
 
     Ref(Ref),
     Deref(punct!("*")),
-    Lifetime(Lifetime),
+    Lifetime(LifetimePrefix),
 
     Eq(punct!("==")),
     NotEq(punct!("!=")),
@@ -17,7 +17,6 @@ pub enum UnOp {
     MoreEq(punct!(">=")),
     LessEq(punct!("<=")),
 
-    Question(punct!("?")),
     RangeExclusive(punct!("..")),
     RangeInclusive(punct!("..=")),
 }
@@ -30,6 +29,9 @@ pub enum BinOp {
     Mul(punct!("*")),
     Div(punct!("/")),
     Rem(punct!("%")),
+    WrappingAdd(punct!("+%")),
+    WrappingSub(punct!("-%")),
+    WrappingMul(punct!("*%")),
 
     And(punct!("&")),
     Or(punct!("|")),
@@ -40,6 +42,25 @@ pub enum BinOp {
     Bound(punct!(":")),
     RangeExclusive(punct!("..")),
     RangeInclusive(punct!("..=")),
+
+    Is(keyword!("is")),
+}
+
+impl BinOp {
+    /// Higher binds tighter. All binary operators are left associative.
+    pub fn precedence(self) -> u8 {
+        match self {
+            Self::Bound(_) => 0,
+            Self::RangeExclusive(_) | Self::RangeInclusive(_) => 1,
+            Self::Or(_) => 2,
+            Self::Xor(_) => 3,
+            Self::And(_) => 4,
+            Self::Shl(_) | Self::Shr(_) => 5,
+            Self::Add(_) | Self::Sub(_) | Self::WrappingAdd(_) | Self::WrappingSub(_) => 6,
+            Self::Mul(_) | Self::Div(_) | Self::Rem(_) | Self::WrappingMul(_) => 7,
+            Self::Is(_) => 8,
+        }
+    }
 }
 
 // Ref
@@ -68,3 +89,18 @@ pub struct Lifetime {
     #[option_spanned]
     pub ident: Try<Ident>,
 }
+
+impl Lifetime {
+    pub fn str_id(&self) -> Option<StrId> {
+        self.ident.success_ref().map(Ident::str_id)
+    }
+}
+
+/// A lifetime before an expression. With a `:` it's the label of the loop or block after it.
+#[derive(Debug, Spanned, OptionParse)]
+#[desc = "`'`"]
+pub struct LifetimePrefix {
+    pub lifetime: Lifetime,
+    #[option_spanned]
+    pub colon: Option<punct!(":")>,
+}
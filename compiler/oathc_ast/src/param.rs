@@ -27,11 +27,43 @@ pub struct Param {
     pub bounds: Option<Bounds>,
 }
 
+/// What a parameter binds, which is a pattern or a tuple of parameters that each have their own type and bounds.
+///
+/// Generic parameters can also be lifetimes, like `'a`, which only the borrow checker looks at.
 #[derive(Debug, Spanned, OptionParse, Highlightable)]
 #[desc = "a parameter"]
 pub enum ParamBody {
-    Ident(#[highlightable] Ident),
     Tuple(#[highlightable] FramedParams<delims!("( )")>),
+    Pattern(#[highlightable] Pattern),
+    Lifetime(LifetimeParam),
+}
+
+/// A lifetime parameter with the lifetimes it outlives, like `'b: 'a + 'c`.
+#[derive(Debug, Spanned, OptionParse)]
+#[desc = "`'`"]
+pub struct LifetimeParam {
+    pub lifetime: Lifetime,
+    #[option_spanned]
+    pub bounds: Option<LifetimeBounds>,
+}
+
+#[derive(Debug, Spanned, OptionParse)]
+#[desc = "`: ...`"]
+pub struct LifetimeBounds {
+    pub colon: punct!(":"),
+    #[option_spanned]
+    pub first: Try<Lifetime>,
+    #[option_spanned]
+    #[parse_as(Repeated<LifetimeBound>)]
+    pub rest: Vec<LifetimeBound>,
+}
+
+#[derive(Debug, Spanned, OptionParse)]
+#[desc = "`+`"]
+pub struct LifetimeBound {
+    pub plus: punct!("+"),
+    #[option_spanned]
+    pub lifetime: Try<Lifetime>,
 }
 
 #[derive(Debug, Spanned, OptionParse)]
@@ -41,3 +73,30 @@ pub struct Bounds {
     #[option_spanned]
     pub expr: Try<Expr>,
 }
+
+impl ParamBody {
+    /// The identifier of a parameter that's just an identifier, like `x` or `_`.
+    pub fn as_ident(&self) -> Option<Ident> {
+        match self {
+            Self::Pattern(pattern) => pattern.as_ident(),
+            Self::Tuple(_) | Self::Lifetime(_) => None,
+        }
+    }
+}
+
+impl LifetimeBounds {
+    /// The lifetimes the parameter outlives.
+    pub fn lifetimes(&self) -> impl Iterator<Item = &Lifetime> {
+        [&self.first]
+            .into_iter()
+            .chain(self.rest.iter().map(|bound| &bound.lifetime))
+            .filter_map(Try::success_ref)
+    }
+}
+
+impl Param {
+    /// Whether the parameter is a lifetime, which isn't given a value when the item is used.
+    pub fn is_lifetime(&self) -> bool {
+        matches!(self.body, Try::Success(ParamBody::Lifetime(_)))
+    }
+}
@@ -0,0 +1,234 @@
+use super::*;
+
+/// A pattern that a value is matched against, like the arms of a `match` or the name of a `let`.
+#[derive(Debug, Spanned, OptionParse, Highlightable)]
+#[desc = "a pattern"]
+pub enum Pattern {
+    Path(#[highlightable] PathPattern),
+    Literal(Box<LiteralPattern>),
+    RangeTo(Box<RangeToPattern>),
+    Tuple(#[highlightable] TuplePattern),
+    Array(#[highlightable] ArrayPattern),
+}
+
+// Path
+
+/// A binding like `x`, a wildcard `_`, a struct like `Point { x, y = 0 }`, or an enum variant like `Shape.Circle(r)`.
+#[derive(Debug, Spanned, OptionParse)]
+#[desc = "a pattern"]
+pub struct PathPattern {
+    pub ident: Ident,
+    #[option_spanned]
+    pub tail: Option<PathPatternTail>,
+}
+
+#[derive(Debug, Spanned, OptionParse, Highlightable)]
+#[desc = "either `.` or `{ }`"]
+pub enum PathPatternTail {
+    Variant(#[highlightable] VariantPattern),
+    Fields(#[highlightable] FieldsPattern),
+}
+
+/// `.Variant` or `.Variant(payload)`.
+#[derive(Debug, Spanned, OptionParse, Highlightable)]
+#[desc = "`.`"]
+pub struct VariantPattern {
+    pub dot: punct!("."),
+    #[highlight(HighlightColor::Blue)]
+    #[option_spanned]
+    pub ident: Try<Ident>,
+    #[highlightable]
+    #[option_spanned]
+    pub payload: Option<TuplePattern>,
+}
+
+#[derive(Debug, Spanned, OptionParse, Highlightable)]
+#[desc = "`{ }`"]
+#[framed]
+pub struct FieldsPattern {
+    pub frame: Frame<delims!("{ }")>,
+    #[highlightable]
+    #[parse_as(Trailing<_, punct!(",")>)]
+    pub fields: Vec<FieldPattern>,
+}
+
+#[derive(Debug, Spanned, OptionParse, Highlightable)]
+#[desc = "a field pattern"]
+pub enum FieldPattern {
+    Field(#[highlightable] NamedFieldPattern),
+    Rest(punct!("..")),
+}
+
+#[derive(Debug, Spanned, OptionParse)]
+#[desc = "a field pattern"]
+pub struct NamedFieldPattern {
+    #[highlight(HighlightColor::Cyan)]
+    pub ident: Ident,
+    #[option_spanned]
+    pub value: Option<PatternAssign>,
+}
+
+#[derive(Debug, Spanned, OptionParse, Highlightable)]
+#[desc = "`=`"]
+pub struct PatternAssign {
+    pub eq: punct!("="),
+    #[highlightable]
+    #[option_spanned]
+    pub pattern: Try<Box<Pattern>>,
+}
+
+// Literal
+
+/// A literal like `5`, or a range like `0..10`, `-5..=5` or `3..`.
+#[derive(Debug, Spanned, OptionParse)]
+#[desc = "a literal pattern"]
+pub struct LiteralPattern {
+    pub start: PatternLiteral,
+    #[option_spanned]
+    pub end: Option<RangePatternEnd>,
+}
+
+#[derive(Debug, Spanned, OptionParse)]
+#[desc = "a literal"]
+pub enum PatternLiteral {
+    Literal(Literal),
+    Neg(NegPatternLiteral),
+}
+
+#[derive(Debug, Spanned, OptionParse)]
+#[desc = "`-`"]
+pub struct NegPatternLiteral {
+    pub minus: punct!("-"),
+    #[option_spanned]
+    pub literal: Try<Literal>,
+}
+
+#[derive(Debug, Spanned, OptionParse)]
+#[desc = "`..` or `..=`"]
+pub enum RangePatternOp {
+    Exclusive(punct!("..")),
+    Inclusive(punct!("..=")),
+}
+
+#[derive(Debug, Spanned, OptionParse)]
+#[desc = "`..` or `..=`"]
+pub struct RangePatternEnd {
+    pub op: RangePatternOp,
+    #[option_spanned]
+    pub end: Option<PatternLiteral>,
+}
+
+/// A range without a start, like `..10` or `..=9`.
+#[derive(Debug, Spanned, OptionParse)]
+#[desc = "a range pattern"]
+pub struct RangeToPattern {
+    pub op: RangePatternOp,
+    #[option_spanned]
+    pub end: Try<PatternLiteral>,
+}
+
+// Tuple
+
+#[derive(Debug, Spanned, OptionParse, Highlightable)]
+#[desc = "a tuple pattern"]
+#[framed]
+pub struct TuplePattern {
+    pub frame: Frame<delims!("( )")>,
+    #[highlightable]
+    #[parse_as(Trailing<_, punct!(",")>)]
+    pub items: Vec<Pattern>,
+}
+
+#[derive(Debug, Spanned, OptionParse, Highlightable)]
+#[desc = "an array pattern"]
+#[framed]
+pub struct ArrayPattern {
+    pub frame: Frame<delims!("[ ]")>,
+    #[highlightable]
+    #[parse_as(Trailing<_, punct!(",")>)]
+    pub items: Vec<Pattern>,
+}
+
+/// The names a pattern binds get the color of the pattern, and the rest of it keeps its own colors.
+impl Highlightable for PathPattern {
+    fn highlight(&self, color: HighlightColor, h: &mut Vec<Highlight>) {
+        match &self.tail {
+            Some(tail) => tail.highlight(color, h),
+            None => self.ident.highlight(color, h),
+        }
+    }
+}
+
+/// Shorthand fields bind the field, so they're highlighted as bindings.
+impl Highlightable for NamedFieldPattern {
+    fn highlight(&self, color: HighlightColor, h: &mut Vec<Highlight>) {
+        match &self.value {
+            Some(assign) => assign.highlight(color, h),
+            None => self.ident.highlight(color, h),
+        }
+    }
+}
+
+impl Pattern {
+    /// The identifier of a pattern that's just an identifier, like `x` or `_`.
+    pub fn as_ident(&self) -> Option<Ident> {
+        match self {
+            Self::Path(PathPattern { ident, tail: None }) => Some(*ident),
+            _ => None,
+        }
+    }
+
+    /// Whether the pattern can fail to match a value of the right type, without looking at what the names in it refer to.
+    ///
+    /// Enum variants count as refutable, even for enums with a single variant.
+    pub fn is_refutable(&self, interner: &Interner) -> bool {
+        match self {
+            Self::Path(path) => match &path.tail {
+                None => path.bool_literal(interner).is_some(),
+                Some(PathPatternTail::Variant(_)) => true,
+                Some(PathPatternTail::Fields(fields)) => fields.fields.iter().any(|field| match field {
+                    FieldPattern::Field(NamedFieldPattern {
+                        value:
+                            Some(PatternAssign {
+                                pattern: Try::Success(pattern),
+                                ..
+                            }),
+                        ..
+                    }) => pattern.is_refutable(interner),
+                    _ => false,
+                }),
+            },
+            Self::Literal(_) | Self::RangeTo(_) => true,
+            Self::Tuple(tuple) => tuple.items.iter().any(|item| item.is_refutable(interner)),
+            Self::Array(array) => array.items.iter().any(|item| item.is_refutable(interner)),
+        }
+    }
+}
+
+impl PathPattern {
+    /// Whether this is the `_` wildcard.
+    pub fn is_wildcard(&self, interner: &Interner) -> bool {
+        self.tail.is_none() && interner.unintern(self.ident.str_id()) == "_"
+    }
+
+    /// `true` or `false`, which are matched as literals rather than bound.
+    pub fn bool_literal(&self, interner: &Interner) -> Option<bool> {
+        if self.tail.is_some() {
+            return None;
+        }
+
+        match interner.unintern(self.ident.str_id()).as_str() {
+            "true" => Some(true),
+            "false" => Some(false),
+            _ => None,
+        }
+    }
+
+    /// The name this pattern binds, if it's a plain identifier.
+    pub fn binding(&self, interner: &Interner) -> Option<Ident> {
+        match self.tail.is_none() && !self.is_wildcard(interner) && self.bool_literal(interner).is_none() {
+            true => Some(self.ident),
+            false => None,
+        }
+    }
+}
@@ -0,0 +1,583 @@
+use super::*;
+
+/// Checks that references don't conflict with each other or outlive what they borrow, and that moved values aren't used.
+///
+/// Function bodies are checked in order, tracking which locals are moved and which loans each local holds on the paths that
+/// lead to each point. A loan is live while the local that holds it is used later, so a reference ends at its last use rather
+/// than at the end of its scope.
+///
+/// While a reference is live, its place can be used in some ways but not others:
+/// - `&` can share its place with other `&`s, and its place can still be read but not assigned.
+/// - `&mut` can share its place with other `&mut`s, and its place can still be read and assigned.
+/// - `&sole` is the only reference to its place, and its place can't be assigned.
+/// - `&smut` is the only reference to its place, and its place can't be used at all except through it.
+///
+/// The references a function's parameters hold come from outside of it, and live for the lifetimes in its signature.
+/// The function can only return the ones that live for the output's lifetimes.
+///
+/// A closure is checked like a function where it's written, and holds the locals it captures:
+/// it moves the ones it moves, borrows the ones it changes as `&smut`, and borrows the rest as `&`.
+#[derive(Debug)]
+pub struct BorrowChecker<'ctx> {
+    pub(crate) namespace: &'ctx Namespace<'ctx>,
+    pub(crate) interner: &'ctx Interner,
+    pub(crate) diagnostics: &'ctx Diagnostics,
+    pub(crate) locals: Vec<LocalInfo>,
+    pub(crate) scopes: Vec<HashMap<StrId, LocalId>>,
+    pub(crate) loans: Vec<Loan>,
+    pub(crate) state: State,
+    /// The lifetimes of the function being checked.
+    pub(crate) signature: Signature,
+    /// Where each name is used in the function being checked, to tell whether a local is used after some point.
+    pub(crate) uses: HashMap<StrId, Vec<Span>>,
+    /// The loops around the code being checked, all of which can run again after it.
+    pub(crate) loops: Vec<Span>,
+    /// The other branches of the `if`s and `match`es around the code being checked, which don't run after it.
+    pub(crate) excluded: Vec<Span>,
+    /// Where the loops and labeled blocks around the code being checked are left through `break` and `continue`.
+    pub(crate) exits: Vec<LoopExits>,
+    /// Where the `try` blocks around the code being checked are left through `?`.
+    pub(crate) tries: Vec<TryExits>,
+    /// The first local of the closure being checked. The locals before it belong to the functions around the closure.
+    pub(crate) first_local: LocalId,
+    /// How the closure being checked uses each local it captures, and where.
+    pub(crate) captures: BTreeMap<LocalId, (Capture, Span)>,
+    /// `false` while checking the first iteration of a loop, which is checked again starting from what it leaves behind.
+    pub(crate) reporting: bool,
+    pub(crate) errors: Vec<DiagnosticHandle>,
+}
+
+pub(crate) type LocalId = usize;
+pub(crate) type LoanId = usize;
+
+#[derive(Debug, Clone)]
+pub(crate) struct LocalInfo {
+    pub name: StrId,
+    pub mutable: bool,
+    pub class: Class,
+}
+
+/// A reference to a place, made by a `&` expression.
+///
+/// The references a parameter holds are loans with the lifetime they have in the signature, whose place is the parameter.
+/// They borrow from outside of the function, so they don't conflict with anything in it.
+#[derive(Debug, Clone)]
+pub(crate) struct Loan {
+    pub place: Place,
+    pub kind: RefKind,
+    pub span: Span,
+    pub region: Option<Region>,
+}
+
+/// A local or a part of it, like `a`, `a.b` or `a.b[i]`.
+#[derive(Debug, Clone)]
+pub(crate) struct Place {
+    pub local: LocalId,
+    pub projections: Vec<Projection>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Projection {
+    Field(StrId),
+    Unnamed(u128),
+    /// Any element of an array, since indices aren't tracked.
+    Index,
+}
+
+/// The loans held by the value of an expression, and how the value is passed around.
+#[derive(Debug, Clone)]
+pub(crate) struct Value {
+    pub loans: BTreeSet<LoanId>,
+    pub class: Class,
+}
+
+/// What's known at some point of a function, on any of the paths that lead there.
+///
+/// The default state is unreachable, which is what no paths lead to.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct State {
+    /// Where each moved local was moved.
+    pub moved: BTreeMap<LocalId, Vec<Span>>,
+    /// The loans each local holds.
+    pub holds: BTreeMap<LocalId, BTreeSet<LoanId>>,
+    /// The loans used by the statement being checked, which are live until it ends even if no local holds them.
+    pub temps: BTreeSet<LoanId>,
+    /// `false` after `return`, `break` and `continue`.
+    pub reachable: bool,
+}
+
+/// The states at the `break`s and `continue`s of a loop or a labeled block, and the values of its `break`s.
+#[derive(Debug, Default)]
+pub(crate) struct LoopExits {
+    pub label: Option<StrId>,
+    /// Unlabeled `break`s and `continue`s are for the innermost loop, which labeled blocks aren't.
+    pub is_loop: bool,
+    pub breaks: Vec<State>,
+    pub continues: Vec<State>,
+    pub value: Option<Value>,
+    /// The number of scopes around the loop, whose locals are the ones that are still there after leaving it.
+    pub depth: usize,
+}
+
+/// The states at the `?`s of a `try` block, and the loans of the errors they propagate.
+#[derive(Debug, Default)]
+pub(crate) struct TryExits {
+    pub states: Vec<State>,
+    pub loans: BTreeSet<LoanId>,
+    /// The number of scopes around the block, whose locals are the ones that are still there after leaving it.
+    pub depth: usize,
+}
+
+impl<'ctx> BorrowChecker<'ctx> {
+    pub fn new(namespace: &'ctx Namespace<'ctx>, interner: &'ctx Interner, diagnostics: &'ctx Diagnostics) -> Self {
+        Self {
+            namespace,
+            interner,
+            diagnostics,
+            locals: Vec::new(),
+            scopes: Vec::new(),
+            loans: Vec::new(),
+            state: State::new(),
+            signature: Signature::default(),
+            uses: HashMap::new(),
+            loops: Vec::new(),
+            excluded: Vec::new(),
+            exits: Vec::new(),
+            tries: Vec::new(),
+            first_local: 0,
+            captures: BTreeMap::new(),
+            reporting: true,
+            errors: Vec::new(),
+        }
+    }
+
+    /// Checks every function in the namespace, including the functions of impls, and the lifetimes of structs and enums.
+    pub fn check_items(&mut self, errors: &mut Vec<DiagnosticHandle>) {
+        for (_, item) in self.namespace.items() {
+            match &item.core {
+                Try::Success(ItemCore::Fn(fn_)) => self.check_fn(fn_, None),
+                Try::Success(ItemCore::Impl(impl_)) => {
+                    for item in impl_.items() {
+                        if let Try::Success(ItemCore::Fn(fn_)) = &item.core {
+                            self.check_fn(fn_, Some(impl_));
+                        }
+                    }
+                }
+                Try::Success(ItemCore::Struct(struct_)) => {
+                    let fields = struct_.fields.success_ref().map(|fields| fields.items.as_slice());
+                    self.check_type_lifetimes(struct_.generics.as_ref(), fields);
+                }
+                Try::Success(ItemCore::Enum(enum_)) => {
+                    let variants = enum_.variants.success_ref().map(|variants| variants.items.as_slice());
+                    self.check_type_lifetimes(enum_.generics.as_ref(), variants);
+                }
+                _ => {}
+            }
+        }
+
+        errors.append(&mut self.errors);
+    }
+
+    fn check_fn(&mut self, fn_: &'ctx Fn, impl_: Option<&'ctx Impl>) {
+        let (signature, errors) = self.signature(fn_, impl_);
+        for error in errors {
+            self.error(error);
+        }
+        for param in fn_.generics.iter().flat_map(|generics| &generics.items) {
+            self.check_local_lifetimes(param);
+        }
+
+        let (Try::Success(input), Try::Success(FnBody::Block(body))) = (&fn_.input, &fn_.body) else {
+            return;
+        };
+
+        self.locals.clear();
+        self.loans.clear();
+        self.state = State::new();
+        self.scopes = vec![HashMap::new()];
+        self.uses.clear();
+        uses_in_block(body, &mut self.uses);
+        self.loops.clear();
+        self.excluded.clear();
+        self.exits.clear();
+        self.tries.clear();
+        self.reporting = true;
+
+        self.first_local = 0;
+        self.captures.clear();
+
+        self.bind_input(&input.items, &signature);
+        self.signature = signature;
+
+        self.scopes.push(HashMap::new());
+        let (value, span) = self.check_stmts(body);
+        if self.state.reachable {
+            self.check_return(&value, span);
+        }
+        self.end_scope(body, None);
+    }
+
+    /// Binds the parameters of a function or a closure, each holding a loan for each lifetime it has in the signature.
+    pub(crate) fn bind_input(&mut self, params: &'ctx [Param], signature: &Signature) {
+        for (param, regions) in params.iter().zip(&signature.inputs) {
+            let first = self.locals.len();
+            self.bind_param(param, Value::new(self.param_class(param)));
+
+            let Some(span) = param.option_span() else {
+                continue;
+            };
+            if first == self.locals.len() {
+                continue;
+            }
+
+            let kind = match self.locals[first].class {
+                Class::Ref(kind) => kind,
+                _ => RefKind::Shared,
+            };
+            for region in regions {
+                let id = self.loans.len();
+                self.loans.push(Loan {
+                    place: Place::local(first),
+                    kind,
+                    span,
+                    region: Some(*region),
+                });
+
+                for local in first..self.locals.len() {
+                    self.state.holds.entry(local).or_default().insert(id);
+                }
+            }
+        }
+    }
+
+    /// Checks that a returned value doesn't borrow from the function's locals, which are dropped when it returns,
+    /// and that the references it gets from the parameters live for the output's lifetimes.
+    pub(crate) fn check_return(&mut self, value: &Value, span: Span) {
+        let Some(output) = &self.signature.output else {
+            return;
+        };
+
+        let mut errors = Vec::new();
+        for loan in &value.loans {
+            let Loan {
+                place,
+                span: loan_span,
+                region,
+                ..
+            } = &self.loans[*loan];
+
+            match region {
+                Some(region) if output.iter().any(|output| self.outlives(*region, *output)) => {}
+                // What a closure captures outlives it.
+                None if place.local < self.first_local => {}
+                Some(region) => {
+                    let expected = output.iter().map(|region| self.region_name(*region)).collect::<Vec<_>>();
+                    let labels = vec![Label {
+                        span: *loan_span,
+                        message: format!("the reference comes from `{}`", self.place_name(place)),
+                    }];
+                    errors.push(Error::OutlivesViolated(
+                        span,
+                        self.region_name(*region),
+                        expected.join(" or "),
+                        labels,
+                    ));
+                }
+                None => {
+                    let labels = vec![Label {
+                        span: *loan_span,
+                        message: format!("`{}` is borrowed here", self.place_name(place)),
+                    }];
+                    errors.push(Error::ReturnsLocalRef(span, self.locals[place.local].name, labels));
+                }
+            }
+        }
+
+        for error in errors {
+            self.error(error);
+        }
+    }
+
+    /// Binds the names of a parameter in the innermost scope, each holding the loans of the value.
+    pub(crate) fn bind_param(&mut self, param: &'ctx Param, value: Value) {
+        match &param.body {
+            Try::Success(ParamBody::Pattern(pattern)) => match pattern.as_ident() {
+                Some(ident) => self.bind_local(ident, param.mut_.is_some(), value),
+                None => {
+                    let mut names = Vec::new();
+                    pattern_bindings(pattern, self.interner, &mut names);
+
+                    for ident in names {
+                        self.bind_local(ident, param.mut_.is_some(), Value::with_loans(value.loans.clone()));
+                    }
+                }
+            },
+            Try::Success(ParamBody::Tuple(params)) => {
+                for param in &params.items {
+                    let class = match param.type_ {
+                        Some(_) => self.param_class(param),
+                        None => Class::Copy,
+                    };
+                    self.bind_param(
+                        param,
+                        Value {
+                            loans: value.loans.clone(),
+                            class,
+                        },
+                    );
+                }
+            }
+            Try::Success(ParamBody::Lifetime(param)) => self.error(Error::MisplacedLifetime(param.span())),
+            Try::Failure(_) => {}
+        }
+    }
+
+    pub(crate) fn bind_local(&mut self, ident: Ident, mutable: bool, value: Value) {
+        if self.interner.unintern(ident.str_id()) == "_" {
+            return;
+        }
+
+        let id = self.locals.len();
+        self.locals.push(LocalInfo {
+            name: ident.str_id(),
+            mutable,
+            class: value.class,
+        });
+        if !value.loans.is_empty() {
+            self.state.holds.insert(id, value.loans);
+        }
+
+        // A shadowed local can't be used anymore, so the loans it holds end.
+        if let Some(shadowed) = self.scopes.last_mut().unwrap().insert(ident.str_id(), id) {
+            self.state.forget(shadowed);
+        }
+    }
+
+    pub(crate) fn local(&self, name: StrId) -> Option<LocalId> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(&name).copied())
+    }
+
+    /// Drops the locals of the innermost scope. Loans of them that are still live afterwards would dangle.
+    pub(crate) fn end_scope(&mut self, block: &'ctx Block, value: Option<(&Value, Span)>) {
+        let scope = self.scopes.pop().unwrap();
+
+        if self.state.reachable {
+            let end = block.frame.delims.close_span;
+
+            let mut errors = Vec::new();
+            for (id, loan) in self.loans.iter().enumerate() {
+                if loan.region.is_some() || !scope.values().any(|local| *local == loan.place.local) {
+                    continue;
+                }
+
+                let name = self.locals[loan.place.local].name;
+                let dropped = Label {
+                    span: end,
+                    message: format!("`{}` is dropped here", Interned(&name, self.interner)),
+                };
+
+                if let Some((_, span)) = value.filter(|(value, _)| value.loans.contains(&id)) {
+                    let used = Label {
+                        span,
+                        message: "but the block's value still borrows it".to_string(),
+                    };
+                    errors.push(Error::DanglingRef(loan.span, name, vec![dropped, used]));
+                    continue;
+                }
+
+                let holder = self.state.holds.iter().find_map(|(holder, loans)| match loans.contains(&id) {
+                    true if !scope.values().any(|local| local == holder) => self.used_after(*holder, end),
+                    _ => None,
+                });
+                if let Some(use_) = holder {
+                    let used = Label {
+                        span: use_,
+                        message: "but the borrow is used later here".to_string(),
+                    };
+                    errors.push(Error::DanglingRef(loan.span, name, vec![dropped, used]));
+                }
+            }
+
+            for error in errors {
+                self.error(error);
+            }
+        }
+
+        for local in scope.values() {
+            self.state.forget(*local);
+        }
+    }
+
+    /// Where a local is used after `point`, if it is, which is what keeps the loans it holds live at `point`.
+    ///
+    /// Uses are found by name, so uses of other locals with the same name count too.
+    pub(crate) fn used_after(&self, local: LocalId, point: Span) -> Option<Span> {
+        let uses = self.uses.get(&self.locals[local].name)?;
+
+        uses.iter().copied().find(|use_| {
+            self.loops.iter().any(|loop_| contains(*loop_, *use_))
+                || (use_.start() >= point.end() && !self.excluded.iter().any(|excluded| contains(*excluded, *use_)))
+        })
+    }
+
+    /// The loans of places that overlap `place` that are live at `point`,
+    /// with where each is used later if it's held by a local rather than by the statement being checked.
+    ///
+    /// The loans of parameters don't borrow from the function's locals, so they're never included.
+    pub(crate) fn live_loans(&self, place: &Place, point: Span) -> Vec<(LoanId, Option<Span>)> {
+        let overlaps = |loan: LoanId| self.loans[loan].region.is_none() && self.loans[loan].place.overlaps(place);
+
+        let mut output = self
+            .state
+            .temps
+            .iter()
+            .filter(|loan| overlaps(**loan))
+            .map(|loan| (*loan, None))
+            .collect::<Vec<_>>();
+
+        for (holder, loans) in &self.state.holds {
+            let Some(use_) = self.used_after(*holder, point) else {
+                continue;
+            };
+
+            for loan in loans {
+                if overlaps(*loan) && !output.iter().any(|(other, _)| other == loan) {
+                    output.push((*loan, Some(use_)));
+                }
+            }
+        }
+
+        output
+    }
+
+    /// Labels where a loan was made, and where it's used later if it is.
+    pub(crate) fn loan_labels(&self, loan: LoanId, use_: Option<Span>) -> Vec<Label> {
+        let Loan { place, kind, span, .. } = &self.loans[loan];
+
+        let mut labels = vec![Label {
+            span: *span,
+            message: format!("`{}` is borrowed as `{}` here", self.place_name(place), kind.name()),
+        }];
+        if let Some(use_) = use_ {
+            labels.push(Label {
+                span: use_,
+                message: "and the borrow is used later here".to_string(),
+            });
+        }
+
+        labels
+    }
+
+    /// How a place is written in code, like `a.b[_]`.
+    pub(crate) fn place_name(&self, place: &Place) -> String {
+        let mut output = self.interner.unintern(self.locals[place.local].name).to_string();
+
+        for projection in &place.projections {
+            match projection {
+                Projection::Field(name) => output.push_str(&format!(".{}", self.interner.unintern(*name))),
+                Projection::Unnamed(index) => output.push_str(&format!(".{index}")),
+                Projection::Index => output.push_str("[_]"),
+            }
+        }
+
+        output
+    }
+
+    pub(crate) fn error(&mut self, error: Error) {
+        if self.reporting {
+            self.errors.push(self.diagnostics.push_error(error));
+        }
+    }
+}
+
+impl Place {
+    pub fn local(local: LocalId) -> Self {
+        Self {
+            local,
+            projections: Vec::new(),
+        }
+    }
+
+    /// Whether the places share memory, which is when one is a part of the other.
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.local == other.local
+            && self
+                .projections
+                .iter()
+                .zip(&other.projections)
+                .all(|(lhs, rhs)| match (lhs, rhs) {
+                    (Projection::Index, Projection::Index) => true,
+                    _ => lhs == rhs,
+                })
+    }
+}
+
+impl Value {
+    pub fn new(class: Class) -> Self {
+        Self {
+            loans: BTreeSet::new(),
+            class,
+        }
+    }
+
+    /// A value of a type the checker doesn't know, which holds some loans.
+    pub fn with_loans(loans: BTreeSet<LoanId>) -> Self {
+        Self {
+            loans,
+            class: Class::Copy,
+        }
+    }
+
+    /// A value that's either of two values, which holds the loans of both.
+    pub fn join(self, other: Self) -> Self {
+        Self {
+            loans: self.loans.into_iter().chain(other.loans).collect(),
+            class: self.class.join(other.class),
+        }
+    }
+}
+
+impl State {
+    pub fn new() -> Self {
+        Self {
+            moved: BTreeMap::new(),
+            holds: BTreeMap::new(),
+            temps: BTreeSet::new(),
+            reachable: true,
+        }
+    }
+
+    /// Removes what's known about a local that can't be used anymore.
+    pub fn forget(&mut self, local: LocalId) {
+        self.moved.remove(&local);
+        self.holds.remove(&local);
+    }
+
+    /// The state after either of two paths, where whatever happened on one of them may have happened.
+    pub fn join(mut self, other: Self) -> Self {
+        match (self.reachable, other.reachable) {
+            (_, false) => return self,
+            (false, true) => return other,
+            (true, true) => {}
+        }
+
+        for (local, spans) in other.moved {
+            let output = self.moved.entry(local).or_default();
+            for span in spans {
+                if !output.contains(&span) {
+                    output.push(span);
+                }
+            }
+        }
+        for (local, loans) in other.holds {
+            self.holds.entry(local).or_default().extend(loans);
+        }
+        self.temps.extend(other.temps);
+
+        self
+    }
+}
+
+fn contains(outer: Span, inner: Span) -> bool {
+    outer.start() <= inner.start() && inner.end() <= outer.end()
+}
@@ -0,0 +1,499 @@
+use super::*;
+
+impl<'ctx> BorrowChecker<'ctx> {
+    pub(crate) fn check_block(&mut self, block: &'ctx Block) -> Value {
+        self.scopes.push(HashMap::new());
+        let (value, span) = self.check_stmts(block);
+        self.end_scope(block, Some((&value, span)));
+
+        value
+    }
+
+    /// Returns the value of the block and the span it came from. The innermost scope is the block's.
+    pub(crate) fn check_stmts(&mut self, block: &'ctx Block) -> (Value, Span) {
+        for stmt in &block.stmts {
+            if !self.state.reachable {
+                break;
+            }
+
+            let outer_temps = take(&mut self.state.temps);
+            match self.check_stmt(stmt) {
+                Some(output) => {
+                    // The loans of an `eval`ed value stay live in the statement the block is in.
+                    self.state.temps.extend(outer_temps);
+                    return output;
+                }
+                None => self.state.temps = outer_temps,
+            }
+        }
+
+        (Value::new(Class::Copy), block.span())
+    }
+
+    /// Returns the value of the block if the statement is an `eval`.
+    fn check_stmt(&mut self, stmt: &'ctx Stmt) -> Option<(Value, Span)> {
+        match stmt {
+            Stmt::Item(_) | Stmt::Assume(_) => {}
+            Stmt::Let(let_) => self.check_let(let_),
+            Stmt::Eval(stmt) => {
+                let span = stmt.value.option_span().unwrap_or(stmt.keyword.span());
+                return Some((self.check_value(stmt.value.as_ref()), span));
+            }
+            Stmt::Return(stmt) => {
+                let span = stmt.value.option_span().unwrap_or(stmt.keyword.span());
+                let value = self.check_value(stmt.value.as_ref());
+
+                self.check_return(&value, span);
+                self.state.reachable = false;
+            }
+            Stmt::Break(stmt) => {
+                let value = self.check_value(stmt.value.as_ref());
+                self.exit_loop(stmt.label.as_ref(), Some(value));
+            }
+            Stmt::Continue(stmt) => self.exit_loop(stmt.label.as_ref(), None),
+            Stmt::Expr(stmt) => match &stmt.set {
+                Some(set) => {
+                    let value = self.check_expr(&set.value, true);
+                    self.assign(&stmt.expr, value);
+                }
+                None => {
+                    self.check_expr(&stmt.expr, true);
+                }
+            },
+        }
+
+        None
+    }
+
+    fn check_let(&mut self, let_: &'ctx LetStmt) {
+        let Try::Success(param) = &let_.name else {
+            return;
+        };
+        self.check_local_lifetimes(param);
+
+        let value = match &let_.value {
+            Some(assign) => self.check_expr(&assign.value, true),
+            None => Value::new(Class::Copy),
+        };
+        let class = match param.type_ {
+            Some(_) => self.param_class(param),
+            None => value.class,
+        };
+
+        self.bind_param(param, Value { class, ..value });
+    }
+
+    /// Assigning to a whole local replaces the loans it holds, and makes it usable again if it was moved.
+    fn assign(&mut self, place: &'ctx Expr, value: Value) {
+        let Some(tree) = place.tree() else {
+            return;
+        };
+        let span = tree.span();
+
+        if let Some(unary) = tree.as_unary() {
+            if let Some(UnOp::Deref(_)) = unary.prefixes.first() {
+                let target = self.check_unary(unary, 1, false);
+                self.check_write_through(target.class, "assign", span);
+                return;
+            }
+
+            if let Some(place) = self.place(unary, 0) {
+                let local = &self.locals[place.local];
+                if let (Class::Ref(_), false) = (local.class, place.projections.is_empty()) {
+                    // Assigning to a field through a reference, like `r.x = 1`.
+                    let target = self.use_place(Place::local(place.local), span, false);
+                    self.check_write_through(target.class, "assign", span);
+                    return;
+                }
+
+                let conflict = self
+                    .live_loans(&place, span)
+                    .into_iter()
+                    .find(|(loan, _)| self.loans[*loan].kind != RefKind::Mut);
+                if let Some((loan, use_)) = conflict {
+                    let kind = self.loans[loan].kind.name();
+                    let labels = self.loan_labels(loan, use_);
+                    self.error(Error::UseWhileBorrowed(
+                        span,
+                        "assign to",
+                        self.place_name(&place),
+                        kind,
+                        labels,
+                    ));
+                }
+
+                self.capture(place.local, Capture::SoleMut, span);
+
+                if place.projections.is_empty() {
+                    self.state.moved.remove(&place.local);
+                    self.state.holds.remove(&place.local);
+                }
+                if !value.loans.is_empty() {
+                    self.state.holds.entry(place.local).or_default().extend(value.loans);
+                }
+                return;
+            }
+        }
+
+        self.check_tree(&tree, false);
+    }
+
+    /// Checks the value of an `eval`, `return` or `break`, which is `()` when it's left out.
+    fn check_value(&mut self, value: Option<&'ctx Expr>) -> Value {
+        match value {
+            Some(value) => self.check_expr(value, true),
+            None => Value::new(Class::Copy),
+        }
+    }
+
+    /// Checks a labeled block, whose value is either the value it evaluates to or one of its `break`s leaves it with.
+    pub(crate) fn check_labeled_block(&mut self, block: &'ctx Block, label: StrId) -> Value {
+        self.exits.push(LoopExits {
+            label: Some(label),
+            depth: self.scopes.len(),
+            ..LoopExits::default()
+        });
+        let value = self.check_block(block);
+        let exits = self.exits.pop().unwrap();
+
+        self.state = exits.breaks.into_iter().fold(take(&mut self.state), State::join);
+
+        match exits.value {
+            Some(exits) => value.join(exits),
+            None => value,
+        }
+    }
+
+    pub(crate) fn check_try(&mut self, try_: &'ctx TryBlock) -> Value {
+        let Try::Success(block) = &try_.block else {
+            return Value::new(Class::Copy);
+        };
+
+        self.tries.push(TryExits {
+            depth: self.scopes.len(),
+            ..TryExits::default()
+        });
+        let value = self.check_block(block);
+        let exits = self.tries.pop().unwrap();
+
+        self.state = exits.states.into_iter().fold(take(&mut self.state), State::join);
+
+        Value {
+            loans: value.loans.into_iter().chain(exits.loans).collect(),
+            class: value.class,
+        }
+    }
+
+    /// Propagates the error of a `?` out of the innermost `try` block, or out of the function.
+    pub(crate) fn propagate(&mut self, value: &Value, span: Span) {
+        let Some(exits) = self.tries.last() else {
+            self.check_return(value, span);
+            return;
+        };
+
+        let mut state = self.state.clone();
+        for scope in &self.scopes[exits.depth..] {
+            for local in scope.values() {
+                state.forget(*local);
+            }
+        }
+
+        let exits = self.tries.last_mut().unwrap();
+        exits.states.push(state);
+        exits.loans.extend(&value.loans);
+    }
+
+    /// Leaves a loop or a labeled block through a `break` with its value or a `continue`, without the locals of its scopes.
+    ///
+    /// Without a label that's the innermost loop.
+    fn exit_loop(&mut self, label: Option<&Lifetime>, value: Option<Value>) {
+        let target = match label {
+            Some(label) => self
+                .exits
+                .iter()
+                .rposition(|exits| exits.label.is_some() && exits.label == label.str_id()),
+            None => self.exits.iter().rposition(|exits| exits.is_loop),
+        };
+
+        if let Some(index) = target {
+            let mut state = self.state.clone();
+            for scope in &self.scopes[self.exits[index].depth..] {
+                for local in scope.values() {
+                    state.forget(*local);
+                }
+            }
+
+            let exits = &mut self.exits[index];
+            match value {
+                Some(value) => {
+                    exits.breaks.push(state);
+                    exits.value = Some(match exits.value.take() {
+                        Some(other) => other.join(value),
+                        None => value,
+                    });
+                }
+                None => exits.continues.push(state),
+            }
+        }
+
+        self.state.reachable = false;
+    }
+
+    pub(crate) fn check_if(&mut self, if_: &'ctx If) -> Value {
+        if let Try::Success(condition) = &if_.condition {
+            self.check_expr(&**condition, false);
+        }
+
+        let (then, else_) = match &if_.body {
+            IfBody::Then(then) => (then.expr.option_span(), then.else_.as_ref()),
+            IfBody::Block(block) => (block.block.option_span(), block.else_.as_ref()),
+        };
+        let else_span = else_.and_then(|else_| else_.expr.option_span());
+
+        let entry = self.state.clone();
+
+        self.excluded.extend(else_span);
+        let then_value = match &if_.body {
+            IfBody::Then(then) => self.check_expr(&then.expr, true),
+            IfBody::Block(block) => match &block.block {
+                Try::Success(block) => self.check_block(block),
+                Try::Failure(_) => Value::new(Class::Copy),
+            },
+        };
+        if else_span.is_some() {
+            self.excluded.pop();
+        }
+        let then_state = replace(&mut self.state, entry);
+
+        self.excluded.extend(then);
+        let else_value = match else_ {
+            Some(else_) => self.check_expr(&else_.expr, true),
+            None => Value::new(Class::Copy),
+        };
+        if then.is_some() {
+            self.excluded.pop();
+        }
+
+        self.state = then_state.join(take(&mut self.state));
+
+        Value {
+            loans: then_value.loans.into_iter().chain(else_value.loans).collect(),
+            class: then_value.class.join(else_value.class),
+        }
+    }
+
+    pub(crate) fn check_match(&mut self, match_: &'ctx Match) -> Value {
+        let scrutinee = match &match_.value {
+            Try::Success(value) => self.check_expr(&**value, false),
+            Try::Failure(_) => Value::new(Class::Copy),
+        };
+        let Try::Success(body) = &match_.body else {
+            return Value::new(Class::Copy);
+        };
+
+        let entry = self.state.clone();
+        let mut output = State::default();
+        let mut value = None::<Value>;
+
+        for (index, arm) in body.arms.iter().enumerate() {
+            let others = body
+                .arms
+                .iter()
+                .enumerate()
+                .filter(|(other, _)| *other != index)
+                .map(|(_, other)| other.span())
+                .collect::<Vec<_>>();
+            let excluded_len = self.excluded.len();
+            self.excluded.extend(others);
+
+            self.state = entry.clone();
+            self.scopes.push(HashMap::new());
+            for pattern in arm.patterns() {
+                let mut names = Vec::new();
+                pattern_bindings(pattern, self.interner, &mut names);
+
+                for ident in names {
+                    self.bind_local(ident, false, Value::with_loans(scrutinee.loans.clone()));
+                }
+            }
+
+            if let Some(guard) = &arm.guard {
+                self.check_expr(&guard.condition, false);
+            }
+            let arm_value = self.check_expr(&arm.value, true);
+
+            for local in self.scopes.pop().unwrap().values() {
+                self.state.forget(*local);
+            }
+            self.excluded.truncate(excluded_len);
+
+            output = output.join(take(&mut self.state));
+            value = Some(match value {
+                Some(value) => Value {
+                    loans: value.loans.into_iter().chain(arm_value.loans).collect(),
+                    class: value.class.join(arm_value.class),
+                },
+                None => arm_value,
+            });
+        }
+
+        self.state = output;
+
+        value.unwrap_or(Value::new(Class::Copy))
+    }
+
+    pub(crate) fn check_loop(&mut self, loop_: &'ctx Loop, label: Option<StrId>) -> Value {
+        self.check_loop_body(loop_.span(), &loop_.block, None, None, label)
+    }
+
+    pub(crate) fn check_while(&mut self, while_: &'ctx While, label: Option<StrId>) -> Value {
+        self.check_loop_body(while_.span(), &while_.block, Some(&while_.condition), None, label)
+    }
+
+    pub(crate) fn check_until(&mut self, until: &'ctx Until, label: Option<StrId>) -> Value {
+        self.check_loop_body(until.span(), &until.block, Some(&until.condition), None, label)
+    }
+
+    pub(crate) fn check_for(&mut self, for_: &'ctx For, label: Option<StrId>) -> Value {
+        let iter = self.check_expr(&for_.iter, true);
+        let item = for_.item.success_ref().map(|item| (&**item, iter));
+
+        self.check_loop_body(for_.span(), &for_.block, None, item, label)
+    }
+
+    /// Checks a quantifier, whose block runs for any number of the items like the body of a loop,
+    /// without moving what it's over.
+    pub(crate) fn check_quantifier(&mut self, quantifier: &'ctx Quantifier) -> Value {
+        let iter = self.check_expr(&quantifier.iter, false);
+        let item = quantifier.item.success_ref().map(|item| (&**item, iter));
+
+        let reporting = self.reporting;
+        self.loops.push(quantifier.span());
+
+        self.reporting = false;
+        let entry = self.state.clone();
+        let (first, _) = self.check_iteration(&quantifier.block, None, item.clone(), None);
+
+        self.reporting = reporting;
+        self.state = entry.join(first);
+        let head = self.state.clone();
+        let (end, _) = self.check_iteration(&quantifier.block, None, item, None);
+
+        self.loops.pop();
+        self.state = head.join(end);
+
+        Value::new(Class::Copy)
+    }
+
+    /// Checks the body of a loop twice, first without reporting errors to find what one iteration leaves behind,
+    /// and then from the state any number of iterations may leave.
+    ///
+    /// Without a condition the loop only exits through `break`. Returns the value of the loop, which its `break`s leave it with.
+    fn check_loop_body(
+        &mut self,
+        span: Span,
+        block: &'ctx Try<Block>,
+        condition: Option<&'ctx Try<Box<BraceExpr>>>,
+        item: Option<(&'ctx Param, Value)>,
+        label: Option<StrId>,
+    ) -> Value {
+        let reporting = self.reporting;
+        self.loops.push(span);
+
+        self.reporting = false;
+        let entry = self.state.clone();
+        let (first, _) = self.check_iteration(block, condition, item.clone(), label);
+
+        self.reporting = reporting;
+        self.state = entry.join(first);
+        let head = self.state.clone();
+        let (end, exits) = self.check_iteration(block, condition, item, label);
+
+        self.loops.pop();
+
+        let exit = condition.map(|_| head.join(end));
+        self.state = exits.breaks.into_iter().chain(exit).fold(State::default(), State::join);
+
+        exits.value.unwrap_or(Value::new(Class::Copy))
+    }
+
+    /// Returns the state at the end of the iteration, joined with the states at its `continue`s,
+    /// and the exits of the iteration, with the states at its `break`s.
+    fn check_iteration(
+        &mut self,
+        block: &'ctx Try<Block>,
+        condition: Option<&'ctx Try<Box<BraceExpr>>>,
+        item: Option<(&'ctx Param, Value)>,
+        label: Option<StrId>,
+    ) -> (State, LoopExits) {
+        if let Some(Try::Success(condition)) = condition {
+            let outer_temps = take(&mut self.state.temps);
+            self.check_expr(&**condition, false);
+            self.state.temps = outer_temps;
+        }
+
+        self.exits.push(LoopExits {
+            label,
+            is_loop: true,
+            depth: self.scopes.len(),
+            ..LoopExits::default()
+        });
+        self.scopes.push(HashMap::new());
+        if let Some((item, iter)) = item {
+            let class = match item.type_ {
+                Some(_) => self.param_class(item),
+                None => Class::Copy,
+            };
+            self.bind_param(item, Value { class, ..iter });
+        }
+
+        if let Try::Success(block) = block {
+            let outer_temps = take(&mut self.state.temps);
+            self.check_block(block);
+            self.state.temps = outer_temps;
+        }
+
+        for local in self.scopes.pop().unwrap().values() {
+            self.state.forget(*local);
+        }
+        let mut exits = self.exits.pop().unwrap();
+
+        let end = take(&mut exits.continues)
+            .into_iter()
+            .fold(take(&mut self.state), State::join);
+        (end, exits)
+    }
+}
+
+/// Collects the names a pattern binds.
+pub(crate) fn pattern_bindings(pattern: &Pattern, interner: &Interner, output: &mut Vec<Ident>) {
+    match pattern {
+        Pattern::Path(path) => match &path.tail {
+            None => output.extend(path.binding(interner)),
+            Some(PathPatternTail::Variant(variant)) => {
+                for item in variant.payload.iter().flat_map(|payload| &payload.items) {
+                    pattern_bindings(item, interner, output);
+                }
+            }
+            Some(PathPatternTail::Fields(fields)) => {
+                for field in &fields.fields {
+                    match field {
+                        FieldPattern::Field(NamedFieldPattern { ident, value: None }) => output.push(*ident),
+                        FieldPattern::Field(NamedFieldPattern {
+                            value:
+                                Some(PatternAssign {
+                                    pattern: Try::Success(pattern),
+                                    ..
+                                }),
+                            ..
+                        }) => pattern_bindings(pattern, interner, output),
+                        FieldPattern::Field(_) | FieldPattern::Rest(_) => {}
+                    }
+                }
+            }
+        },
+        Pattern::Literal(_) | Pattern::RangeTo(_) => {}
+        Pattern::Tuple(tuple) => tuple.items.iter().for_each(|item| pattern_bindings(item, interner, output)),
+        Pattern::Array(array) => array.items.iter().for_each(|item| pattern_bindings(item, interner, output)),
+    }
+}
@@ -0,0 +1,366 @@
+use super::*;
+
+impl<'ctx> BorrowChecker<'ctx> {
+    /// Checks an expression, whose value is moved out of the local it's read from if `moves` is `true`.
+    pub(crate) fn check_expr(&mut self, expr: &'ctx impl ToExprTree, moves: bool) -> Value {
+        match expr.tree() {
+            Some(tree) => self.check_tree(&tree, moves),
+            None => Value::new(Class::Copy),
+        }
+    }
+
+    pub(crate) fn check_tree(&mut self, tree: &ExprTree<'ctx>, moves: bool) -> Value {
+        match tree {
+            ExprTree::Unary(unary) => self.check_unary(unary, 0, moves),
+            ExprTree::BinOp(lhs, _, rhs) => {
+                self.check_tree(lhs, false);
+                self.check_tree(rhs, false);
+
+                Value::new(Class::Copy)
+            }
+        }
+    }
+
+    /// Checks a unary expression without its first `skip` prefixes.
+    pub(crate) fn check_unary(&mut self, unary: &UnaryExprRef<'ctx>, skip: usize, moves: bool) -> Value {
+        let Some(prefix) = unary.prefixes.get(skip) else {
+            return self.check_operand(unary, moves);
+        };
+        let span = Span::from_positions(prefix.span().start(), unary.span.end()).unwrap_or(unary.span);
+
+        match prefix {
+            UnOp::Ref(ref_) => {
+                let kind = RefKind::of(ref_);
+
+                if let Some(UnOp::Deref(_)) = unary.prefixes.get(skip + 1) {
+                    let target = self.check_unary(unary, skip + 2, false);
+                    if kind.is_mut() {
+                        self.check_write_through(target.class, "mutably borrow", span);
+                    }
+
+                    return Value {
+                        loans: target.loans,
+                        class: Class::Ref(kind),
+                    };
+                }
+
+                match self.place(unary, skip + 1) {
+                    Some(place) => self.borrow(place, kind, span),
+                    None => Value {
+                        loans: self.check_unary(unary, skip + 1, true).loans,
+                        class: Class::Ref(kind),
+                    },
+                }
+            }
+            UnOp::Lifetime(_) => self.check_unary(unary, skip + 1, moves),
+            _ => {
+                self.check_unary(unary, skip + 1, false);
+
+                Value::new(Class::Copy)
+            }
+        }
+    }
+
+    /// Checks the core of a unary expression and its extensions, after its prefixes.
+    fn check_operand(&mut self, unary: &UnaryExprRef<'ctx>, moves: bool) -> Value {
+        if let Some(place) = self.place(unary, unary.prefixes.len()) {
+            let moves = moves && place.projections.is_empty();
+            return self.use_place(place, unary.span, moves);
+        }
+
+        let label = unary.label.and_then(Lifetime::str_id);
+        let mut value = match unary.core {
+            ExprCore::Ident(ident) => match self.local(ident.str_id()) {
+                Some(local) => self.use_place(Place::local(local), ident.span(), false),
+                None => Value::new(Class::Copy),
+            },
+            ExprCore::Keyword(ExprKeyword::Fn(fn_)) => self.check_closure(fn_),
+            ExprCore::Keyword(_) | ExprCore::Literal(_) => Value::new(Class::Copy),
+            ExprCore::Block(block) => match label {
+                Some(label) => self.check_labeled_block(block, label),
+                None => self.check_block(block),
+            },
+            ExprCore::Tuple(tuple) if tuple.items.len() == 1 => self.check_expr(&tuple.items[0], moves),
+            ExprCore::Tuple(tuple) => {
+                let items = tuple.items.iter().map(|item| self.check_expr(item, true)).collect::<Vec<_>>();
+                collect_values(items)
+            }
+            ExprCore::Array(array) => {
+                if let Some(len) = &array.len {
+                    self.check_expr(&len.len, false);
+                }
+
+                let items = array.items.iter().map(|item| self.check_expr(item, true)).collect::<Vec<_>>();
+                collect_values(items)
+            }
+            ExprCore::If(if_) => self.check_if(if_),
+            ExprCore::Loop(loop_) => self.check_loop(loop_, label),
+            ExprCore::While(while_) => self.check_while(while_, label),
+            ExprCore::Until(until) => self.check_until(until, label),
+            ExprCore::For(for_) => self.check_for(for_, label),
+            ExprCore::Quantifier(quantifier) => self.check_quantifier(quantifier),
+            ExprCore::Match(match_) => self.check_match(match_),
+            ExprCore::Try(try_) => self.check_try(try_),
+        };
+
+        // What calling the expression returns, which is known for functions and enum variants.
+        let mut output = None;
+        let mut item = match unary.core {
+            ExprCore::Ident(ident) if self.local(ident.str_id()).is_none() => self.namespace.get(ident.str_id()),
+            _ => None,
+        };
+
+        for ext in &unary.exts {
+            value = match ext {
+                UnaryExprExt::Member(_) => {
+                    output = None;
+                    match item.take().map(|id| &self.namespace.item(id).core) {
+                        Some(Try::Success(ItemCore::Enum(_))) => {
+                            output = Some((Class::Move, true));
+                            Value::new(Class::Move)
+                        }
+                        _ => Value::with_loans(value.loans),
+                    }
+                }
+                UnaryExprExt::Call(tuple) => {
+                    let callee = item.take();
+                    let (class, holds_refs) = match callee {
+                        Some(id) => self.output_class(id),
+                        None => output.take().unwrap_or((Class::Copy, false)),
+                    };
+                    let borrowed = callee.and_then(|id| self.borrowed_inputs(id));
+
+                    // The loans of the arguments end with the call, unless what it returns can hold them.
+                    let temps = self.state.temps.clone();
+                    let args = tuple.items.iter().map(|item| self.check_expr(item, true)).collect::<Vec<_>>();
+                    self.state.temps = temps;
+
+                    // The output only borrows from the arguments whose lifetimes it can have.
+                    let loans = match holds_refs {
+                        true => args
+                            .into_iter()
+                            .enumerate()
+                            .filter(|(index, _)| borrowed.as_ref().is_none_or(|borrowed| borrowed.get(*index) != Some(&false)))
+                            .flat_map(|(_, arg)| arg.loans)
+                            .collect(),
+                        false => BTreeSet::new(),
+                    };
+                    self.state.temps.extend(&loans);
+
+                    Value { loans, class }
+                }
+                UnaryExprExt::Index(array) => {
+                    for item in &array.items {
+                        self.check_expr(item, false);
+                    }
+
+                    Value::with_loans(value.loans)
+                }
+                UnaryExprExt::Generics(_) => value,
+                UnaryExprExt::Question(question) => {
+                    output = None;
+                    self.propagate(&value, question.span());
+
+                    Value::with_loans(value.loans)
+                }
+                UnaryExprExt::Construct(construct) => {
+                    item = None;
+
+                    let fields = construct
+                        .items
+                        .iter()
+                        .filter_map(|field| field.set.success_ref())
+                        .map(|set| self.check_expr(&set.value, true))
+                        .collect::<Vec<_>>();
+
+                    Value {
+                        class: Class::Move,
+                        ..collect_values(fields)
+                    }
+                }
+            };
+        }
+
+        value
+    }
+
+    /// The place a unary expression without its first `skip` prefixes refers to, if it's a local or a part of one.
+    ///
+    /// The indices of the place are checked.
+    pub(crate) fn place(&mut self, unary: &UnaryExprRef<'ctx>, skip: usize) -> Option<Place> {
+        let ExprCore::Ident(ident) = unary.core else {
+            return None;
+        };
+        if unary.prefixes.len() != skip {
+            return None;
+        }
+        let local = self.local(ident.str_id())?;
+
+        let mut projections = Vec::new();
+        for ext in &unary.exts {
+            projections.push(match ext {
+                UnaryExprExt::Member(UnaryExprMemberExt {
+                    member: Try::Success(Member::Named(name)),
+                    ..
+                }) => Projection::Field(name.str_id()),
+                UnaryExprExt::Member(UnaryExprMemberExt {
+                    member:
+                        Try::Success(Member::Unnamed(IntLiteral {
+                            value: Try::Success(index),
+                            ..
+                        })),
+                    ..
+                }) => Projection::Unnamed(*index),
+                UnaryExprExt::Index(_) => Projection::Index,
+                _ => return None,
+            });
+        }
+
+        for ext in &unary.exts {
+            if let UnaryExprExt::Index(array) = ext {
+                for item in &array.items {
+                    self.check_expr(item, false);
+                }
+            }
+        }
+
+        Some(Place { local, projections })
+    }
+
+    /// Reads a place, or moves out of it if `moves` is `true` and its value isn't copied.
+    pub(crate) fn use_place(&mut self, place: Place, span: Span, moves: bool) -> Value {
+        let class = self.locals[place.local].class;
+
+        self.check_moved(place.local, span);
+
+        let moves = moves && class.moves();
+        self.capture(place.local, if moves { Capture::Move } else { Capture::Shared }, span);
+
+        let conflict = self
+            .live_loans(&place, span)
+            .into_iter()
+            .find(|(loan, _)| moves || self.loans[*loan].kind == RefKind::SoleMut);
+        if let Some((loan, use_)) = conflict {
+            let action = if moves { "move" } else { "use" };
+            let kind = self.loans[loan].kind.name();
+            let labels = self.loan_labels(loan, use_);
+            self.error(Error::UseWhileBorrowed(span, action, self.place_name(&place), kind, labels));
+        }
+
+        let loans = match moves {
+            true => {
+                self.state.moved.insert(place.local, vec![span]);
+                self.state.holds.remove(&place.local).unwrap_or_default()
+            }
+            false => self.state.holds.get(&place.local).cloned().unwrap_or_default(),
+        };
+        self.state.temps.extend(&loans);
+
+        let class = if place.projections.is_empty() { class } else { Class::Copy };
+        Value { loans, class }
+    }
+
+    /// Borrows a place, or reborrows what a reference borrows when the place is a part of what the reference points to.
+    pub(crate) fn borrow(&mut self, place: Place, kind: RefKind, span: Span) -> Value {
+        let LocalInfo { name, mutable, class } = self.locals[place.local];
+
+        if let (Class::Ref(_), false) = (class, place.projections.is_empty()) {
+            let target = self.use_place(Place::local(place.local), span, false);
+            if kind.is_mut() {
+                self.check_write_through(target.class, "mutably borrow", span);
+            }
+
+            return Value {
+                loans: target.loans,
+                class: Class::Ref(kind),
+            };
+        }
+
+        if kind.is_mut() && !mutable {
+            self.error(Error::BorrowImmutable(span, name, kind.name()));
+        }
+        self.check_moved(place.local, span);
+        self.capture(
+            place.local,
+            if kind.is_mut() { Capture::SoleMut } else { Capture::Shared },
+            span,
+        );
+
+        let conflict = self
+            .live_loans(&place, span)
+            .into_iter()
+            .find(|(loan, _)| self.loans[*loan].kind.conflicts(kind));
+        if let Some((loan, use_)) = conflict {
+            let other = self.loans[loan].kind.name();
+            let labels = self.loan_labels(loan, use_);
+            self.error(Error::BorrowConflict(
+                span,
+                self.place_name(&place),
+                kind.name(),
+                other,
+                labels,
+            ));
+        }
+
+        let id = self.loans.len();
+        self.loans.push(Loan {
+            place,
+            kind,
+            span,
+            region: None,
+        });
+        self.state.temps.insert(id);
+
+        Value {
+            loans: BTreeSet::from([id]),
+            class: Class::Ref(kind),
+        }
+    }
+
+    /// Checks that a local isn't used after it's moved, which is reported once.
+    fn check_moved(&mut self, local: LocalId, span: Span) {
+        let Some(moved) = self.state.moved.remove(&local) else {
+            return;
+        };
+        let name = self.locals[local].name;
+
+        let labels = moved
+            .into_iter()
+            .map(|moved| Label {
+                span: moved,
+                message: match moved.start() >= span.start() {
+                    true => format!(
+                        "`{}` is moved here, in a previous iteration of the loop",
+                        Interned(&name, self.interner)
+                    ),
+                    false => format!("`{}` is moved here", Interned(&name, self.interner)),
+                },
+            })
+            .collect();
+        self.error(Error::UseAfterMove(span, name, labels));
+    }
+
+    /// Checks that what a reference points to can be changed through it.
+    pub(crate) fn check_write_through(&mut self, class: Class, action: &'static str, span: Span) {
+        if let Class::Ref(kind) = class
+            && !kind.is_mut()
+        {
+            self.error(Error::ImmutableRef(span, action, kind.name()));
+        }
+    }
+}
+
+/// A value made of other values, which holds all of their loans.
+fn collect_values(values: Vec<Value>) -> Value {
+    let class = values
+        .iter()
+        .fold(Class::Copy, |class, value| match (class, value.class.moves()) {
+            (Class::Copy, false) => Class::Copy,
+            _ => Class::Move,
+        });
+
+    Value {
+        loans: values.into_iter().flat_map(|value| value.loans).collect(),
+        class,
+    }
+}
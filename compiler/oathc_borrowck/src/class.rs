@@ -0,0 +1,192 @@
+use super::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RefKind {
+    Shared,
+    Mut,
+    Sole,
+    SoleMut,
+}
+
+/// How values of a type are passed around.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Class {
+    /// Integers, `bool`s and types the checker doesn't know, which are copied.
+    Copy,
+    /// Structs and enums, which are moved.
+    Move,
+    Ref(RefKind),
+}
+
+impl RefKind {
+    pub fn of(ref_: &Ref) -> Self {
+        match ref_.bounds {
+            Some(RefModifier::Mut(_)) => Self::Mut,
+            Some(RefModifier::Sole(_)) => Self::Sole,
+            Some(RefModifier::SoleMut(_)) => Self::SoleMut,
+            Some(RefModifier::Lifetime(_)) | None => Self::Shared,
+        }
+    }
+
+    pub fn is_mut(self) -> bool {
+        matches!(self, Self::Mut | Self::SoleMut)
+    }
+
+    pub fn is_sole(self) -> bool {
+        matches!(self, Self::Sole | Self::SoleMut)
+    }
+
+    /// Whether two references to overlapping places can't be live at the same time.
+    ///
+    /// Sole references can't share their place with any other reference,
+    /// and `&` expects its place not to change, so it can't share it with `&mut`.
+    pub fn conflicts(self, other: Self) -> bool {
+        self.is_sole() || other.is_sole() || self.is_mut() != other.is_mut()
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Shared => "&",
+            Self::Mut => "&mut",
+            Self::Sole => "&sole",
+            Self::SoleMut => "&smut",
+        }
+    }
+}
+
+impl Class {
+    /// Whether using a value of this class by value leaves the place it came from unusable.
+    pub fn moves(self) -> bool {
+        match self {
+            Self::Copy => false,
+            Self::Move => true,
+            Self::Ref(kind) => kind.is_sole(),
+        }
+    }
+
+    /// The class of a value that's either of two values, like the branches of an `if`.
+    pub fn join(self, other: Self) -> Self {
+        match self == other {
+            true => self,
+            false if self.moves() || other.moves() => Self::Move,
+            false => Self::Copy,
+        }
+    }
+}
+
+impl<'ctx> BorrowChecker<'ctx> {
+    pub(crate) fn param_class(&self, param: &Param) -> Class {
+        match param.type_.as_ref().and_then(AngleUnaryExpr::as_ref_expr) {
+            Some(type_) => self.type_class(&ExprTree::Unary(type_)),
+            None => Class::Copy,
+        }
+    }
+
+    /// The class of a type expression.
+    pub(crate) fn type_class(&self, tree: &ExprTree) -> Class {
+        let Some(unary) = tree.as_unary() else {
+            return Class::Copy;
+        };
+
+        if let Some(UnOp::Ref(ref_)) = unary.prefixes.first() {
+            return Class::Ref(RefKind::of(ref_));
+        }
+        if !unary.prefixes.is_empty() {
+            return Class::Copy;
+        }
+
+        match unary.core {
+            ExprCore::Ident(ident) => match self.namespace.get(ident.str_id()).map(|id| &self.namespace.item(id).core) {
+                Some(Try::Success(ItemCore::Struct(_) | ItemCore::Enum(_))) => Class::Move,
+                _ => Class::Copy,
+            },
+            ExprCore::Tuple(tuple) => tuple
+                .items
+                .iter()
+                .map(|item| item.tree().map_or(Class::Copy, |tree| self.type_class(&tree)))
+                .fold(Class::Copy, |class, item| match (class, item) {
+                    (Class::Copy, item) if !item.moves() => Class::Copy,
+                    _ => Class::Move,
+                }),
+            ExprCore::Array(array) => match array.items.first().and_then(ToExprTree::tree) {
+                Some(item) => self.type_class(&item).join(Class::Copy),
+                None => Class::Copy,
+            },
+            _ => Class::Copy,
+        }
+    }
+
+    /// The class of what a function returns, and whether it can hold references, which may borrow from its arguments.
+    pub(crate) fn output_class(&self, id: ItemId) -> (Class, bool) {
+        let Try::Success(ItemCore::Fn(fn_)) = &self.namespace.item(id).core else {
+            return (Class::Copy, false);
+        };
+        let Some(mut tree) = fn_.output.as_ref().and_then(ToExprTree::tree) else {
+            return (Class::Copy, false);
+        };
+        while let ExprTree::BinOp(lhs, BinOp::Bound(_), _) = tree {
+            tree = *lhs;
+        }
+
+        (self.type_class(&tree), self.holds_refs(&tree, &mut Vec::new()))
+    }
+
+    /// Whether values of a type can hold references, which is assumed for types the checker doesn't know.
+    pub(crate) fn holds_refs(&self, tree: &ExprTree, structs: &mut Vec<ItemId>) -> bool {
+        let Some(unary) = tree.as_unary() else {
+            return false;
+        };
+
+        match unary.prefixes.first() {
+            Some(UnOp::Ref(_)) => return true,
+            Some(_) => return false,
+            None => {}
+        }
+
+        match unary.core {
+            ExprCore::Ident(ident) => {
+                let name = self.interner.unintern(ident.str_id());
+                if name == "bool" || name == "type" || IntType::from_name(&name).is_some() {
+                    return false;
+                }
+
+                let Some(id) = self.namespace.get(ident.str_id()) else {
+                    return true;
+                };
+                if structs.contains(&id) {
+                    return false;
+                }
+
+                let params = match &self.namespace.item(id).core {
+                    Try::Success(ItemCore::Struct(struct_)) => struct_.fields.success_ref(),
+                    Try::Success(ItemCore::Enum(enum_)) => enum_.variants.success_ref(),
+                    _ => return true,
+                };
+
+                structs.push(id);
+                let output = params.is_some_and(|params| {
+                    params
+                        .items
+                        .iter()
+                        .any(|param| match param.type_.as_ref().and_then(AngleUnaryExpr::as_ref_expr) {
+                            Some(type_) => self.holds_refs(&ExprTree::Unary(type_), structs),
+                            None => false,
+                        })
+                });
+                structs.pop();
+
+                output
+            }
+            ExprCore::Tuple(tuple) => tuple
+                .items
+                .iter()
+                .any(|item| item.tree().is_some_and(|tree| self.holds_refs(&tree, structs))),
+            ExprCore::Array(array) => array
+                .items
+                .first()
+                .and_then(ToExprTree::tree)
+                .is_some_and(|item| self.holds_refs(&item, structs)),
+            _ => true,
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use super::*;
+
+/// How a closure captures a local, from the weakest to the strongest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Capture {
+    /// The closure only reads the local, so it borrows it as `&`.
+    Shared,
+    /// The closure changes the local, so it borrows it as `&smut`.
+    SoleMut,
+    /// The closure moves the local's value out of it, so it takes the value.
+    Move,
+}
+
+impl<'ctx> BorrowChecker<'ctx> {
+    /// Checks the body of a closure, and captures the locals it uses.
+    ///
+    /// A closure without a body is a function type, which doesn't hold anything.
+    pub(crate) fn check_closure(&mut self, fn_: &'ctx FnExpr) -> Value {
+        let Some(body) = &fn_.body else {
+            return Value::new(Class::Copy);
+        };
+        let params = fn_.input.success_ref().map_or(&[][..], |input| &input.items);
+
+        // A closure has no lifetime parameters of its own, but it can use the ones of the function it's in.
+        let (signature, _, errors) = self.elide(self.signature.lifetimes.clone(), params, fn_.output.as_deref());
+        for error in errors {
+            self.error(error);
+        }
+
+        let mut uses = HashMap::new();
+        uses_in_block(body, &mut uses);
+
+        let depth = self.scopes.len();
+        let outer_first_local = replace(&mut self.first_local, self.locals.len());
+        let outer_captures = take(&mut self.captures);
+        let outer_state = replace(&mut self.state, State::new());
+        let outer_uses = replace(&mut self.uses, uses);
+        let outer_loops = take(&mut self.loops);
+        let outer_excluded = take(&mut self.excluded);
+        let outer_exits = take(&mut self.exits);
+        let outer_tries = take(&mut self.tries);
+
+        self.scopes.push(HashMap::new());
+        self.bind_input(params, &signature);
+        let outer_signature = replace(&mut self.signature, signature);
+
+        self.scopes.push(HashMap::new());
+        let (value, span) = self.check_stmts(body);
+        if self.state.reachable {
+            self.check_return(&value, span);
+        }
+        self.end_scope(body, None);
+        self.scopes.truncate(depth);
+
+        let captures = replace(&mut self.captures, outer_captures);
+        self.first_local = outer_first_local;
+        self.state = outer_state;
+        self.signature = outer_signature;
+        self.uses = outer_uses;
+        self.loops = outer_loops;
+        self.excluded = outer_excluded;
+        self.exits = outer_exits;
+        self.tries = outer_tries;
+
+        let mut output = Value::new(Class::Copy);
+        for (local, (capture, span)) in captures {
+            let place = Place::local(local);
+            let value = match capture {
+                Capture::Shared => self.borrow(place, RefKind::Shared, span),
+                Capture::SoleMut => self.borrow(place, RefKind::SoleMut, span),
+                Capture::Move => self.use_place(place, span, true),
+            };
+
+            output.loans.extend(value.loans);
+            if capture != Capture::Shared {
+                output.class = Class::Move;
+            }
+        }
+
+        output
+    }
+
+    /// Records that the closure being checked uses a local, if the local is from outside of it.
+    pub(crate) fn capture(&mut self, local: LocalId, capture: Capture, span: Span) {
+        if local >= self.first_local {
+            return;
+        }
+
+        let entry = self.captures.entry(local).or_insert((capture, span));
+        if capture > entry.0 {
+            *entry = (capture, span);
+        }
+    }
+}
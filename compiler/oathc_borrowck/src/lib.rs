@@ -0,0 +1,26 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    mem::{replace, take},
+};
+
+use oathc_ast::*;
+use oathc_comptime::IntType;
+use oathc_diagnostics::*;
+use oathc_interner::*;
+use oathc_res::{ItemId, Namespace};
+use oathc_span::*;
+use oathc_tokens::*;
+
+mod borrowck;
+mod check_block;
+mod check_expr;
+mod class;
+mod closure;
+mod lifetimes;
+mod uses;
+pub use borrowck::*;
+use check_block::*;
+use class::*;
+use closure::*;
+use lifetimes::*;
+use uses::*;
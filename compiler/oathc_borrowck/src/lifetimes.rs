@@ -0,0 +1,353 @@
+use super::*;
+
+/// A lifetime in a function's signature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Region {
+    /// A lifetime parameter, like `'a`.
+    Named(StrId),
+    /// The lifetime of the references in the type of the parameter at an index that don't name one, which they all share.
+    Elided(usize),
+}
+
+/// The lifetimes of a function's parameters and output, after elision.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Signature {
+    /// The lifetime parameters of the function and of its impl.
+    pub lifetimes: Vec<StrId>,
+    /// The lifetime parameters that are declared to outlive others, like `'b: 'a`, as `('b, 'a)`.
+    pub outlives: Vec<(StrId, StrId)>,
+    /// The lifetimes of the references each parameter holds.
+    pub inputs: Vec<Vec<Region>>,
+    /// The lifetimes the references the output holds can have, or `None` if it can't hold references.
+    ///
+    /// When the output doesn't name its lifetimes and only one lifetime is in the inputs, it's that lifetime.
+    pub output: Option<Vec<Region>>,
+    /// The names of the parameters, to refer to their elided lifetimes.
+    pub names: Vec<String>,
+}
+
+impl<'ctx> BorrowChecker<'ctx> {
+    /// Resolves the lifetimes of a function's signature and elides the ones the output doesn't name.
+    pub(crate) fn signature(&self, fn_: &Fn, impl_: Option<&Impl>) -> (Signature, Vec<Error>) {
+        let impl_lifetimes = lifetime_params(
+            impl_
+                .and_then(|impl_| impl_.generics.as_ref())
+                .map_or(&[], |generics| &generics.items),
+        );
+        let fn_lifetimes = lifetime_params(fn_.generics.as_ref().map_or(&[], |generics| &generics.items));
+        let lifetimes = impl_lifetimes.iter().chain(&fn_lifetimes).map(|(name, _)| *name).collect();
+
+        let params = fn_.input.success_ref().map_or(&[][..], |input| &input.items);
+        let (mut signature, mut used, mut errors) = self.elide(lifetimes, params, fn_.output.as_ref());
+
+        let generics = impl_
+            .and_then(|impl_| impl_.generics.as_ref())
+            .into_iter()
+            .chain(&fn_.generics)
+            .flat_map(|generics| &generics.items);
+        for (name, bound) in lifetime_bounds(generics) {
+            match bound.str_id() {
+                Some(bound_name) if signature.lifetimes.contains(&bound_name) => {
+                    signature.outlives.push((name, bound_name));
+                    used.push((bound_name, bound.span()));
+                }
+                Some(bound_name) => errors.push(Error::UndeclaredLifetime(bound.span(), bound_name)),
+                None => {}
+            }
+        }
+
+        // The lifetimes of an impl can be used by any of its functions, so only the function's own have to be used.
+        for (name, span) in fn_lifetimes {
+            if !used.iter().any(|(used, _)| *used == name) {
+                errors.push(Error::UnusedLifetime(span, name));
+            }
+        }
+
+        (signature, errors)
+    }
+
+    /// Resolves the lifetimes of the parameters and the output of a function or a closure, where `lifetimes` are declared,
+    /// and returns the lifetimes they name.
+    pub(crate) fn elide(
+        &self,
+        lifetimes: Vec<StrId>,
+        params: &[Param],
+        output: Option<&BraceExpr>,
+    ) -> (Signature, Vec<(StrId, Span)>, Vec<Error>) {
+        let mut errors = Vec::new();
+        let mut signature = Signature {
+            lifetimes,
+            ..Signature::default()
+        };
+
+        let mut used = Vec::new();
+        for (index, param) in params.iter().enumerate() {
+            let mut named = Vec::new();
+            let elided = self.param_lifetimes(param, &mut named);
+
+            let mut regions = Vec::new();
+            for (name, _) in &named {
+                add_region(&mut regions, Region::Named(*name));
+            }
+            if elided {
+                regions.push(Region::Elided(index));
+            }
+
+            signature.inputs.push(regions);
+            signature
+                .names
+                .push(match param.body.success_ref().and_then(ParamBody::as_ident) {
+                    Some(ident) => self.interner.unintern(ident.str_id()).to_string(),
+                    None => "_".to_string(),
+                });
+            used.extend(named);
+        }
+
+        if let Some(tree) = output.and_then(ToExprTree::tree) {
+            let mut tree = tree;
+            while let ExprTree::BinOp(lhs, BinOp::Bound(_), _) = tree {
+                tree = *lhs;
+            }
+
+            let mut named = Vec::new();
+            let elided = self.type_lifetimes(&tree, &mut named);
+
+            if elided || !named.is_empty() {
+                let mut output = Vec::new();
+                for (name, _) in &named {
+                    add_region(&mut output, Region::Named(*name));
+                }
+                if elided {
+                    let mut inputs = Vec::new();
+                    for region in signature.inputs.iter().flatten() {
+                        add_region(&mut inputs, *region);
+                    }
+
+                    match inputs.len() {
+                        0 => errors.push(Error::MissingLifetime(
+                            tree.span(),
+                            "there's no parameter for the output to borrow from",
+                        )),
+                        1 => {}
+                        _ => errors.push(Error::MissingLifetime(
+                            tree.span(),
+                            "the output could borrow from more than one parameter",
+                        )),
+                    }
+
+                    for region in inputs {
+                        add_region(&mut output, region);
+                    }
+                }
+
+                signature.output = Some(output);
+            }
+            used.extend(named);
+        }
+
+        for (name, span) in &used {
+            if !signature.lifetimes.contains(name) {
+                errors.push(Error::UndeclaredLifetime(*span, *name));
+            }
+        }
+
+        (signature, used, errors)
+    }
+
+    /// Checks that the lifetimes the fields of a struct or the variants of an enum name are its lifetime parameters, and
+    /// that all of its lifetime parameters are used.
+    pub(crate) fn check_type_lifetimes(&mut self, generics: Option<&GenericParams>, fields: Option<&[Param]>) {
+        let declared = lifetime_params(generics.map_or(&[], |generics| &generics.params));
+
+        let mut used = Vec::new();
+        for field in fields.unwrap_or_default() {
+            if let Try::Success(ParamBody::Lifetime(param)) = &field.body {
+                self.error(Error::MisplacedLifetime(param.span()));
+            }
+            self.param_lifetimes(field, &mut used);
+        }
+
+        for (name, span) in &used {
+            if !declared.iter().any(|(declared, _)| declared == name) {
+                self.error(Error::UndeclaredLifetime(*span, *name));
+            }
+        }
+        // A type with no fields can't be checked, since it failed to parse.
+        if fields.is_some() {
+            for (name, span) in declared {
+                if !used.iter().any(|(used, _)| *used == name) {
+                    self.error(Error::UnusedLifetime(span, name));
+                }
+            }
+        }
+    }
+
+    /// Checks that the lifetimes the type of a local names are lifetime parameters of the function.
+    pub(crate) fn check_local_lifetimes(&mut self, param: &Param) {
+        let mut used = Vec::new();
+        self.param_lifetimes(param, &mut used);
+
+        for (name, span) in used {
+            if !self.signature.lifetimes.contains(&name) {
+                self.error(Error::UndeclaredLifetime(span, name));
+            }
+        }
+    }
+
+    /// Collects the lifetimes the type of a parameter names, and returns whether it holds references that don't name one.
+    fn param_lifetimes(&self, param: &Param, output: &mut Vec<(StrId, Span)>) -> bool {
+        match (&param.type_, &param.body) {
+            (Some(type_), _) => match type_.as_ref_expr() {
+                Some(type_) => self.type_lifetimes(&ExprTree::Unary(type_), output),
+                None => false,
+            },
+            (None, Try::Success(ParamBody::Tuple(params))) => params
+                .items
+                .iter()
+                .fold(false, |elided, param| self.param_lifetimes(param, output) | elided),
+            (None, _) => false,
+        }
+    }
+
+    /// Collects the lifetimes a type names, and returns whether it holds references that don't name one.
+    fn type_lifetimes(&self, tree: &ExprTree, output: &mut Vec<(StrId, Span)>) -> bool {
+        let Some(unary) = tree.as_unary() else {
+            return false;
+        };
+
+        let mut elided = false;
+        for prefix in &unary.prefixes {
+            match prefix {
+                UnOp::Ref(Ref {
+                    bounds: Some(RefModifier::Lifetime(lifetime)),
+                    ..
+                }) => {
+                    if let Try::Success(ident) = &lifetime.ident {
+                        output.push((ident.str_id(), lifetime.span()));
+                    }
+                }
+                UnOp::Ref(_) => elided = true,
+                _ => return elided,
+            }
+        }
+
+        for ext in &unary.exts {
+            if let UnaryExprExt::Generics(generics) = ext {
+                for arg in generics.args.iter().filter_map(ToExprTree::tree) {
+                    elided |= self.type_lifetimes(&arg, output);
+                }
+            }
+        }
+
+        let target = match unary.core {
+            ExprCore::Tuple(tuple) => tuple
+                .items
+                .iter()
+                .filter_map(ToExprTree::tree)
+                .fold(false, |elided, item| self.type_lifetimes(&item, output) | elided),
+            ExprCore::Array(array) => array
+                .items
+                .first()
+                .and_then(ToExprTree::tree)
+                .is_some_and(|item| self.type_lifetimes(&item, output)),
+            // The lifetimes of the references in generic types are given where they're instantiated, so they aren't elided.
+            ExprCore::Ident(ident) => match self.namespace.get(ident.str_id()).map(|id| &self.namespace.item(id).core) {
+                Some(Try::Success(ItemCore::Struct(_) | ItemCore::Enum(_))) => {
+                    let target = UnaryExprRef {
+                        prefixes: Vec::new(),
+                        ..unary.clone()
+                    };
+                    self.holds_refs(&ExprTree::Unary(target), &mut Vec::new())
+                }
+                _ => false,
+            },
+            _ => false,
+        };
+
+        elided || target
+    }
+
+    /// Which arguments of a call to a function the output may borrow from,
+    /// or `None` if it isn't known, like when the output is of a generic type.
+    pub(crate) fn borrowed_inputs(&self, id: ItemId) -> Option<Vec<bool>> {
+        let Try::Success(ItemCore::Fn(fn_)) = &self.namespace.item(id).core else {
+            return None;
+        };
+        let (signature, _) = self.signature(fn_, None);
+        let output = signature.output?;
+
+        Some(
+            signature
+                .inputs
+                .iter()
+                .map(|regions| regions.iter().any(|region| output.contains(region)))
+                .collect(),
+        )
+    }
+
+    /// Whether a lifetime lives at least as long as another, because they're the same or because it's declared to
+    /// outlive it, directly or through other lifetimes.
+    pub(crate) fn outlives(&self, region: Region, target: Region) -> bool {
+        let mut longer = vec![region];
+        let mut index = 0;
+        while let Some(region) = longer.get(index).copied() {
+            if region == target {
+                return true;
+            }
+            if let Region::Named(name) = region {
+                for (_, shorter) in self.signature.outlives.iter().filter(|(longer, _)| *longer == name) {
+                    add_region(&mut longer, Region::Named(*shorter));
+                }
+            }
+            index += 1;
+        }
+
+        false
+    }
+
+    /// How a lifetime is described in errors.
+    pub(crate) fn region_name(&self, region: Region) -> String {
+        match region {
+            Region::Named(name) => format!("`'{}`", self.interner.unintern(name)),
+            Region::Elided(index) => format!("the lifetime of `{}`", self.signature.names[index]),
+        }
+    }
+}
+
+/// The lifetime parameters in a list of generic parameters.
+fn lifetime_params(params: &[Param]) -> Vec<(StrId, Span)> {
+    params
+        .iter()
+        .filter_map(|param| match &param.body {
+            Try::Success(ParamBody::Lifetime(LifetimeParam {
+                lifetime:
+                    lifetime @ Lifetime {
+                        ident: Try::Success(ident),
+                        ..
+                    },
+                ..
+            })) => Some((ident.str_id(), lifetime.span())),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The lifetime parameters in a list of generic parameters paired with each lifetime they're declared to outlive.
+fn lifetime_bounds<'a>(params: impl Iterator<Item = &'a Param>) -> Vec<(StrId, &'a Lifetime)> {
+    params
+        .filter_map(|param| match &param.body {
+            Try::Success(ParamBody::Lifetime(LifetimeParam {
+                lifetime,
+                bounds: Some(bounds),
+            })) => Some((lifetime.str_id()?, bounds)),
+            _ => None,
+        })
+        .flat_map(|(name, bounds)| bounds.lifetimes().map(move |bound| (name, bound)))
+        .collect()
+}
+
+fn add_region(regions: &mut Vec<Region>, region: Region) {
+    if !regions.contains(&region) {
+        regions.push(region);
+    }
+}
@@ -0,0 +1,152 @@
+use super::*;
+
+/// Collects where each name is used in a block.
+///
+/// Assigning to a whole local isn't a use of it, since the value it had before doesn't matter.
+pub(crate) fn uses_in_block(block: &Block, output: &mut HashMap<StrId, Vec<Span>>) {
+    for stmt in &block.stmts {
+        match stmt {
+            Stmt::Item(_) => {}
+            Stmt::Let(let_) => {
+                if let Some(assign) = &let_.value {
+                    uses_in_expr(&assign.value, output);
+                }
+            }
+            Stmt::Eval(ControlStmt { value: Some(value), .. })
+            | Stmt::Return(ControlStmt { value: Some(value), .. })
+            | Stmt::Break(BreakStmt { value: Some(value), .. }) => uses_in_expr(value, output),
+            Stmt::Eval(_) | Stmt::Return(_) | Stmt::Break(_) | Stmt::Continue(_) => {}
+            // The fact of an `assume` isn't evaluated at runtime, so it doesn't use anything.
+            Stmt::Assume(_) => {}
+            Stmt::Expr(stmt) => {
+                let assigns_local = stmt.set.is_some()
+                    && stmt
+                        .expr
+                        .tree()
+                        .is_some_and(|tree| tree.as_unary().and_then(UnaryExprRef::as_ident).is_some());
+                if !assigns_local {
+                    uses_in_expr(&stmt.expr, output);
+                }
+
+                if let Some(set) = &stmt.set {
+                    uses_in_expr(&set.value, output);
+                }
+            }
+        }
+    }
+}
+
+fn uses_in_expr(expr: &impl ToExprTree, output: &mut HashMap<StrId, Vec<Span>>) {
+    if let Some(tree) = expr.tree() {
+        uses_in_tree(&tree, output);
+    }
+}
+
+fn uses_in_tree(tree: &ExprTree, output: &mut HashMap<StrId, Vec<Span>>) {
+    match tree {
+        ExprTree::BinOp(lhs, _, rhs) => {
+            uses_in_tree(lhs, output);
+            uses_in_tree(rhs, output);
+        }
+        ExprTree::Unary(unary) => {
+            uses_in_core(unary.core, output);
+
+            for ext in &unary.exts {
+                match ext {
+                    UnaryExprExt::Member(_) | UnaryExprExt::Generics(_) | UnaryExprExt::Question(_) => {}
+                    UnaryExprExt::Call(tuple) => tuple.items.iter().for_each(|item| uses_in_expr(item, output)),
+                    UnaryExprExt::Index(array) => array.items.iter().for_each(|item| uses_in_expr(item, output)),
+                    UnaryExprExt::Construct(construct) => {
+                        for field in &construct.items {
+                            if let Try::Success(set) = &field.set {
+                                uses_in_expr(&set.value, output);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn uses_in_core(core: &ExprCore, output: &mut HashMap<StrId, Vec<Span>>) {
+    match core {
+        ExprCore::Ident(ident) => output.entry(ident.str_id()).or_default().push(ident.span()),
+        ExprCore::Keyword(ExprKeyword::Fn(fn_)) => {
+            if let Some(body) = &fn_.body {
+                uses_in_block(body, output);
+            }
+        }
+        ExprCore::Keyword(_) | ExprCore::Literal(_) => {}
+        ExprCore::Block(block) => uses_in_block(block, output),
+        ExprCore::Tuple(tuple) => tuple.items.iter().for_each(|item| uses_in_expr(item, output)),
+        ExprCore::Array(array) => {
+            array.items.iter().for_each(|item| uses_in_expr(item, output));
+            if let Some(len) = &array.len {
+                uses_in_expr(&len.len, output);
+            }
+        }
+        ExprCore::If(if_) => {
+            uses_in_expr(&if_.condition, output);
+
+            let else_ = match &if_.body {
+                IfBody::Then(then) => {
+                    uses_in_expr(&then.expr, output);
+                    &then.else_
+                }
+                IfBody::Block(block) => {
+                    if let Try::Success(block) = &block.block {
+                        uses_in_block(block, output);
+                    }
+                    &block.else_
+                }
+            };
+            if let Some(else_) = else_ {
+                uses_in_expr(&else_.expr, output);
+            }
+        }
+        ExprCore::Loop(loop_) => {
+            if let Try::Success(block) = &loop_.block {
+                uses_in_block(block, output);
+            }
+        }
+        ExprCore::Try(try_) => {
+            if let Try::Success(block) = &try_.block {
+                uses_in_block(block, output);
+            }
+        }
+        ExprCore::While(while_) => {
+            uses_in_expr(&while_.condition, output);
+            if let Try::Success(block) = &while_.block {
+                uses_in_block(block, output);
+            }
+        }
+        ExprCore::Until(until) => {
+            uses_in_expr(&until.condition, output);
+            if let Try::Success(block) = &until.block {
+                uses_in_block(block, output);
+            }
+        }
+        ExprCore::For(for_) => {
+            uses_in_expr(&for_.iter, output);
+            if let Try::Success(block) = &for_.block {
+                uses_in_block(block, output);
+            }
+        }
+        ExprCore::Quantifier(quantifier) => {
+            uses_in_expr(&quantifier.iter, output);
+            if let Try::Success(block) = &quantifier.block {
+                uses_in_block(block, output);
+            }
+        }
+        ExprCore::Match(match_) => {
+            uses_in_expr(&match_.value, output);
+            for arm in match_.body.success_ref().into_iter().flat_map(|body| &body.arms) {
+                if let Some(guard) = &arm.guard {
+                    uses_in_expr(&guard.condition, output);
+                }
+                uses_in_expr(&arm.value, output);
+            }
+        }
+    }
+}
@@ -0,0 +1,302 @@
+use super::*;
+
+pub const DEFAULT_FUEL: u64 = 1_000_000;
+pub const MAX_DEPTH: usize = 128;
+
+/// An interpreter for compile-time code: generic arguments, `comptime` statics and calls to `comptime fn`s.
+///
+/// Every evaluation step burns fuel (refilled for each top-level evaluation), so infinite loops are reported instead of hanging the compiler,
+/// and re-entering an item with the same arguments is reported as a cycle.
+#[derive(Debug)]
+pub struct Comptime<'ctx> {
+    pub(crate) namespace: &'ctx Namespace<'ctx>,
+    pub(crate) interner: &'ctx Interner,
+    pub(crate) diagnostics: &'ctx Diagnostics,
+    pub(crate) fuel: u64,
+    max_fuel: u64,
+    pub(crate) stack: Vec<Frame>,
+    pub(crate) scopes: Vec<HashMap<StrId, Local>>,
+    /// `None` if evaluating the static failed, so the failure is only reported once.
+    statics: HashMap<ItemId, Option<Value>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Frame {
+    Static(ItemId),
+    Call(Instance, Vec<Value>),
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Local {
+    pub value: Value,
+    pub mut_: bool,
+}
+
+/// Why evaluation stopped before producing a value.
+#[derive(Debug)]
+pub(crate) enum Interrupt {
+    Eval(Span, Value),
+    Return(Span, Value),
+    /// With the label of the loop or block it leaves, or `None` for the innermost loop.
+    Break(Span, Option<StrId>, Value),
+    Continue(Span, Option<StrId>),
+    Failure(Option<DiagnosticHandle>),
+}
+
+pub(crate) type Eval<T> = Result<T, Interrupt>;
+
+impl<'ctx> Comptime<'ctx> {
+    pub fn new(namespace: &'ctx Namespace<'ctx>, interner: &'ctx Interner, diagnostics: &'ctx Diagnostics) -> Self {
+        Self {
+            namespace,
+            interner,
+            diagnostics,
+            fuel: DEFAULT_FUEL,
+            max_fuel: DEFAULT_FUEL,
+            stack: Vec::new(),
+            scopes: Vec::new(),
+            statics: HashMap::new(),
+        }
+    }
+
+    pub fn with_fuel(mut self, fuel: u64) -> Self {
+        self.fuel = fuel;
+        self.max_fuel = fuel;
+        self
+    }
+
+    pub fn namespace(&self) -> &'ctx Namespace<'ctx> {
+        self.namespace
+    }
+
+    /// Evaluates an expression with only items and the given bindings in scope.
+    pub fn eval(&mut self, tree: &ExprTree, bindings: &[(StrId, Value)]) -> Try<Value> {
+        let outer_scopes = take(&mut self.scopes);
+        self.scopes.push(
+            bindings
+                .iter()
+                .map(|(name, value)| {
+                    let local = Local {
+                        value: value.clone(),
+                        mut_: false,
+                    };
+
+                    (*name, local)
+                })
+                .collect(),
+        );
+
+        self.fuel = self.max_fuel;
+        let value = self.eval_tree(tree);
+        self.scopes = outer_scopes;
+
+        self.finish(value)
+    }
+
+    /// Evaluates an expression that's expected to be a type.
+    pub fn eval_type(&mut self, tree: &ExprTree, bindings: &[(StrId, Value)]) -> Try<Type> {
+        match self.eval(tree, bindings) {
+            Try::Success(Value::Type(type_)) => Try::Success(type_),
+            Try::Success(value) => Try::Failure(Some(self.diagnostics.push_error(Error::Mismatch(
+                tree.span(),
+                "a type",
+                value.to_string_interned(self.interner),
+            )))),
+            Try::Failure(error) => Try::Failure(error),
+        }
+    }
+
+    pub fn call(&mut self, instance: &Instance, args: Vec<Value>, span: Span) -> Try<Value> {
+        self.fuel = self.max_fuel;
+        let value = self.call_fn(instance, args, span);
+
+        self.finish(value)
+    }
+
+    pub fn static_value(&mut self, item: ItemId, span: Span) -> Try<Value> {
+        self.fuel = self.max_fuel;
+        let value = self.eval_static(item, span);
+
+        self.finish(value)
+    }
+
+    /// Evaluates every `comptime static` in the namespace.
+    pub fn check_items(&mut self, errors: &mut Vec<DiagnosticHandle>) {
+        for (id, item) in self.namespace.items() {
+            let Try::Success(ItemCore::Static(static_)) = &item.core else {
+                continue;
+            };
+
+            if !item.is_comptime() {
+                continue;
+            }
+
+            if let Try::Failure(Some(error)) = self.static_value(id, static_.keyword.span()) {
+                errors.push(error);
+            }
+        }
+    }
+
+    pub(crate) fn burn(&mut self, span: Span) -> Eval<()> {
+        match self.fuel.checked_sub(1) {
+            Some(fuel) => {
+                self.fuel = fuel;
+                Ok(())
+            }
+            None => Err(self.error(Error::ComptimeFuel(span))),
+        }
+    }
+
+    pub(crate) fn error(&self, error: Error) -> Interrupt {
+        Interrupt::Failure(Some(self.diagnostics.push_error(error)))
+    }
+
+    pub(crate) fn eval_static(&mut self, item: ItemId, span: Span) -> Eval<Value> {
+        match self.statics.get(&item) {
+            Some(Some(value)) => return Ok(value.clone()),
+            Some(None) => return Err(Interrupt::Failure(None)),
+            None => {}
+        }
+
+        let ast = self.namespace.item(item);
+        let name = ast.core.success_ref().and_then(ItemCore::ident).map(|ident| ident.str_id());
+        let (Try::Success(ItemCore::Static(static_)), Some(name)) = (&ast.core, name) else {
+            return Err(Interrupt::Failure(None));
+        };
+
+        if !ast.is_comptime() {
+            return Err(self.error(Error::NotComptime(span, name)));
+        }
+
+        if self.stack.contains(&Frame::Static(item)) {
+            return Err(self.error(Error::ComptimeCycle(span, name)));
+        }
+
+        let Some(assign) = &static_.value else {
+            return Err(self.error(Error::UninitVariable(static_.keyword.span())));
+        };
+        let Some(tree) = assign.value.tree() else {
+            return Err(Interrupt::Failure(None));
+        };
+
+        self.stack.push(Frame::Static(item));
+        let outer_scopes = take(&mut self.scopes);
+
+        let value = self.eval_tree(&tree).and_then(|value| {
+            let param = static_.param.success_ref();
+            match param
+                .and_then(|param| param.type_.as_ref())
+                .and_then(AngleUnaryExpr::as_ref_expr)
+            {
+                Some(type_) => {
+                    let type_ = self.eval_unary_type(&type_)?;
+                    self.expect_type(&value, &type_, tree.span())?;
+
+                    Ok(value)
+                }
+                None => Ok(value),
+            }
+        });
+
+        self.scopes = outer_scopes;
+        self.stack.pop();
+
+        self.statics.insert(item, value.as_ref().ok().cloned());
+
+        value
+    }
+
+    /// Checks that a value is of a type. A function is of a function type if its own type is the same.
+    ///
+    /// A function that's only not of the type because of a bound on one of its parameters is reported with the bound.
+    pub(crate) fn expect_type(&mut self, value: &Value, type_: &Type, span: Span) -> Eval<()> {
+        let is_of = match (value, type_) {
+            (Value::Fn(instance), Type::Fn(..)) => {
+                let fn_type = self.fn_type(instance, span)?;
+                if fn_type != *type_
+                    && let Some((param, bound)) = self.unguaranteed_bound(instance, &fn_type, type_)
+                {
+                    return Err(self.error(Error::FnTypeBound(
+                        span,
+                        instance.name,
+                        format!("{}: {}", Interned(&param, self.interner), Interned(bound, self.interner)),
+                        type_.to_string_interned(self.interner),
+                    )));
+                }
+
+                fn_type == *type_
+            }
+            _ => value.is_of(type_),
+        };
+
+        if is_of {
+            Ok(())
+        } else {
+            Err(self.error(Error::TypeMismatch(
+                span,
+                type_.to_string_interned(self.interner),
+                value.to_string_interned(self.interner),
+            )))
+        }
+    }
+
+    /// The first parameter of a function with a bound that a function type doesn't have,
+    /// if that's the only way the function's type differs from it.
+    fn unguaranteed_bound<'a>(&self, instance: &Instance, fn_type: &'a Type, type_: &Type) -> Option<(StrId, &'a Bound)> {
+        let (Type::Fn(inputs, output), Type::Fn(expected_inputs, expected_output)) = (fn_type, type_) else {
+            return None;
+        };
+        let unrefined = |input: &'a Type| match input {
+            Type::Refined(base, _) => &**base,
+            input => input,
+        };
+        if output != expected_output
+            || inputs.len() != expected_inputs.len()
+            || inputs
+                .iter()
+                .zip(expected_inputs)
+                .any(|(input, expected)| unrefined(input) != expected)
+        {
+            return None;
+        }
+
+        let Try::Success(ItemCore::Fn(Fn {
+            input: Try::Success(params),
+            ..
+        })) = &self.namespace.item(instance.item).core
+        else {
+            return None;
+        };
+
+        params
+            .items
+            .iter()
+            .zip(inputs)
+            .zip(expected_inputs)
+            .find_map(|((param, input), expected)| match input {
+                Type::Refined(_, bound) if input != expected => Some((param_ident(param)?.str_id(), bound)),
+                _ => None,
+            })
+    }
+
+    fn finish(&self, value: Eval<Value>) -> Try<Value> {
+        match value {
+            Ok(value) => Try::Success(value),
+            Err(Interrupt::Failure(error)) => Try::Failure(error),
+            Err(Interrupt::Eval(span, _)) => {
+                Try::Failure(Some(self.diagnostics.push_error(Error::Misplaced(span, "eval", "a block"))))
+            }
+            Err(Interrupt::Return(span, _)) => Try::Failure(Some(self.diagnostics.push_error(Error::Misplaced(
+                span,
+                "return",
+                "a function",
+            )))),
+            Err(Interrupt::Break(span, ..)) => {
+                Try::Failure(Some(self.diagnostics.push_error(Error::Misplaced(span, "break", "a loop"))))
+            }
+            Err(Interrupt::Continue(span, _)) => Try::Failure(Some(
+                self.diagnostics.push_error(Error::Misplaced(span, "continue", "a loop")),
+            )),
+        }
+    }
+}
@@ -0,0 +1,306 @@
+use super::*;
+
+impl<'ctx> Comptime<'ctx> {
+    pub(crate) fn eval_block(&mut self, block: &Block) -> Eval<Value> {
+        self.scopes.push(HashMap::new());
+        let value = block.stmts.iter().try_for_each(|stmt| self.eval_stmt(stmt));
+        self.scopes.pop();
+
+        match value {
+            Ok(()) => Ok(Value::UNIT),
+            Err(Interrupt::Eval(_, value)) => Ok(value),
+            Err(interrupt) => Err(interrupt),
+        }
+    }
+
+    fn eval_stmt(&mut self, stmt: &Stmt) -> Eval<()> {
+        match stmt {
+            Stmt::Item(item) => match item.core.success_ref().and_then(ItemCore::ident) {
+                Some(ident) => Err(self.error(Error::ComptimeUnsupported(ident.span, "local items"))),
+                None => Err(Interrupt::Failure(None)),
+            },
+            Stmt::Let(let_) => {
+                let Try::Success(param) = &let_.name else {
+                    return Err(Interrupt::Failure(None));
+                };
+                let Some(assign) = &let_.value else {
+                    return Err(self.error(Error::UninitVariable(let_.keyword.span())));
+                };
+
+                let value = self.eval_tree_of(&assign.value)?;
+                let span = assign.value.option_span().unwrap_or(assign.eq.span());
+
+                self.bind_param(param, value, span)
+            }
+            Stmt::Eval(stmt) => {
+                let value = self.eval_value(stmt.value.as_ref())?;
+                Err(Interrupt::Eval(stmt.keyword.span(), value))
+            }
+            Stmt::Return(stmt) => {
+                let value = self.eval_value(stmt.value.as_ref())?;
+                Err(Interrupt::Return(stmt.keyword.span(), value))
+            }
+            Stmt::Break(stmt) => {
+                let value = self.eval_value(stmt.value.as_ref())?;
+                let label = stmt.label.as_ref().and_then(Lifetime::str_id);
+                Err(Interrupt::Break(stmt.keyword.span(), label, value))
+            }
+            Stmt::Continue(stmt) => {
+                let label = stmt.label.as_ref().and_then(Lifetime::str_id);
+                Err(Interrupt::Continue(stmt.keyword.span(), label))
+            }
+            // The fact of an `assume` is only for the verifier.
+            Stmt::Assume(_) => Ok(()),
+            Stmt::Expr(stmt) => match &stmt.set {
+                Some(set) => {
+                    let value = self.eval_tree_of(&set.value)?;
+                    self.assign(&stmt.expr, value)
+                }
+                None => self.eval_tree_of(&stmt.expr).map(|_| ()),
+            },
+        }
+    }
+
+    /// The value of an `eval`, `return` or `break`, which is `()` when it's left out.
+    fn eval_value(&mut self, value: Option<&Expr>) -> Eval<Value> {
+        match value {
+            Some(value) => self.eval_tree_of(value),
+            None => Ok(Value::UNIT),
+        }
+    }
+
+    /// Assigns to a local, or to a member or an item of one.
+    fn assign(&mut self, place: &Expr, value: Value) -> Eval<()> {
+        let Some(tree) = place.tree() else {
+            return Err(Interrupt::Failure(None));
+        };
+        let Some(unary) = tree.as_unary().filter(|unary| unary.prefixes.is_empty()) else {
+            return Err(self.error(Error::Expected(tree.span(), "an assignable place")));
+        };
+        let ExprCore::Ident(ident) = unary.core else {
+            return Err(self.error(Error::Expected(unary.span, "an assignable place")));
+        };
+
+        let mut path = Vec::with_capacity(unary.exts.len());
+        for ext in &unary.exts {
+            path.push(match ext {
+                UnaryExprExt::Member(member) => match &member.member {
+                    Try::Success(member) => Place::Member(member),
+                    Try::Failure(_) => return Err(Interrupt::Failure(None)),
+                },
+                UnaryExprExt::Index(index) => {
+                    let [index] = index.items.as_slice() else {
+                        return Err(self.error(Error::Expected(index.span(), "a single index")));
+                    };
+
+                    match self.eval_tree_of(index)? {
+                        Value::Int(value) => Place::Index(value, index.option_span().unwrap_or(ident.span)),
+                        value => return Err(self.mismatch(index.option_span().unwrap_or(ident.span), "an integer", &value)),
+                    }
+                }
+                ext => return Err(self.error(Error::Expected(ext.span(), "an assignable place"))),
+            });
+        }
+
+        let name = ident.str_id();
+        let Some(local) = self.scopes.iter_mut().rev().find_map(|scope| scope.get_mut(&name)) else {
+            return Err(self.error(Error::DoesntExist(ident.span, name)));
+        };
+        if !local.mut_ {
+            return Err(self.error(Error::Immutable(ident.span, name)));
+        }
+
+        let mut target = &mut local.value;
+        for place in path {
+            target = match (target, place) {
+                (Value::Struct(_, fields), Place::Member(Member::Named(field))) => {
+                    match fields.iter_mut().find(|(name, _)| *name == field.str_id()) {
+                        Some((_, value)) => value,
+                        None => return Err(self.error(Error::NoField(field.span, "this struct".to_string(), field.str_id()))),
+                    }
+                }
+                (Value::Tuple(values), Place::Member(Member::Unnamed(index))) => {
+                    let len = values.len();
+                    match index.value {
+                        Try::Success(value) if (value as usize) < len => &mut values[value as usize],
+                        Try::Success(value) => {
+                            return Err(self.error(Error::ComptimeIndexOutOfBounds(index.span, value as i128, len)));
+                        }
+                        Try::Failure(_) => return Err(Interrupt::Failure(None)),
+                    }
+                }
+                (Value::Array(values), Place::Index(index, span)) => {
+                    let len = values.len();
+                    match usize::try_from(index) {
+                        Ok(index) if index < len => &mut values[index],
+                        _ => return Err(self.error(Error::ComptimeIndexOutOfBounds(span, index, len))),
+                    }
+                }
+                (_, Place::Member(member)) => return Err(self.error(Error::Expected(member.span(), "a field"))),
+                (_, Place::Index(_, span)) => return Err(self.error(Error::Expected(span, "an array"))),
+            };
+        }
+
+        *target = value;
+        Ok(())
+    }
+
+    pub(crate) fn eval_if(&mut self, if_: &If) -> Eval<Value> {
+        let condition = self.eval_condition(&if_.condition, if_.keyword.span())?;
+
+        match &if_.body {
+            IfBody::Then(then) => {
+                if condition {
+                    self.eval_tree_of(&then.expr)
+                } else {
+                    match &then.else_ {
+                        Some(else_) => self.eval_tree_of(&else_.expr),
+                        None => Ok(Value::UNIT),
+                    }
+                }
+            }
+            IfBody::Block(block) => {
+                if condition {
+                    match &block.block {
+                        Try::Success(block) => self.eval_block(block),
+                        Try::Failure(_) => Err(Interrupt::Failure(None)),
+                    }
+                } else {
+                    match &block.else_ {
+                        Some(else_) => self.eval_tree_of(&else_.expr),
+                        None => Ok(Value::UNIT),
+                    }
+                }
+            }
+        }
+    }
+
+    pub(crate) fn eval_loop(&mut self, loop_: &Loop, label: Option<StrId>) -> Eval<Value> {
+        let Try::Success(block) = &loop_.block else {
+            return Err(Interrupt::Failure(None));
+        };
+
+        loop {
+            self.burn(loop_.keyword.span())?;
+
+            if let Some(output) = loop_exit(self.eval_block(block), label) {
+                return output;
+            }
+        }
+    }
+
+    pub(crate) fn eval_while(&mut self, while_: &While, label: Option<StrId>) -> Eval<Value> {
+        self.eval_conditional_loop(&while_.condition, &while_.block, while_.keyword.span(), true, label)
+    }
+
+    pub(crate) fn eval_until(&mut self, until: &Until, label: Option<StrId>) -> Eval<Value> {
+        self.eval_conditional_loop(&until.condition, &until.block, until.keyword.span(), false, label)
+    }
+
+    pub(crate) fn eval_for(&mut self, for_: &For, label: Option<StrId>) -> Eval<Value> {
+        let (Try::Success(item), Try::Success(block)) = (&for_.item, &for_.block) else {
+            return Err(Interrupt::Failure(None));
+        };
+
+        let span = for_.iter.option_span().unwrap_or(for_.keyword.span());
+        let values: Box<dyn Iterator<Item = Value>> = match self.eval_tree_of(&for_.iter)? {
+            Value::Array(values) => Box::new(values.into_iter()),
+            Value::Range(start, end) => Box::new((start..end).map(Value::Int)),
+            value => return Err(self.mismatch(span, "an array or a range", &value)),
+        };
+
+        for value in values {
+            self.burn(for_.keyword.span())?;
+
+            self.scopes.push(HashMap::new());
+            let output = self.bind_param(item, value, span).and_then(|()| self.eval_block(block));
+            self.scopes.pop();
+
+            if let Some(output) = loop_exit(output, label) {
+                return output;
+            }
+        }
+
+        Ok(Value::UNIT)
+    }
+
+    /// Evaluates `all` and `any`, which stop at the first item that decides them.
+    pub(crate) fn eval_quantifier(&mut self, quantifier: &Quantifier) -> Eval<Value> {
+        let (Try::Success(item), Try::Success(block)) = (&quantifier.item, &quantifier.block) else {
+            return Err(Interrupt::Failure(None));
+        };
+
+        let span = quantifier.iter.option_span().unwrap_or(quantifier.keyword.span());
+        let values: Box<dyn Iterator<Item = Value>> = match self.eval_tree_of(&quantifier.iter)? {
+            Value::Array(values) => Box::new(values.into_iter()),
+            Value::Range(start, end) => Box::new((start..end).map(Value::Int)),
+            value => return Err(self.mismatch(span, "an array or a range", &value)),
+        };
+
+        let all = matches!(quantifier.keyword, QuantifierKeyword::All(_));
+        for value in values {
+            self.burn(quantifier.keyword.span())?;
+
+            self.scopes.push(HashMap::new());
+            let output = self.bind_param(item, value, span).and_then(|()| self.eval_block(block));
+            self.scopes.pop();
+
+            match output? {
+                Value::Bool(holds) if holds != all => return Ok(Value::Bool(!all)),
+                Value::Bool(_) => {}
+                value => return Err(self.mismatch(block.span(), "a bool", &value)),
+            }
+        }
+
+        Ok(Value::Bool(all))
+    }
+
+    fn eval_conditional_loop(
+        &mut self,
+        condition: &Try<Box<BraceExpr>>,
+        block: &Try<Block>,
+        span: Span,
+        continue_if: bool,
+        label: Option<StrId>,
+    ) -> Eval<Value> {
+        let Try::Success(block) = block else {
+            return Err(Interrupt::Failure(None));
+        };
+
+        while self.eval_condition(condition, span)? == continue_if {
+            self.burn(span)?;
+
+            if let Some(output) = loop_exit(self.eval_block(block), label) {
+                return output;
+            }
+        }
+
+        Ok(Value::UNIT)
+    }
+
+    fn eval_condition(&mut self, condition: &Try<Box<BraceExpr>>, span: Span) -> Eval<bool> {
+        match self.eval_tree_of(condition)? {
+            Value::Bool(value) => Ok(value),
+            value => Err(self.mismatch(condition.option_span().unwrap_or(span), "a bool", &value)),
+        }
+    }
+}
+
+enum Place<'a> {
+    Member(&'a Member),
+    Index(i128, Span),
+}
+
+/// What a loop labeled `label` does after an iteration that ended with `output`:
+/// `None` to run again, or what the loop evaluates to.
+fn loop_exit(output: Eval<Value>, label: Option<StrId>) -> Option<Eval<Value>> {
+    // A `break` or a `continue` without a label is for the innermost loop.
+    let targets = |target: Option<StrId>| target.is_none() || target == label;
+
+    match output {
+        Ok(_) => None,
+        Err(Interrupt::Continue(_, target)) if targets(target) => None,
+        Err(Interrupt::Break(_, target, value)) if targets(target) => Some(Ok(value)),
+        Err(interrupt) => Some(Err(interrupt)),
+    }
+}
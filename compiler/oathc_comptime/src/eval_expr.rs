@@ -0,0 +1,650 @@
+use super::*;
+
+impl<'ctx> Comptime<'ctx> {
+    pub(crate) fn eval_tree(&mut self, tree: &ExprTree) -> Eval<Value> {
+        match tree {
+            ExprTree::Unary(unary) => self.eval_unary(unary),
+            ExprTree::BinOp(_, BinOp::Is(_), _) => Err(self.error(Error::ComptimeUnsupported(tree.span(), "`is`"))),
+            ExprTree::BinOp(lhs, op, rhs) => {
+                let lhs = self.eval_tree(lhs)?;
+                let rhs = self.eval_tree(rhs)?;
+
+                self.eval_bin_op(lhs, *op, rhs, tree.span())
+            }
+        }
+    }
+
+    pub(crate) fn eval_unary(&mut self, unary: &UnaryExprRef) -> Eval<Value> {
+        self.burn(unary.span)?;
+
+        let mut span = unary.core.span();
+        let mut value = self.eval_core(unary.core, unary.label.and_then(Lifetime::str_id))?;
+
+        for ext in &unary.exts {
+            value = self.eval_ext(value, ext, span)?;
+            span = span.connect(ext.span());
+        }
+
+        for prefix in unary.prefixes.iter().rev() {
+            span = prefix.span().connect(span);
+            value = self.eval_prefix(prefix, value, span)?;
+        }
+
+        Ok(value)
+    }
+
+    pub(crate) fn eval_unary_type(&mut self, unary: &UnaryExprRef) -> Eval<Type> {
+        match self.eval_unary(unary)? {
+            Value::Type(type_) => Ok(type_),
+            value => Err(self.mismatch(unary.span, "a type", &value)),
+        }
+    }
+
+    pub(crate) fn eval_tree_of(&mut self, expr: &impl ToExprTree) -> Eval<Value> {
+        match expr.tree() {
+            Some(tree) => self.eval_tree(&tree),
+            None => Err(Interrupt::Failure(None)),
+        }
+    }
+
+    pub(crate) fn mismatch(&self, span: Span, expected: &'static str, found: &Value) -> Interrupt {
+        self.error(Error::Mismatch(span, expected, found.to_string_interned(self.interner)))
+    }
+
+    /// Evaluates the core of a unary expression, which is labeled `label` if it's a loop or a block.
+    fn eval_core(&mut self, core: &ExprCore, label: Option<StrId>) -> Eval<Value> {
+        match core {
+            ExprCore::Ident(ident) => self.eval_ident(*ident),
+            ExprCore::Keyword(ExprKeyword::Type(_)) => Ok(Value::Type(Type::Type)),
+            ExprCore::Keyword(ExprKeyword::Fn(fn_)) => match fn_.body {
+                Some(_) => Err(self.error(Error::ComptimeUnsupported(fn_.span(), "closures"))),
+                None => {
+                    let span = fn_.span();
+                    self.eval_fn_type(&fn_.input, fn_.output.as_deref(), span).map(Value::Type)
+                }
+            },
+            ExprCore::Keyword(ExprKeyword::Out(keyword)) => Err(self.error(Error::ComptimeUnsupported(keyword.span(), "`out`"))),
+            ExprCore::Literal(Literal::Int(literal)) => match literal.value {
+                Try::Success(value) => i128::try_from(value)
+                    .map(Value::Int)
+                    .map_err(|_| self.error(Error::ComptimeOverflow(literal.span))),
+                Try::Failure(_) => Err(Interrupt::Failure(None)),
+            },
+            ExprCore::Literal(literal) => Err(self.error(Error::ComptimeUnsupported(literal.span(), "this literal"))),
+            ExprCore::Block(block) => match self.eval_block(block) {
+                Err(Interrupt::Break(_, Some(target), value)) if Some(target) == label => Ok(value),
+                output => output,
+            },
+            ExprCore::Tuple(tuple) => {
+                let mut values = tuple
+                    .items
+                    .iter()
+                    .map(|item| self.eval_tree_of(item))
+                    .collect::<Eval<Vec<_>>>()?;
+
+                if values.len() == 1 {
+                    Ok(values.pop().unwrap())
+                } else if values.iter().all(|value| matches!(value, Value::Type(_))) && !values.is_empty() {
+                    Ok(Value::Type(Type::Tuple(
+                        values
+                            .into_iter()
+                            .map(|value| match value {
+                                Value::Type(type_) => type_,
+                                _ => unreachable!(),
+                            })
+                            .collect(),
+                    )))
+                } else {
+                    Ok(Value::Tuple(values))
+                }
+            }
+            ExprCore::Array(array) => self.eval_array(array),
+            ExprCore::If(if_) => self.eval_if(if_),
+            ExprCore::Loop(loop_) => self.eval_loop(loop_, label),
+            ExprCore::While(while_) => self.eval_while(while_, label),
+            ExprCore::Until(until) => self.eval_until(until, label),
+            ExprCore::For(for_) => self.eval_for(for_, label),
+            ExprCore::Quantifier(quantifier) => self.eval_quantifier(quantifier),
+            ExprCore::Match(match_) => self.eval_match(match_),
+            ExprCore::Try(try_) => Err(self.error(Error::ComptimeUnsupported(try_.keyword.span(), "`try`"))),
+        }
+    }
+
+    fn eval_array(&mut self, array: &Array) -> Eval<Value> {
+        let Some(len) = &array.len else {
+            return Ok(Value::Array(
+                array.items.iter().map(|item| self.eval_tree_of(item)).collect::<Eval<_>>()?,
+            ));
+        };
+
+        let len_span = len.len.option_span().unwrap_or(len.semi.span());
+        let len = match self.eval_tree_of(&len.len)? {
+            Value::Int(len) => usize::try_from(len).map_err(|_| self.error(Error::ComptimeOverflow(len_span)))?,
+            value => return Err(self.mismatch(len_span, "an array length", &value)),
+        };
+
+        let [item] = array.items.as_slice() else {
+            return Err(self.error(Error::Expected(array.span(), "a single array item before `;`")));
+        };
+
+        match self.eval_tree_of(item)? {
+            Value::Type(item_type) => Ok(Value::Type(Type::Array(Box::new(item_type), len))),
+            value => Ok(Value::Array(vec![value; len])),
+        }
+    }
+
+    pub(crate) fn eval_ident(&mut self, ident: Ident) -> Eval<Value> {
+        let name = ident.str_id();
+
+        if let Some(local) = self.scopes.iter().rev().find_map(|scope| scope.get(&name)) {
+            return Ok(local.value.clone());
+        }
+
+        let name_str = self.interner.unintern(name);
+        match name_str.as_str() {
+            "true" => return Ok(Value::Bool(true)),
+            "false" => return Ok(Value::Bool(false)),
+            _ => {}
+        }
+        if let Some(type_) = Type::from_name(&name_str) {
+            return Ok(Value::Type(type_));
+        }
+
+        let Some(item) = self.namespace.get(name) else {
+            return Err(self.error(Error::DoesntExist(ident.span, name)));
+        };
+
+        let instance = Instance {
+            item,
+            name,
+            args: Vec::new(),
+        };
+
+        match &self.namespace.item(item).core {
+            Try::Success(ItemCore::Fn(_)) => Ok(Value::Fn(instance)),
+            Try::Success(ItemCore::Struct(_)) => Ok(Value::Type(Type::Struct(instance))),
+            Try::Success(ItemCore::Enum(_)) => Ok(Value::Type(Type::Enum(instance))),
+            Try::Success(ItemCore::Static(_)) => self.eval_static(item, ident.span),
+            Try::Success(ItemCore::Trait(_)) => Ok(Value::Bound(Bound::Impl(instance))),
+            _ => Err(self.error(Error::NotComptime(ident.span, name))),
+        }
+    }
+
+    fn eval_ext(&mut self, value: Value, ext: &UnaryExprExt, span: Span) -> Eval<Value> {
+        match ext {
+            UnaryExprExt::Member(member) => {
+                let Try::Success(member) = &member.member else {
+                    return Err(Interrupt::Failure(None));
+                };
+
+                match (value, member) {
+                    (Value::Struct(_, fields), Member::Named(name))
+                        if fields.iter().any(|(field, _)| *field == name.str_id()) =>
+                    {
+                        Ok(fields.into_iter().find(|(field, _)| *field == name.str_id()).unwrap().1)
+                    }
+                    (Value::Tuple(mut values), Member::Unnamed(index)) => match index.value {
+                        Try::Success(index_value) if (index_value as usize) < values.len() => {
+                            Ok(values.swap_remove(index_value as usize))
+                        }
+                        Try::Success(index_value) => {
+                            Err(self.error(Error::ComptimeIndexOutOfBounds(index.span, index_value as i128, values.len())))
+                        }
+                        Try::Failure(_) => Err(Interrupt::Failure(None)),
+                    },
+                    (value, Member::Named(name)) => Err(self.error(Error::NoField(
+                        name.span,
+                        value.to_string_interned(self.interner),
+                        name.str_id(),
+                    ))),
+                    (value, Member::Unnamed(index)) => Err(self.mismatch(index.span, "a tuple", &value)),
+                }
+            }
+            UnaryExprExt::Call(tuple) => {
+                let args = tuple
+                    .items
+                    .iter()
+                    .map(|item| self.eval_tree_of(item))
+                    .collect::<Eval<Vec<_>>>()?;
+
+                match value {
+                    Value::Fn(instance) => self.call_fn(&instance, args, span.connect(tuple.span())),
+                    value => Err(self.mismatch(span, "a function", &value)),
+                }
+            }
+            UnaryExprExt::Index(index) => {
+                let [index_expr] = index.items.as_slice() else {
+                    return Err(self.error(Error::Expected(index.span(), "a single index")));
+                };
+
+                let index_value = match self.eval_tree_of(index_expr)? {
+                    Value::Int(index_value) => index_value,
+                    index_value => return Err(self.mismatch(index.span(), "an integer", &index_value)),
+                };
+
+                match value {
+                    Value::Array(mut values) => match usize::try_from(index_value) {
+                        Ok(index_value) if index_value < values.len() => Ok(values.swap_remove(index_value)),
+                        _ => Err(self.error(Error::ComptimeIndexOutOfBounds(index.span(), index_value, values.len()))),
+                    },
+                    value => Err(self.mismatch(span, "an array", &value)),
+                }
+            }
+            UnaryExprExt::Generics(generics) => {
+                let args = generics
+                    .args
+                    .iter()
+                    .map(|arg| self.eval_tree_of(arg))
+                    .collect::<Eval<Vec<_>>>()?;
+
+                let span = span.connect(generics.span());
+                match value {
+                    Value::Fn(instance) if instance.args.is_empty() => Ok(Value::Fn(self.instantiate(instance, args, span)?)),
+                    Value::Type(Type::Struct(instance)) if instance.args.is_empty() => {
+                        Ok(Value::Type(Type::Struct(self.instantiate(instance, args, span)?)))
+                    }
+                    Value::Type(Type::Enum(instance)) if instance.args.is_empty() => {
+                        Ok(Value::Type(Type::Enum(self.instantiate(instance, args, span)?)))
+                    }
+                    Value::Bound(Bound::Impl(instance)) if instance.args.is_empty() => {
+                        Ok(Value::Bound(Bound::Impl(self.instantiate(instance, args, span)?)))
+                    }
+                    value => Err(self.mismatch(span, "a generic item", &value)),
+                }
+            }
+            UnaryExprExt::Construct(construct) => match value {
+                Value::Type(Type::Struct(instance)) => self.construct(instance, construct),
+                value => Err(self.mismatch(span, "a struct type", &value)),
+            },
+            UnaryExprExt::Question(question) => Err(self.error(Error::ComptimeUnsupported(question.span(), "`?`"))),
+        }
+    }
+
+    fn eval_prefix(&mut self, prefix: &UnOp, value: Value, span: Span) -> Eval<Value> {
+        if let Some(op) = CmpOp::from_un_op(prefix) {
+            return Ok(Value::Bound(Bound::Cmp(op, Box::new(value))));
+        }
+
+        match (prefix, value) {
+            (UnOp::Not(_), Value::Bool(value)) => Ok(Value::Bool(!value)),
+            (UnOp::Not(_), Value::Int(value)) => Ok(Value::Int(!value)),
+            (UnOp::Not(_), Value::Bound(bound)) => Ok(Value::Bound(Bound::Not(Box::new(bound)))),
+            (UnOp::Not(_), value) => Err(self.mismatch(span, "a bool", &value)),
+            (UnOp::Neg(_), Value::Int(value)) => value
+                .checked_neg()
+                .map(Value::Int)
+                .ok_or_else(|| self.error(Error::ComptimeOverflow(span))),
+            (UnOp::Neg(_), value) => Err(self.mismatch(span, "an integer", &value)),
+            (UnOp::Ref(_), _) => Err(self.error(Error::ComptimeUnsupported(span, "references"))),
+            (UnOp::Deref(_), _) => Err(self.error(Error::ComptimeUnsupported(span, "dereferences"))),
+            (UnOp::Lifetime(_), _) => Err(self.error(Error::ComptimeUnsupported(span, "lifetimes"))),
+            (UnOp::RangeExclusive(_) | UnOp::RangeInclusive(_), _) => Err(self.error(Error::ComptimeUnsupported(span, "ranges"))),
+            (UnOp::Eq(_) | UnOp::NotEq(_) | UnOp::More(_) | UnOp::Less(_) | UnOp::MoreEq(_) | UnOp::LessEq(_), _) => {
+                unreachable!()
+            }
+        }
+    }
+
+    fn eval_bin_op(&mut self, lhs: Value, op: BinOp, rhs: Value, span: Span) -> Eval<Value> {
+        match (lhs, op, rhs) {
+            (Value::Type(type_), BinOp::Bound(_), Value::Bound(bound)) => Ok(Value::Type(Type::Refined(Box::new(type_), bound))),
+            (lhs, BinOp::Bound(_), Value::Bound(bound)) => match self.bound_holds(&bound, &lhs)? {
+                Some(holds) => Ok(Value::Bool(holds)),
+                None => Err(self.mismatch(span, "a comparable value", &lhs)),
+            },
+            (lhs, BinOp::Bound(_), Value::Type(type_)) => Ok(Value::Bool(lhs.is_of(&type_))),
+            (_, BinOp::Bound(_), rhs) => Err(self.mismatch(span, "a bound", &rhs)),
+
+            (Value::Int(start), BinOp::RangeExclusive(_), Value::Int(end)) => Ok(Value::Range(start, end)),
+            (Value::Int(start), BinOp::RangeInclusive(_), Value::Int(end)) => match end.checked_add(1) {
+                Some(end) => Ok(Value::Range(start, end)),
+                None => Err(self.error(Error::ComptimeOverflow(span))),
+            },
+            (_, BinOp::Is(_), _) => Err(self.error(Error::ComptimeUnsupported(span, "`is`"))),
+
+            (Value::Bool(lhs), BinOp::And(_), Value::Bool(rhs)) => Ok(Value::Bool(lhs & rhs)),
+            (Value::Bool(lhs), BinOp::Or(_), Value::Bool(rhs)) => Ok(Value::Bool(lhs | rhs)),
+            (Value::Bool(lhs), BinOp::Xor(_), Value::Bool(rhs)) => Ok(Value::Bool(lhs ^ rhs)),
+
+            (Value::Bound(lhs), BinOp::And(_), Value::Bound(rhs)) => Ok(Value::Bound(Bound::And(Box::new(lhs), Box::new(rhs)))),
+            (Value::Bound(lhs), BinOp::Or(_), Value::Bound(rhs)) => Ok(Value::Bound(Bound::Or(Box::new(lhs), Box::new(rhs)))),
+
+            (Value::Int(lhs), op, Value::Int(rhs)) => {
+                let value = match op {
+                    BinOp::Add(_) => lhs.checked_add(rhs),
+                    BinOp::Sub(_) => lhs.checked_sub(rhs),
+                    BinOp::Mul(_) => lhs.checked_mul(rhs),
+                    BinOp::WrappingAdd(_) => Some(lhs.wrapping_add(rhs)),
+                    BinOp::WrappingSub(_) => Some(lhs.wrapping_sub(rhs)),
+                    BinOp::WrappingMul(_) => Some(lhs.wrapping_mul(rhs)),
+                    BinOp::Div(_) | BinOp::Rem(_) if rhs == 0 => return Err(self.error(Error::ComptimeDivByZero(span))),
+                    BinOp::Div(_) => lhs.checked_div(rhs),
+                    BinOp::Rem(_) => lhs.checked_rem(rhs),
+                    BinOp::And(_) => Some(lhs & rhs),
+                    BinOp::Or(_) => Some(lhs | rhs),
+                    BinOp::Xor(_) => Some(lhs ^ rhs),
+                    BinOp::Shl(_) => u32::try_from(rhs).ok().and_then(|rhs| lhs.checked_shl(rhs)),
+                    BinOp::Shr(_) => u32::try_from(rhs).ok().and_then(|rhs| lhs.checked_shr(rhs)),
+                    BinOp::Bound(_) | BinOp::RangeExclusive(_) | BinOp::RangeInclusive(_) | BinOp::Is(_) => unreachable!(),
+                };
+
+                value.map(Value::Int).ok_or_else(|| self.error(Error::ComptimeOverflow(span)))
+            }
+
+            (Value::Int(_), _, rhs) => Err(self.mismatch(span, "an integer", &rhs)),
+            (Value::Bool(_), _, rhs) => Err(self.mismatch(span, "a bool", &rhs)),
+            (lhs, _, _) => Err(self.mismatch(span, "an integer or a bool", &lhs)),
+        }
+    }
+
+    /// Checks generic arguments against the item's generic parameters.
+    pub(crate) fn instantiate(&mut self, mut instance: Instance, args: Vec<Value>, span: Span) -> Eval<Instance> {
+        let params = generic_params(self.namespace.item(instance.item));
+        if params.len() != args.len() {
+            return Err(self.error(Error::ArgCount(span, params.len(), args.len())));
+        }
+
+        self.scopes.push(HashMap::new());
+        let bound = params
+            .iter()
+            .zip(&args)
+            .try_for_each(|(param, arg)| self.bind_generic(param, arg.clone(), span));
+        self.scopes.pop();
+        bound?;
+
+        instance.args = args;
+        Ok(instance)
+    }
+
+    /// Binds the parameter in the innermost scope, after checking the value against the parameter's type and bounds.
+    pub(crate) fn bind_param(&mut self, param: &Param, value: Value, span: Span) -> Eval<()> {
+        if let Some(type_) = param.type_.as_ref().and_then(AngleUnaryExpr::as_ref_expr) {
+            let type_ = self.eval_unary_type(&type_)?;
+            self.expect_type(&value, &type_, span)?;
+        }
+
+        if let Some(bounds) = &param.bounds {
+            match self.eval_tree_of(&bounds.expr)? {
+                Value::Bound(bound) => {
+                    if self.bound_holds(&bound, &value)? != Some(true) {
+                        return Err(self.error(Error::ComptimeBoundViolated(
+                            span,
+                            value.to_string_interned(self.interner),
+                            bound.to_string_interned(self.interner),
+                        )));
+                    }
+                }
+                Value::Type(type_) => self.expect_type(&value, &type_, span)?,
+                bound => return Err(self.mismatch(bounds.span(), "a bound", &bound)),
+            }
+        }
+
+        let mut_ = param.mut_.is_some();
+        match &param.body {
+            Try::Success(ParamBody::Pattern(pattern)) => {
+                let mut bindings = Vec::new();
+                if !self.match_pattern(pattern, &value, &mut bindings)? {
+                    let value = value.to_string_interned(self.interner);
+                    return Err(self.error(Error::ComptimePatternMismatch(pattern.span(), value)));
+                }
+
+                let scope = self.scopes.last_mut().unwrap();
+                for (name, value) in bindings {
+                    scope.insert(name, Local { value, mut_ });
+                }
+
+                Ok(())
+            }
+            Try::Success(ParamBody::Tuple(params)) => match value {
+                Value::Tuple(values) if values.len() == params.items.len() => params
+                    .items
+                    .iter()
+                    .zip(values)
+                    .try_for_each(|(param, value)| self.bind_param(param, value, span)),
+                value => Err(self.mismatch(span, "a tuple", &value)),
+            },
+            Try::Success(ParamBody::Lifetime(_)) => Ok(()),
+            Try::Failure(_) => Err(Interrupt::Failure(None)),
+        }
+    }
+
+    /// Generic parameters without a type are types.
+    fn bind_generic(&mut self, param: &Param, value: Value, span: Span) -> Eval<()> {
+        if param.type_.is_none() && !matches!(value, Value::Type(_)) {
+            return Err(self.mismatch(span, "a type", &value));
+        }
+
+        self.bind_param(param, value, span)
+    }
+
+    pub(crate) fn bind_generics(&mut self, instance: &Instance, span: Span) -> Eval<()> {
+        let params = generic_params(self.namespace.item(instance.item));
+        if params.len() != instance.args.len() {
+            return Err(self.error(Error::ArgCount(span, params.len(), instance.args.len())));
+        }
+
+        params
+            .iter()
+            .zip(&instance.args)
+            .try_for_each(|(param, arg)| self.bind_generic(param, arg.clone(), span))
+    }
+
+    pub(crate) fn call_fn(&mut self, instance: &Instance, args: Vec<Value>, span: Span) -> Eval<Value> {
+        let item = self.namespace.item(instance.item);
+        let Try::Success(ItemCore::Fn(fn_)) = &item.core else {
+            return Err(self.mismatch(span, "a function", &Value::Fn(instance.clone())));
+        };
+
+        if !item.is_comptime() {
+            return Err(self.error(Error::NotComptime(span, instance.name)));
+        }
+
+        let frame = Frame::Call(instance.clone(), args.clone());
+        if self.stack.contains(&frame) {
+            return Err(self.error(Error::ComptimeCycle(span, instance.name)));
+        }
+        if self.stack.len() >= MAX_DEPTH {
+            return Err(self.error(Error::ComptimeRecursionLimit(span, instance.name)));
+        }
+
+        self.burn(span)?;
+
+        self.stack.push(frame);
+        let outer_scopes = take(&mut self.scopes);
+        self.scopes.push(HashMap::new());
+
+        let value = self.call_fn_body(fn_, instance, args, span);
+
+        self.scopes = outer_scopes;
+        self.stack.pop();
+
+        value
+    }
+
+    fn call_fn_body(&mut self, fn_: &Fn, instance: &Instance, args: Vec<Value>, span: Span) -> Eval<Value> {
+        self.bind_generics(instance, span)?;
+
+        let Try::Success(input) = &fn_.input else {
+            return Err(Interrupt::Failure(None));
+        };
+        if input.items.len() != args.len() {
+            return Err(self.error(Error::ArgCount(span, input.items.len(), args.len())));
+        }
+
+        for (param, arg) in input.items.iter().zip(args) {
+            self.bind_param(param, arg, span)?;
+        }
+
+        let output_type = match &fn_.output {
+            Some(output) => match self.eval_tree_of(output)? {
+                Value::Type(type_) => Some(type_),
+                value => return Err(self.mismatch(output.option_span().unwrap_or(span), "a type", &value)),
+            },
+            None => None,
+        };
+
+        let Try::Success(FnBody::Block(body)) = &fn_.body else {
+            return Err(self.error(Error::NotComptime(span, instance.name)));
+        };
+
+        let value = match self.eval_block(body) {
+            Ok(value) | Err(Interrupt::Return(_, value)) => value,
+            Err(interrupt) => return Err(interrupt),
+        };
+
+        if let Some(output_type) = output_type {
+            self.expect_type(&value, &output_type, span)?;
+        }
+
+        Ok(value)
+    }
+
+    /// The type of a function instance, which is what it's checked against when it's given for a function type.
+    pub(crate) fn fn_type(&mut self, instance: &Instance, span: Span) -> Eval<Type> {
+        let Try::Success(ItemCore::Fn(fn_)) = &self.namespace.item(instance.item).core else {
+            return Err(self.mismatch(span, "a function", &Value::Fn(instance.clone())));
+        };
+
+        let outer_scopes = take(&mut self.scopes);
+        self.scopes.push(HashMap::new());
+
+        let type_ = self
+            .bind_generics(instance, span)
+            .and_then(|_| self.eval_fn_type(&fn_.input, fn_.output.as_ref(), span));
+
+        self.scopes = outer_scopes;
+
+        type_
+    }
+
+    /// The type of functions with the parameters and output. In function types, a parameter without a type can be
+    /// just the type, like in `fn(i32) bool`.
+    fn eval_fn_type(&mut self, input: &Try<FramedParams<delims!("( )")>>, output: Option<&BraceExpr>, span: Span) -> Eval<Type> {
+        let Try::Success(input) = input else {
+            return Err(Interrupt::Failure(None));
+        };
+
+        let mut inputs = Vec::new();
+        for param in &input.items {
+            let param_span = param.option_span().unwrap_or(span);
+            let mut type_ = match (&param.type_, param_ident(param)) {
+                (Some(type_), _) => match type_.as_ref_expr() {
+                    Some(type_) => self.eval_unary_type(&type_)?,
+                    None => return Err(Interrupt::Failure(None)),
+                },
+                (None, Some(ident)) => match self.eval_ident(ident)? {
+                    Value::Type(type_) => type_,
+                    value => return Err(self.mismatch(param_span, "a type", &value)),
+                },
+                (None, None) => return Err(self.error(Error::ComptimeUnsupported(param_span, "parameters without types"))),
+            };
+
+            if let Some(bounds) = &param.bounds {
+                match self.eval_tree_of(&bounds.expr)? {
+                    Value::Bound(bound) => type_ = Type::Refined(Box::new(type_), bound),
+                    bound => return Err(self.mismatch(bounds.span(), "a bound", &bound)),
+                }
+            }
+
+            inputs.push(type_);
+        }
+
+        let output = match output {
+            Some(output) => match self.eval_tree_of(output)? {
+                Value::Type(type_) => type_,
+                value => return Err(self.mismatch(output.option_span().unwrap_or(span), "a type", &value)),
+            },
+            None => Type::UNIT,
+        };
+
+        Ok(Type::Fn(inputs, Box::new(output)))
+    }
+
+    fn construct(&mut self, instance: Instance, construct: &Construct) -> Eval<Value> {
+        let span = construct.span();
+        let Try::Success(ItemCore::Struct(struct_)) = &self.namespace.item(instance.item).core else {
+            return Err(self.mismatch(span, "a struct type", &Value::Type(Type::Struct(instance))));
+        };
+        let Try::Success(fields) = &struct_.fields else {
+            return Err(Interrupt::Failure(None));
+        };
+
+        let mut values = HashMap::new();
+        for field in &construct.items {
+            let Some(tree) = field.set.success_ref().and_then(|set| set.value.tree()) else {
+                return Err(Interrupt::Failure(None));
+            };
+            let value = self.eval_tree(&tree)?;
+            let name = field.ident.str_id();
+
+            let is_field = fields
+                .items
+                .iter()
+                .any(|param| param_ident(param).is_some_and(|ident| ident.str_id() == name));
+            if !is_field {
+                return Err(self.error(Error::NoField(
+                    field.ident.span,
+                    instance.to_string_interned(self.interner),
+                    name,
+                )));
+            }
+            if values.insert(name, (value, field.ident.span)).is_some() {
+                return Err(self.error(Error::AlreadyExists(field.ident.span, name)));
+            }
+        }
+
+        self.scopes.push(HashMap::new());
+        let output = self.construct_fields(&instance, &fields.items, values, span);
+        self.scopes.pop();
+
+        output.map(|fields| Value::Struct(instance, fields))
+    }
+
+    /// Fields are bound in declaration order, so a field's bounds can refer to the fields before it.
+    fn construct_fields(
+        &mut self,
+        instance: &Instance,
+        params: &[Param],
+        mut values: HashMap<StrId, (Value, Span)>,
+        span: Span,
+    ) -> Eval<Vec<(StrId, Value)>> {
+        self.bind_generics(instance, span)?;
+
+        let mut fields = Vec::with_capacity(params.len());
+        for param in params {
+            let Some(ident) = param_ident(param) else {
+                return Err(Interrupt::Failure(None));
+            };
+
+            let Some((value, value_span)) = values.remove(&ident.str_id()) else {
+                return Err(self.error(Error::MissingField(span, ident.str_id())));
+            };
+
+            self.bind_param(param, value.clone(), value_span)?;
+            fields.push((ident.str_id(), value));
+        }
+
+        Ok(fields)
+    }
+}
+
+/// The generic parameters of an item that are given values, which are all of them except lifetimes.
+pub fn generic_params(item: &oathc_ast::Item) -> Vec<&Param> {
+    let params = match &item.core {
+        Try::Success(ItemCore::Fn(Fn { generics, .. }))
+        | Try::Success(ItemCore::Sys(Sys { generics, .. }))
+        | Try::Success(ItemCore::Trait(Trait { generics, .. })) => {
+            generics.as_ref().map_or(&[][..], |generics| generics.items.as_slice())
+        }
+        Try::Success(ItemCore::Struct(Struct { generics, .. })) | Try::Success(ItemCore::Enum(Enum { generics, .. })) => {
+            generics.as_ref().map_or(&[][..], |generics| generics.params.as_slice())
+        }
+        _ => &[],
+    };
+
+    params.iter().filter(|param| !param.is_lifetime()).collect()
+}
+
+pub fn param_ident(param: &Param) -> Option<Ident> {
+    param.body.success_ref()?.as_ident()
+}
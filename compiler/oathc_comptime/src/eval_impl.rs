@@ -0,0 +1,60 @@
+use super::*;
+
+impl<'ctx> Comptime<'ctx> {
+    /// Whether the value satisfies the bound, like `Bound::holds`, also checking which traits a type implements.
+    pub(crate) fn bound_holds(&mut self, bound: &Bound, value: &Value) -> Eval<Option<bool>> {
+        Ok(match bound {
+            Bound::Impl(trait_) => Some(self.implements(value, trait_)?),
+            Bound::Not(bound) => self.bound_holds(bound, value)?.map(|holds| !holds),
+            Bound::And(lhs, rhs) => match (self.bound_holds(lhs, value)?, self.bound_holds(rhs, value)?) {
+                (Some(lhs), Some(rhs)) => Some(lhs && rhs),
+                _ => None,
+            },
+            Bound::Or(lhs, rhs) => match (self.bound_holds(lhs, value)?, self.bound_holds(rhs, value)?) {
+                (Some(lhs), Some(rhs)) => Some(lhs || rhs),
+                _ => None,
+            },
+            Bound::Cmp(..) => bound.holds(value),
+        })
+    }
+
+    /// Whether there's an impl of the trait, with its generic arguments, for the type.
+    ///
+    /// A generic impl is taken to implement the trait for every instance of the item it's for.
+    fn implements(&mut self, value: &Value, trait_: &Instance) -> Eval<bool> {
+        let item = match value {
+            Value::Type(Type::Struct(instance) | Type::Enum(instance)) => instance.item,
+            _ => return Ok(false),
+        };
+
+        for (_, impl_item) in self.namespace.items() {
+            let Try::Success(ItemCore::Impl(impl_)) = &impl_item.core else {
+                continue;
+            };
+            let Some((id, _, args)) = self.namespace.impl_trait(impl_) else {
+                continue;
+            };
+            let Some(target) = impl_.target().tree() else {
+                continue;
+            };
+            let target_item = target.as_unary().and_then(|unary| match unary.core {
+                ExprCore::Ident(ident) => self.namespace.get(ident.str_id()),
+                _ => None,
+            });
+            if id != trait_.item || target_item != Some(item) {
+                continue;
+            }
+            if impl_.generics.is_some() {
+                return Ok(true);
+            }
+
+            let target = self.eval_tree(&target)?;
+            let args = args.iter().map(|arg| self.eval_tree(arg)).collect::<Eval<Vec<_>>>()?;
+            if target == *value && args == trait_.args {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+}
@@ -0,0 +1,206 @@
+use super::*;
+
+impl<'ctx> Comptime<'ctx> {
+    /// Evaluates the first arm whose pattern matches and whose guard holds.
+    pub(crate) fn eval_match(&mut self, match_: &Match) -> Eval<Value> {
+        let Try::Success(body) = &match_.body else {
+            return Err(Interrupt::Failure(None));
+        };
+
+        let value = self.eval_tree_of(&match_.value)?;
+
+        for arm in &body.arms {
+            for pattern in arm.patterns() {
+                let mut bindings = Vec::new();
+                if !self.match_pattern(pattern, &value, &mut bindings)? {
+                    continue;
+                }
+
+                self.scopes.push(
+                    bindings
+                        .into_iter()
+                        .map(|(name, value)| (name, Local { value, mut_: false }))
+                        .collect(),
+                );
+                let output = self.eval_arm(arm);
+                self.scopes.pop();
+
+                if let Some(value) = output? {
+                    return Ok(value);
+                }
+            }
+        }
+
+        let span = match_.value.option_span().unwrap_or(match_.keyword.span());
+        Err(self.error(Error::ComptimeNoMatch(span, value.to_string_interned(self.interner))))
+    }
+
+    /// Returns `None` if the arm's guard doesn't hold.
+    fn eval_arm(&mut self, arm: &MatchArm) -> Eval<Option<Value>> {
+        if let Some(guard) = &arm.guard {
+            match self.eval_tree_of(&guard.condition)? {
+                Value::Bool(true) => {}
+                Value::Bool(false) => return Ok(None),
+                value => {
+                    let span = guard.condition.option_span().unwrap_or(guard.keyword.span());
+                    return Err(self.mismatch(span, "a bool", &value));
+                }
+            }
+        }
+
+        self.eval_tree_of(&arm.value).map(Some)
+    }
+
+    /// Whether the value matches the pattern, collecting what the pattern binds.
+    pub(crate) fn match_pattern(&mut self, pattern: &Pattern, value: &Value, bindings: &mut Vec<(StrId, Value)>) -> Eval<bool> {
+        match pattern {
+            Pattern::Path(path) => self.match_path(path, value, bindings),
+            Pattern::Literal(literal) => {
+                let Value::Int(value) = value else {
+                    return Err(self.mismatch(literal.span(), "an integer", value));
+                };
+
+                let start = self.pattern_int(&literal.start)?;
+                match &literal.end {
+                    None => Ok(*value == start),
+                    Some(end) => Ok(*value >= start && self.below_range_end(&end.op, end.end.as_ref(), *value)?),
+                }
+            }
+            Pattern::RangeTo(range) => {
+                let Value::Int(value) = value else {
+                    return Err(self.mismatch(range.span(), "an integer", value));
+                };
+
+                match &range.end {
+                    Try::Success(end) => self.below_range_end(&range.op, Some(end), *value),
+                    Try::Failure(_) => Err(Interrupt::Failure(None)),
+                }
+            }
+            Pattern::Tuple(tuple) if tuple.items.len() == 1 => self.match_pattern(&tuple.items[0], value, bindings),
+            Pattern::Tuple(tuple) => match value {
+                Value::Tuple(values) if values.len() == tuple.items.len() => self.match_all(&tuple.items, values, bindings),
+                value => Err(self.mismatch(tuple.span(), "a tuple", value)),
+            },
+            Pattern::Array(array) => match value {
+                Value::Array(values) if values.len() == array.items.len() => self.match_all(&array.items, values, bindings),
+                Value::Array(_) => Ok(false),
+                value => Err(self.mismatch(array.span(), "an array", value)),
+            },
+        }
+    }
+
+    fn match_all(&mut self, patterns: &[Pattern], values: &[Value], bindings: &mut Vec<(StrId, Value)>) -> Eval<bool> {
+        for (pattern, value) in patterns.iter().zip(values) {
+            if !self.match_pattern(pattern, value, bindings)? {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    fn match_path(&mut self, path: &PathPattern, value: &Value, bindings: &mut Vec<(StrId, Value)>) -> Eval<bool> {
+        if path.is_wildcard(self.interner) {
+            return Ok(true);
+        }
+        if let Some(expected) = path.bool_literal(self.interner) {
+            return match value {
+                Value::Bool(value) => Ok(*value == expected),
+                value => Err(self.mismatch(path.span(), "a bool", value)),
+            };
+        }
+        if let Some(ident) = path.binding(self.interner) {
+            bindings.push((ident.str_id(), value.clone()));
+            return Ok(true);
+        }
+
+        let Some(PathPatternTail::Fields(fields)) = &path.tail else {
+            return Err(self.error(Error::ComptimeUnsupported(path.span(), "enum patterns")));
+        };
+
+        let instance = match self.eval_ident(path.ident)? {
+            Value::Type(Type::Struct(instance)) => instance,
+            type_ => return Err(self.mismatch(path.ident.span, "a struct type", &type_)),
+        };
+        let Value::Struct(value_instance, values) = value else {
+            return Err(self.mismatch(path.span(), "a struct", value));
+        };
+        if value_instance.item != instance.item {
+            return Err(self.error(Error::TypeMismatch(
+                path.span(),
+                instance.to_string_interned(self.interner),
+                value_instance.to_string_interned(self.interner),
+            )));
+        }
+
+        let mut matched = true;
+        for field in &fields.fields {
+            let FieldPattern::Field(field) = field else {
+                continue;
+            };
+            let name = field.ident.str_id();
+            let Some((_, field_value)) = values.iter().find(|(field_name, _)| *field_name == name) else {
+                return Err(self.error(Error::NoField(
+                    field.ident.span,
+                    instance.to_string_interned(self.interner),
+                    name,
+                )));
+            };
+
+            match &field.value {
+                Some(assign) => match &assign.pattern {
+                    Try::Success(pattern) => matched &= self.match_pattern(pattern, field_value, bindings)?,
+                    Try::Failure(_) => return Err(Interrupt::Failure(None)),
+                },
+                None => bindings.push((name, field_value.clone())),
+            }
+        }
+
+        let has_rest = fields.fields.iter().any(|field| matches!(field, FieldPattern::Rest(_)));
+        if !has_rest {
+            let missing = values.iter().find(|(name, _)| {
+                !fields
+                    .fields
+                    .iter()
+                    .any(|field| matches!(field, FieldPattern::Field(field) if field.ident.str_id() == *name))
+            });
+            if let Some((name, _)) = missing {
+                return Err(self.error(Error::MissingField(fields.span(), *name)));
+            }
+        }
+
+        Ok(matched)
+    }
+
+    fn below_range_end(&mut self, op: &RangePatternOp, end: Option<&PatternLiteral>, value: i128) -> Eval<bool> {
+        let Some(end) = end else {
+            return Ok(true);
+        };
+
+        let end = self.pattern_int(end)?;
+        match op {
+            RangePatternOp::Exclusive(_) => Ok(value < end),
+            RangePatternOp::Inclusive(_) => Ok(value <= end),
+        }
+    }
+
+    fn pattern_int(&mut self, literal: &PatternLiteral) -> Eval<i128> {
+        let (literal, negate) = match literal {
+            PatternLiteral::Literal(literal) => (literal, false),
+            PatternLiteral::Neg(neg) => match &neg.literal {
+                Try::Success(literal) => (literal, true),
+                Try::Failure(_) => return Err(Interrupt::Failure(None)),
+            },
+        };
+
+        let Literal::Int(int) = literal else {
+            return Err(self.error(Error::ComptimeUnsupported(literal.span(), "this literal")));
+        };
+        let Try::Success(value) = int.value else {
+            return Err(Interrupt::Failure(None));
+        };
+
+        let value = i128::try_from(value).map_err(|_| self.error(Error::ComptimeOverflow(int.span)))?;
+        Ok(if negate { -value } else { value })
+    }
+}
@@ -0,0 +1,18 @@
+use std::{collections::HashMap, fmt::Formatter, mem::take};
+
+use oathc_ast::*;
+use oathc_diagnostics::*;
+use oathc_interner::*;
+use oathc_res::{ItemId, Namespace};
+use oathc_span::*;
+use oathc_tokens::*;
+
+mod comptime;
+mod eval_block;
+mod eval_expr;
+mod eval_impl;
+mod eval_match;
+mod value;
+pub use comptime::*;
+pub use eval_expr::{generic_params, param_ident};
+pub use value::*;
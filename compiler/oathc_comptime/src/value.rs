@@ -0,0 +1,358 @@
+use super::*;
+
+/// A compile-time value.
+///
+/// Types are values too, so `Polygon<3>` and `[Point; N]` evaluate to a `Value::Type`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Value {
+    Int(i128),
+    Bool(bool),
+    Type(Type),
+    Tuple(Vec<Value>),
+    Array(Vec<Value>),
+    Struct(Instance, Vec<(StrId, Value)>),
+    Fn(Instance),
+    Bound(Bound),
+    /// The integers from the first up to but not including the second, like `0..n`.
+    Range(i128, i128),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Type {
+    Int(IntType),
+    Bool,
+    Type,
+    Tuple(Vec<Type>),
+    Array(Box<Type>, usize),
+    Struct(Instance),
+    Enum(Instance),
+    Refined(Box<Type>, Bound),
+    /// The type of functions that take parameters of the types and return the output type, like `fn(x i32) bool`.
+    Fn(Vec<Type>, Box<Type>),
+}
+
+/// An item together with the generic arguments it was given.
+///
+/// Two instances are the same item-instance iff they are equal.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Instance {
+    pub item: ItemId,
+    pub name: StrId,
+    pub args: Vec<Value>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum IntType {
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+}
+
+/// A predicate over a value, like `!= 5` or `>= start & < end`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Bound {
+    Cmp(CmpOp, Box<Value>),
+    Not(Box<Bound>),
+    And(Box<Bound>, Box<Bound>),
+    Or(Box<Bound>, Box<Bound>),
+    /// Implemented by the type, like `Shape` in `S type: Shape`.
+    Impl(Instance),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CmpOp {
+    Eq,
+    NotEq,
+    More,
+    Less,
+    MoreEq,
+    LessEq,
+}
+
+impl Value {
+    pub const UNIT: Self = Self::Tuple(Vec::new());
+
+    pub fn desc(&self) -> &'static str {
+        match self {
+            Self::Int(_) => "an integer",
+            Self::Bool(_) => "a bool",
+            Self::Type(_) => "a type",
+            Self::Tuple(_) => "a tuple",
+            Self::Array(_) => "an array",
+            Self::Struct(..) => "a struct",
+            Self::Fn(_) => "a function",
+            Self::Bound(_) => "a bound",
+            Self::Range(..) => "a range",
+        }
+    }
+
+    /// Returns whether the value is of the type, including any refinements the type has.
+    pub fn is_of(&self, type_: &Type) -> bool {
+        match (self, type_) {
+            (_, Type::Refined(base, bound)) => self.is_of(base) && bound.holds(self) == Some(true),
+            (Self::Int(value), Type::Int(int_type)) => int_type.contains(*value),
+            (Self::Bool(_), Type::Bool) => true,
+            (Self::Type(_), Type::Type) => true,
+            (Self::Tuple(values), Type::Tuple(types)) => {
+                values.len() == types.len() && values.iter().zip(types).all(|(value, type_)| value.is_of(type_))
+            }
+            (Self::Array(values), Type::Array(item_type, len)) => {
+                values.len() == *len && values.iter().all(|value| value.is_of(item_type))
+            }
+            (Self::Struct(instance, _), Type::Struct(type_instance)) => instance == type_instance,
+            _ => false,
+        }
+    }
+}
+
+impl Type {
+    pub const UNIT: Self = Self::Tuple(Vec::new());
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "bool" => Some(Self::Bool),
+            _ => IntType::from_name(name).map(Self::Int),
+        }
+    }
+
+    /// Strips refinements.
+    pub fn base(&self) -> &Self {
+        match self {
+            Self::Refined(base, _) => base.base(),
+            _ => self,
+        }
+    }
+}
+
+impl IntType {
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "i8" => Self::I8,
+            "i16" => Self::I16,
+            "i32" => Self::I32,
+            "i64" => Self::I64,
+            "i128" => Self::I128,
+            "u8" => Self::U8,
+            "u16" => Self::U16,
+            "u32" => Self::U32,
+            "u64" => Self::U64,
+            "u128" => Self::U128,
+            _ => return None,
+        })
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::I8 => "i8",
+            Self::I16 => "i16",
+            Self::I32 => "i32",
+            Self::I64 => "i64",
+            Self::I128 => "i128",
+            Self::U8 => "u8",
+            Self::U16 => "u16",
+            Self::U32 => "u32",
+            Self::U64 => "u64",
+            Self::U128 => "u128",
+        }
+    }
+
+    pub fn bits(self) -> u32 {
+        match self {
+            Self::I8 | Self::U8 => 8,
+            Self::I16 | Self::U16 => 16,
+            Self::I32 | Self::U32 => 32,
+            Self::I64 | Self::U64 => 64,
+            Self::I128 | Self::U128 => 128,
+        }
+    }
+
+    pub fn is_signed(self) -> bool {
+        matches!(self, Self::I8 | Self::I16 | Self::I32 | Self::I64 | Self::I128)
+    }
+
+    pub fn min(self) -> i128 {
+        if self.is_signed() { -(1 << (self.bits() - 1)) } else { 0 }
+    }
+
+    /// `u128::MAX` doesn't fit in the compile-time integer representation, so `u128` is capped at `i128::MAX`.
+    pub fn max(self) -> i128 {
+        match self {
+            Self::I128 | Self::U128 => i128::MAX,
+            _ if self.is_signed() => (1 << (self.bits() - 1)) - 1,
+            _ => (1 << self.bits()) - 1,
+        }
+    }
+
+    pub fn contains(self, value: i128) -> bool {
+        (self.min()..=self.max()).contains(&value)
+    }
+}
+
+impl Bound {
+    /// Returns `None` if the bound doesn't apply to values of this kind.
+    pub fn holds(&self, value: &Value) -> Option<bool> {
+        match self {
+            Self::Cmp(op, rhs) => op.apply(value, rhs),
+            Self::Not(bound) => bound.holds(value).map(|holds| !holds),
+            Self::And(lhs, rhs) => Some(lhs.holds(value)? && rhs.holds(value)?),
+            Self::Or(lhs, rhs) => Some(lhs.holds(value)? || rhs.holds(value)?),
+            // Which traits a type implements is only known to `Comptime`.
+            Self::Impl(_) => None,
+        }
+    }
+}
+
+impl CmpOp {
+    pub fn from_un_op(op: &UnOp) -> Option<Self> {
+        Some(match op {
+            UnOp::Eq(_) => Self::Eq,
+            UnOp::NotEq(_) => Self::NotEq,
+            UnOp::More(_) => Self::More,
+            UnOp::Less(_) => Self::Less,
+            UnOp::MoreEq(_) => Self::MoreEq,
+            UnOp::LessEq(_) => Self::LessEq,
+            _ => return None,
+        })
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Eq => "==",
+            Self::NotEq => "!=",
+            Self::More => ">",
+            Self::Less => "<",
+            Self::MoreEq => ">=",
+            Self::LessEq => "<=",
+        }
+    }
+
+    pub fn negate(self) -> Self {
+        match self {
+            Self::Eq => Self::NotEq,
+            Self::NotEq => Self::Eq,
+            Self::More => Self::LessEq,
+            Self::Less => Self::MoreEq,
+            Self::MoreEq => Self::Less,
+            Self::LessEq => Self::More,
+        }
+    }
+
+    /// Returns `None` if the values can't be compared.
+    pub fn apply(self, lhs: &Value, rhs: &Value) -> Option<bool> {
+        match (self, lhs, rhs) {
+            (Self::Eq, _, _) => Some(lhs == rhs),
+            (Self::NotEq, _, _) => Some(lhs != rhs),
+            (_, Value::Int(lhs), Value::Int(rhs)) => Some(match self {
+                Self::More => lhs > rhs,
+                Self::Less => lhs < rhs,
+                Self::MoreEq => lhs >= rhs,
+                Self::LessEq => lhs <= rhs,
+                Self::Eq | Self::NotEq => unreachable!(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+// Display
+
+impl InternedDisplay for Value {
+    fn interned_fmt(&self, f: &mut Formatter, interner: &Interner) -> std::fmt::Result {
+        match self {
+            Self::Int(value) => write!(f, "{value}"),
+            Self::Bool(value) => write!(f, "{value}"),
+            Self::Type(type_) => type_.interned_fmt(f, interner),
+            Self::Tuple(values) => {
+                write!(f, "(")?;
+                fmt_list(values, f, interner)?;
+                write!(f, ")")
+            }
+            Self::Array(values) => {
+                write!(f, "[")?;
+                fmt_list(values, f, interner)?;
+                write!(f, "]")
+            }
+            Self::Struct(instance, fields) => {
+                instance.interned_fmt(f, interner)?;
+                write!(f, " {{ ")?;
+                for (index, (name, value)) in fields.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{} = {}", Interned(name, interner), Interned(value, interner))?;
+                }
+                write!(f, " }}")
+            }
+            Self::Fn(instance) => instance.interned_fmt(f, interner),
+            Self::Bound(bound) => bound.interned_fmt(f, interner),
+            Self::Range(start, end) => write!(f, "{start}..{end}"),
+        }
+    }
+}
+
+impl InternedDisplay for Type {
+    fn interned_fmt(&self, f: &mut Formatter, interner: &Interner) -> std::fmt::Result {
+        match self {
+            Self::Int(int_type) => write!(f, "{}", int_type.name()),
+            Self::Bool => write!(f, "bool"),
+            Self::Type => write!(f, "type"),
+            Self::Tuple(types) => {
+                write!(f, "(")?;
+                fmt_list(types, f, interner)?;
+                write!(f, ")")
+            }
+            Self::Array(item_type, len) => write!(f, "[{}; {len}]", Interned(&**item_type, interner)),
+            Self::Struct(instance) | Self::Enum(instance) => instance.interned_fmt(f, interner),
+            Self::Refined(base, bound) => write!(f, "{}: {}", Interned(&**base, interner), Interned(bound, interner)),
+            Self::Fn(inputs, output) => {
+                write!(f, "fn(")?;
+                fmt_list(inputs, f, interner)?;
+                write!(f, ") {}", Interned(&**output, interner))
+            }
+        }
+    }
+}
+
+impl InternedDisplay for Instance {
+    fn interned_fmt(&self, f: &mut Formatter, interner: &Interner) -> std::fmt::Result {
+        self.name.interned_fmt(f, interner)?;
+
+        if !self.args.is_empty() {
+            write!(f, "<")?;
+            fmt_list(&self.args, f, interner)?;
+            write!(f, ">")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl InternedDisplay for Bound {
+    fn interned_fmt(&self, f: &mut Formatter, interner: &Interner) -> std::fmt::Result {
+        match self {
+            Self::Cmp(op, value) => write!(f, "{} {}", op.as_str(), Interned(&**value, interner)),
+            Self::Not(bound) => write!(f, "!({})", Interned(&**bound, interner)),
+            Self::And(lhs, rhs) => write!(f, "{} & {}", Interned(&**lhs, interner), Interned(&**rhs, interner)),
+            Self::Or(lhs, rhs) => write!(f, "({} | {})", Interned(&**lhs, interner), Interned(&**rhs, interner)),
+            Self::Impl(trait_) => trait_.interned_fmt(f, interner),
+        }
+    }
+}
+
+fn fmt_list<T: InternedDisplay>(items: &[T], f: &mut Formatter, interner: &Interner) -> std::fmt::Result {
+    for (index, item) in items.iter().enumerate() {
+        if index > 0 {
+            write!(f, ", ")?;
+        }
+        item.interned_fmt(f, interner)?;
+    }
+
+    Ok(())
+}
@@ -47,6 +47,151 @@ pub enum Error {
     AlreadyExists(#[span] Span, StrId),
     #[display("todo")]
     ToDo(Span),
+
+    #[display("`{field_1}` can't be evaluated at compile time")]
+    NotComptime(#[span] Span, StrId),
+    #[display("{field_1} can't be evaluated at compile time yet")]
+    ComptimeUnsupported(#[span] Span, &'static str),
+    #[display("compile-time evaluation ran out of fuel, is there an infinite loop?")]
+    ComptimeFuel(#[span] Span),
+    #[display("`{field_1}` depends on itself at compile time")]
+    ComptimeCycle(#[span] Span, StrId),
+    #[display("compile-time recursion limit reached while evaluating `{field_1}`")]
+    ComptimeRecursionLimit(#[span] Span, StrId),
+    #[display("integer overflow at compile time")]
+    ComptimeOverflow(#[span] Span),
+    #[display("division by zero at compile time")]
+    ComptimeDivByZero(#[span] Span),
+    #[display("index `{field_1}` is out of bounds for length `{field_2}`")]
+    ComptimeIndexOutOfBounds(#[span] Span, i128, usize),
+    #[display("`{field_1}` doesn't satisfy `{field_2}`")]
+    ComptimeBoundViolated(#[span] Span, String, String),
+    #[display("no arm of this `match` matches `{field_1}`")]
+    ComptimeNoMatch(#[span] Span, String),
+    #[display("`{field_1}` doesn't match this pattern")]
+    ComptimePatternMismatch(#[span] Span, String),
+    #[display("expected {field_1}, found `{field_2}`")]
+    Mismatch(#[span] Span, &'static str, String),
+    #[display("expected a value of type `{field_1}`, found `{field_2}`")]
+    TypeMismatch(#[span] Span, String, String),
+    #[display("`{field_1}` requires `{field_2}`, which `{field_3}` doesn't guarantee")]
+    FnTypeBound(#[span] Span, StrId, String, String),
+    #[display("expected {field_1} arguments, found {field_2}")]
+    ArgCount(#[span] Span, usize, usize),
+    #[display("`{field_1}` has no field `{field_2}`")]
+    NoField(#[span] Span, String, StrId),
+    #[display("missing field `{field_1}`")]
+    MissingField(#[span] Span, StrId),
+    #[display("`{field_1}` has no variant `{field_2}`")]
+    NoVariant(#[span] Span, String, StrId),
+    #[display("cannot assign to immutable `{field_1}`")]
+    Immutable(#[span] Span, StrId),
+    #[display("`{field_1}` outside of {field_2}")]
+    Misplaced(#[span] Span, &'static str, &'static str),
+
+    #[display("`{field_1}` isn't a trait")]
+    NotATrait(#[span] Span, StrId),
+    #[display("not all members of `{field_1}` are implemented, missing `{field_2}`")]
+    MissingTraitMember(#[span] Span, StrId, StrId),
+    #[display("`{field_1}` isn't a member of trait `{field_2}`")]
+    NotATraitMember(#[span] Span, StrId, StrId),
+    #[display("`{field_1}` is {field_2} in trait `{field_3}`")]
+    TraitMemberKind(#[span] Span, StrId, &'static str, StrId),
+    #[display("`{field_1}` has a different number of {field_3} than in its trait, expected {field_2}, found {field_4}")]
+    TraitParamCount(#[span] Span, StrId, usize, &'static str, usize),
+    #[display("expected `{field_1}` to match the trait, found `{field_2}`")]
+    TraitSignatureMismatch(#[span] Span, String, String),
+
+    #[display("instantiating `{field_1}` recurses without end")]
+    InstantiationDepth(#[span] Span, String, Vec<Label>),
+    #[display("too many instances of generic items, stopped at `{field_1}`")]
+    InstanceLimit(#[span] Span, String, Vec<Label>),
+
+    #[display("fn `{field_1}` requires `{field_2}`{field_3}")]
+    RequireViolated(#[span] Span, StrId, String, Counterexample),
+    #[display("trait `{field_1}` requires `{field_2}`{field_3}")]
+    TraitRequireViolated(#[span] Span, StrId, String, Counterexample),
+    #[display("fn `{field_1}` requires `{field_2}`, but trait `{field_3}` doesn't{field_4}")]
+    ImplRequireStronger(#[span] Span, StrId, String, StrId, Counterexample),
+    #[display("fn `{field_1}` may not keep the promise `{field_2}` of trait `{field_3}`{field_4}")]
+    ImplPromiseWeaker(#[span] Span, StrId, String, StrId, Counterexample),
+    #[display("closure requires `{field_1}`{field_2}")]
+    ClosureRequireViolated(#[span] Span, String, Counterexample),
+    #[display("promised `{field_1}`{field_2}")]
+    PromiseViolated(#[span] Span, String, Counterexample),
+    #[display("`{field_1}` isn't guaranteed to hold{field_2}")]
+    BoundViolated(#[span] Span, String, Counterexample),
+    #[display("`{field_1}` may not be the active variant of `{field_2}`{field_3}")]
+    InactiveVariant(#[span] Span, StrId, String, Counterexample),
+    #[display("`{field_1}` is untagged, so its variant can only be tested in constraints")]
+    UntaggedTest(#[span] Span, String),
+    #[display("`match` isn't exhaustive{field_1}")]
+    NonExhaustive(#[span] Span, String),
+    #[display("this pattern isn't guaranteed to match{field_1}")]
+    RefutablePattern(#[span] Span, String),
+
+    #[display("cannot borrow `{field_1}` as `{field_2}` because it's also borrowed as `{field_3}`")]
+    BorrowConflict(#[span] Span, String, &'static str, &'static str, Vec<Label>),
+    #[display("cannot {field_1} `{field_2}` while it's borrowed as `{field_3}`")]
+    UseWhileBorrowed(#[span] Span, &'static str, String, &'static str, Vec<Label>),
+    #[display("use of moved value `{field_1}`")]
+    UseAfterMove(#[span] Span, StrId, Vec<Label>),
+    #[display("cannot borrow immutable `{field_1}` as `{field_2}`")]
+    BorrowImmutable(#[span] Span, StrId, &'static str),
+    #[display("cannot {field_1} through a `{field_2}` reference")]
+    ImmutableRef(#[span] Span, &'static str, &'static str),
+    #[display("`{field_1}` doesn't live long enough")]
+    DanglingRef(#[span] Span, StrId, Vec<Label>),
+    #[display("returns a reference to `{field_1}`, which is dropped when the function returns")]
+    ReturnsLocalRef(#[span] Span, StrId, Vec<Label>),
+    #[display("undeclared lifetime `'{field_1}`")]
+    UndeclaredLifetime(#[span] Span, StrId),
+    #[display("lifetime `'{field_1}` is never used")]
+    UnusedLifetime(#[span] Span, StrId),
+    #[display("lifetime parameter outside of generic parameters")]
+    MisplacedLifetime(#[span] Span),
+    #[display("missing lifetime, {field_1}")]
+    MissingLifetime(#[span] Span, &'static str),
+    #[display("returns a reference that lives for {field_1}, but the output needs {field_2}")]
+    OutlivesViolated(#[span] Span, String, String, Vec<Label>),
+    #[display("`?` needs an enum with `Ok` and `Err` variants")]
+    NotFallible(#[span] Span),
+    #[display("`?` can only be used in a `try` block, or in a function that returns an enum with `Ok` and `Err` variants")]
+    PropagateOutsideFallible(#[span] Span, Vec<Label>),
+    #[display("`?` propagates errors of type `{field_1}`, but `{field_2}` holds errors of type `{field_3}`")]
+    PropagateMismatch(#[span] Span, String, String, String, Vec<Label>),
+    #[display("`try` block evaluates to `{field_1}`, but it propagates errors as `{field_2}`")]
+    TryMismatch(#[span] Span, String, String, Vec<Label>),
+    #[display("undeclared label `'{field_1}`")]
+    UndeclaredLabel(#[span] Span, StrId),
+    #[display("only loops and blocks can have labels")]
+    MisplacedLabel(#[span] Span),
+    #[display("this value is `{field_1}`, but the {field_2} evaluates to `{field_3}`")]
+    ExitMismatch(#[span] Span, String, &'static str, String, Vec<Label>),
+    #[display("`assume` is denied here, so its fact has to be proven")]
+    DeniedAssume(#[span] Span),
+    #[display("index `{field_1}` may be out of bounds{field_2}")]
+    IndexOutOfBounds(#[span] Span, String, Counterexample),
+    #[display("expected an array of length `{field_1}`, found one of length `{field_2}`")]
+    ArrayPatternLen(#[span] Span, usize, i128),
+    #[display("the array may not have length `{field_1}`{field_2}")]
+    ArrayPatternLenUnproven(#[span] Span, usize, Counterexample),
+    #[display("`{field_1}` may overflow `{field_2}`{field_3}")]
+    Overflow(#[span] Span, String, &'static str, Counterexample),
+    #[display("`{field_1}` may shift by the width of `{field_2}` or more{field_3}")]
+    ShiftOverflow(#[span] Span, String, &'static str, Counterexample),
+    #[display("`{field_1}` may divide by zero{field_2}")]
+    DivByZero(#[span] Span, String, Counterexample),
+    #[display("fn `{field_1}` isn't pure, so it can't be used in a constraint")]
+    ImpurePredicate(#[span] Span, StrId, Vec<Label>),
+    #[display("loop invariant `{field_1}` may not hold when the loop starts{field_2}")]
+    InvariantNotEstablished(#[span] Span, String, Counterexample),
+    #[display("loop invariant `{field_1}` may not be kept by an iteration{field_2}")]
+    InvariantNotKept(#[span] Span, String, Counterexample),
+    #[display("termination measure `{field_1}` may not decrease{field_2}")]
+    NotDecreasing(#[span] Span, String, Counterexample),
+    #[display("termination measure `{field_1}` may be negative{field_2}")]
+    NegativeMeasure(#[span] Span, String, Counterexample),
 }
 
 #[derive(Debug, Clone, From, TryInto, Spanned, InternedDisplay)]
@@ -55,6 +200,68 @@ pub enum Warning {
     UnnesessaryParens(#[span] Span),
     #[display("Syntax: expected {}", field_1)]
     ExpectedCase(#[span] Span, IdentCase),
+    #[display("unreachable `match` arm")]
+    #[from(skip)]
+    UnreachableArm(#[span] Span),
+    #[display("unchecked `assume`")]
+    #[from(skip)]
+    UncheckedAssume(#[span] Span),
+    #[display("this index isn't proven to be in bounds, and is left unverified")]
+    #[from(skip)]
+    UnverifiedIndex(#[span] Span),
+    #[display("this operation isn't proven not to overflow, and is left unverified")]
+    #[from(skip)]
+    UnverifiedOverflow(#[span] Span),
+    #[display("this divisor isn't proven to be nonzero, and is left unverified")]
+    #[from(skip)]
+    UnverifiedDivision(#[span] Span),
+}
+
+impl Diagnostic {
+    /// The secondary locations of the diagnostic.
+    pub fn labels(&self) -> &[Label] {
+        match self {
+            Self::Error(error) => error.labels(),
+            Self::Warning(_) => &[],
+        }
+    }
+}
+
+impl Error {
+    pub fn labels(&self) -> &[Label] {
+        match self {
+            Self::RequireViolated(.., counterexample)
+            | Self::TraitRequireViolated(.., counterexample)
+            | Self::ImplRequireStronger(.., counterexample)
+            | Self::ImplPromiseWeaker(.., counterexample)
+            | Self::ClosureRequireViolated(.., counterexample)
+            | Self::PromiseViolated(.., counterexample)
+            | Self::BoundViolated(.., counterexample)
+            | Self::InactiveVariant(.., counterexample)
+            | Self::IndexOutOfBounds(.., counterexample)
+            | Self::ArrayPatternLenUnproven(.., counterexample)
+            | Self::Overflow(.., counterexample)
+            | Self::ShiftOverflow(.., counterexample)
+            | Self::DivByZero(.., counterexample)
+            | Self::InvariantNotEstablished(.., counterexample)
+            | Self::InvariantNotKept(.., counterexample)
+            | Self::NotDecreasing(.., counterexample)
+            | Self::NegativeMeasure(.., counterexample) => &counterexample.trace,
+            Self::InstantiationDepth(.., backtrace) | Self::InstanceLimit(.., backtrace) => backtrace,
+            Self::BorrowConflict(.., labels)
+            | Self::UseWhileBorrowed(.., labels)
+            | Self::UseAfterMove(.., labels)
+            | Self::DanglingRef(.., labels)
+            | Self::ReturnsLocalRef(.., labels)
+            | Self::OutlivesViolated(.., labels)
+            | Self::PropagateOutsideFallible(.., labels)
+            | Self::PropagateMismatch(.., labels)
+            | Self::ImpurePredicate(.., labels)
+            | Self::TryMismatch(.., labels)
+            | Self::ExitMismatch(.., labels) => labels,
+            _ => &[],
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Display)]
@@ -0,0 +1,31 @@
+use std::fmt::{self, Display, Formatter};
+
+use super::*;
+
+/// A secondary location attached to a diagnostic.
+#[derive(Debug, Clone, Spanned)]
+pub struct Label {
+    #[span]
+    pub span: Span,
+    pub message: String,
+}
+
+/// Why a constraint can fail: values it fails for, and the branches and assignments that lead there.
+#[derive(Debug, Clone, Default)]
+pub struct Counterexample {
+    /// Like `a = 3, b = -1`, empty if no values were found.
+    pub values: String,
+    pub trace: Vec<Label>,
+    /// Whether the constraint couldn't be checked at all, like a comparison of floats.
+    pub unverifiable: bool,
+}
+
+impl Display for Counterexample {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match (self.unverifiable, self.values.is_empty()) {
+            (true, _) => write!(f, ", couldn't verify the constraint"),
+            (false, true) => Ok(()),
+            (false, false) => write!(f, ", fails when `{}`", self.values),
+        }
+    }
+}
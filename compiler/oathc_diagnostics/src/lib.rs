@@ -5,7 +5,9 @@ use oathc_span::*;
 
 mod diagnostic;
 mod diagnostics;
+mod label;
 mod try_;
 pub use diagnostic::*;
 pub use diagnostics::*;
+pub use label::*;
 pub use try_::*;
@@ -0,0 +1,397 @@
+use super::*;
+
+/// A `Generics` postfix in an item, like the `f<N + 1>` in `f<N + 1>(x)`.
+#[derive(Debug, Clone)]
+pub(crate) struct GenericUse<'a> {
+    /// The item and its generic arguments, without what comes after them.
+    pub tree: ExprTree<'a>,
+    /// A local the generic arguments refer to, which isn't known at compile time.
+    pub runtime_local: Option<Ident>,
+}
+
+/// Collects the generic uses of an item, tracking which names are runtime locals along the way.
+#[derive(Debug)]
+pub(crate) struct GenericUses<'a> {
+    pub uses: Vec<GenericUse<'a>>,
+    locals: Vec<HashSet<StrId>>,
+}
+
+impl<'a> GenericUses<'a> {
+    pub fn of_item(item: &'a oathc_ast::Item) -> Vec<GenericUse<'a>> {
+        let mut uses = Self {
+            uses: Vec::new(),
+            locals: vec![HashSet::new()],
+        };
+
+        match &item.core {
+            Try::Success(ItemCore::Fn(fn_)) => uses.fn_(fn_),
+            Try::Success(ItemCore::Struct(struct_)) => {
+                if let Try::Success(fields) = &struct_.fields {
+                    uses.params(&fields.items, &fields.contract);
+                }
+            }
+            Try::Success(ItemCore::Enum(enum_)) => {
+                if let Try::Success(variants) = &enum_.variants {
+                    uses.params(&variants.items, &variants.contract);
+                }
+            }
+            Try::Success(ItemCore::Static(static_)) => {
+                if let Try::Success(param) = &static_.param {
+                    uses.param(param);
+                }
+                if let Some(assign) = &static_.value {
+                    uses.expr(&assign.value);
+                }
+            }
+            _ => {}
+        }
+
+        uses.uses
+    }
+
+    fn fn_(&mut self, fn_: &'a Fn) {
+        for param in fn_.generics.iter().flat_map(|generics| &generics.items) {
+            self.param(param);
+        }
+
+        let Try::Success(input) = &fn_.input else {
+            return;
+        };
+
+        self.params(&input.items, &input.contract);
+        if let Some(output) = &fn_.output {
+            self.expr(output);
+        }
+        self.contract(&fn_.contract);
+
+        if let Try::Success(FnBody::Block(body)) = &fn_.body {
+            self.block(body);
+        }
+    }
+
+    /// Parameters are in scope in each other's types and bounds, like the fields of `struct Range`.
+    fn params(&mut self, params: &'a [Param], contract: &'a Contract) {
+        for param in params {
+            self.bind(param);
+        }
+        for param in params {
+            self.param(param);
+        }
+
+        self.contract(contract);
+    }
+
+    fn contract(&mut self, contract: &'a Contract) {
+        for segment in &contract.segments {
+            let items = match segment {
+                ContractSegment::Require(require) => require.items.success_ref(),
+                ContractSegment::Promise(promise) => promise.items.success_ref(),
+            };
+
+            for item in items.iter().flat_map(|items| &items.items) {
+                self.expr(item);
+            }
+        }
+    }
+
+    fn param(&mut self, param: &'a Param) {
+        if let Some(type_) = param.type_.as_ref().and_then(AngleUnaryExpr::as_ref_expr) {
+            self.tree(&ExprTree::Unary(type_));
+        }
+        if let Some(bounds) = &param.bounds {
+            self.expr(&bounds.expr);
+        }
+
+        if let Try::Success(ParamBody::Tuple(params)) = &param.body {
+            for param in &params.items {
+                self.param(param);
+            }
+        }
+    }
+
+    fn bind(&mut self, param: &Param) {
+        match &param.body {
+            Try::Success(ParamBody::Pattern(pattern)) => self.bind_pattern(pattern),
+            Try::Success(ParamBody::Tuple(params)) => params.items.iter().for_each(|param| self.bind(param)),
+            Try::Success(ParamBody::Lifetime(_)) | Try::Failure(_) => {}
+        }
+    }
+
+    fn block(&mut self, block: &'a Block) {
+        self.locals.push(HashSet::new());
+
+        for stmt in &block.stmts {
+            match stmt {
+                Stmt::Item(_) => {}
+                Stmt::Let(let_) => {
+                    if let Some(assign) = &let_.value {
+                        self.expr(&assign.value);
+                    }
+                    if let Try::Success(param) = &let_.name {
+                        self.param(param);
+                        self.bind(param);
+                    }
+                }
+                Stmt::Eval(ControlStmt { value: Some(value), .. })
+                | Stmt::Return(ControlStmt { value: Some(value), .. })
+                | Stmt::Break(BreakStmt { value: Some(value), .. }) => self.expr(value),
+                Stmt::Eval(_) | Stmt::Return(_) | Stmt::Break(_) | Stmt::Continue(_) => {}
+                Stmt::Assume(stmt) => self.expr(&stmt.value),
+                Stmt::Expr(stmt) => {
+                    self.expr(&stmt.expr);
+                    if let Some(set) = &stmt.set {
+                        self.expr(&set.value);
+                    }
+                }
+            }
+        }
+
+        self.locals.pop();
+    }
+
+    fn expr(&mut self, expr: &'a impl ToExprTree) {
+        if let Some(tree) = expr.tree() {
+            self.tree(&tree);
+        }
+    }
+
+    fn tree(&mut self, tree: &ExprTree<'a>) {
+        match tree {
+            ExprTree::BinOp(lhs, _, rhs) => {
+                self.tree(lhs);
+                self.tree(rhs);
+            }
+            ExprTree::Unary(unary) => {
+                self.core(unary.core);
+
+                for (index, ext) in unary.exts.iter().enumerate() {
+                    match ext {
+                        UnaryExprExt::Member(_) | UnaryExprExt::Question(_) => {}
+                        UnaryExprExt::Call(tuple) => tuple.items.iter().for_each(|item| self.expr(item)),
+                        UnaryExprExt::Index(array) => array.items.iter().for_each(|item| self.expr(item)),
+                        UnaryExprExt::Generics(generics) => {
+                            generics.args.iter().for_each(|arg| self.expr(arg));
+                            self.generic_use(unary, index);
+                        }
+                        UnaryExprExt::Construct(construct) => {
+                            for field in &construct.items {
+                                if let Try::Success(set) = &field.set {
+                                    self.expr(&set.value);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn core(&mut self, core: &'a ExprCore) {
+        match core {
+            ExprCore::Keyword(ExprKeyword::Fn(fn_)) => {
+                self.locals.push(HashSet::new());
+                if let Try::Success(input) = &fn_.input {
+                    self.params(&input.items, &input.contract);
+                }
+                if let Some(output) = &fn_.output {
+                    self.expr(&**output);
+                }
+                self.contract(&fn_.contract);
+                if let Some(body) = &fn_.body {
+                    self.block(body);
+                }
+                self.locals.pop();
+            }
+            ExprCore::Ident(_) | ExprCore::Keyword(_) | ExprCore::Literal(_) => {}
+            ExprCore::Block(block) => self.block(block),
+            ExprCore::Tuple(tuple) => tuple.items.iter().for_each(|item| self.expr(item)),
+            ExprCore::Array(array) => {
+                array.items.iter().for_each(|item| self.expr(item));
+                if let Some(len) = &array.len {
+                    self.expr(&len.len);
+                }
+            }
+            ExprCore::If(if_) => {
+                self.expr(&if_.condition);
+
+                let else_ = match &if_.body {
+                    IfBody::Then(then) => {
+                        self.expr(&then.expr);
+                        &then.else_
+                    }
+                    IfBody::Block(block) => {
+                        if let Try::Success(block) = &block.block {
+                            self.block(block);
+                        }
+                        &block.else_
+                    }
+                };
+                if let Some(else_) = else_ {
+                    self.expr(&else_.expr);
+                }
+            }
+            ExprCore::Loop(loop_) => {
+                if let Try::Success(block) = &loop_.block {
+                    self.block(block);
+                }
+            }
+            ExprCore::Try(try_) => {
+                if let Try::Success(block) = &try_.block {
+                    self.block(block);
+                }
+            }
+            ExprCore::While(while_) => {
+                self.expr(&while_.condition);
+                if let Try::Success(block) = &while_.block {
+                    self.block(block);
+                }
+            }
+            ExprCore::Until(until) => {
+                self.expr(&until.condition);
+                if let Try::Success(block) = &until.block {
+                    self.block(block);
+                }
+            }
+            ExprCore::For(for_) => {
+                self.expr(&for_.iter);
+
+                self.locals.push(HashSet::new());
+                if let Try::Success(item) = &for_.item {
+                    self.param(item);
+                    self.bind(item);
+                }
+                if let Try::Success(block) = &for_.block {
+                    self.block(block);
+                }
+                self.locals.pop();
+            }
+            ExprCore::Quantifier(quantifier) => {
+                self.expr(&quantifier.iter);
+
+                self.locals.push(HashSet::new());
+                if let Try::Success(item) = &quantifier.item {
+                    self.param(item);
+                    self.bind(item);
+                }
+                if let Try::Success(block) = &quantifier.block {
+                    self.block(block);
+                }
+                self.locals.pop();
+            }
+            ExprCore::Match(match_) => {
+                self.expr(&match_.value);
+
+                for arm in match_.body.success_ref().iter().flat_map(|body| &body.arms) {
+                    self.locals.push(HashSet::new());
+                    for pattern in arm.patterns() {
+                        self.bind_pattern(pattern);
+                    }
+                    if let Some(guard) = &arm.guard {
+                        self.expr(&guard.condition);
+                    }
+                    self.expr(&arm.value);
+                    self.locals.pop();
+                }
+            }
+        }
+    }
+
+    fn bind_pattern(&mut self, pattern: &Pattern) {
+        match pattern {
+            Pattern::Path(path) => match &path.tail {
+                None => {
+                    self.locals.last_mut().unwrap().insert(path.ident.str_id());
+                }
+                Some(PathPatternTail::Variant(variant)) => {
+                    for item in variant.payload.iter().flat_map(|payload| &payload.items) {
+                        self.bind_pattern(item);
+                    }
+                }
+                Some(PathPatternTail::Fields(fields)) => {
+                    for field in &fields.fields {
+                        let FieldPattern::Field(field) = field else {
+                            continue;
+                        };
+
+                        match &field.value {
+                            Some(assign) => {
+                                if let Try::Success(pattern) = &assign.pattern {
+                                    self.bind_pattern(pattern);
+                                }
+                            }
+                            None => {
+                                self.locals.last_mut().unwrap().insert(field.ident.str_id());
+                            }
+                        }
+                    }
+                }
+            },
+            Pattern::Tuple(tuple) => tuple.items.iter().for_each(|item| self.bind_pattern(item)),
+            Pattern::Array(array) => array.items.iter().for_each(|item| self.bind_pattern(item)),
+            Pattern::Literal(_) | Pattern::RangeTo(_) => {}
+        }
+    }
+
+    /// Records the part of the unary expression up to its generic arguments at `index`,
+    /// unless it's a generic member of a runtime value.
+    fn generic_use(&mut self, unary: &UnaryExprRef<'a>, index: usize) {
+        if matches!(unary.core, ExprCore::Ident(ident) if self.is_local(ident.str_id())) {
+            return;
+        }
+
+        let exts = unary.exts[..=index].to_vec();
+        let span = exts.iter().fold(unary.core.span(), |span, ext| span.connect(ext.span()));
+
+        let UnaryExprExt::Generics(generics) = unary.exts[index] else {
+            unreachable!()
+        };
+        let runtime_local = generics
+            .args
+            .iter()
+            .filter_map(ToExprTree::tree)
+            .find_map(|arg| self.runtime_local(&arg));
+
+        self.uses.push(GenericUse {
+            tree: ExprTree::Unary(UnaryExprRef {
+                span,
+                attrs: &[],
+                label: None,
+                prefixes: Vec::new(),
+                core: unary.core,
+                exts,
+            }),
+            runtime_local,
+        });
+    }
+
+    fn is_local(&self, name: StrId) -> bool {
+        self.locals.iter().any(|scope| scope.contains(&name))
+    }
+
+    fn runtime_local(&self, tree: &ExprTree) -> Option<Ident> {
+        match tree {
+            ExprTree::BinOp(lhs, _, rhs) => self.runtime_local(lhs).or_else(|| self.runtime_local(rhs)),
+            ExprTree::Unary(unary) => {
+                let in_core = match unary.core {
+                    ExprCore::Ident(ident) if self.is_local(ident.str_id()) => return Some(*ident),
+                    ExprCore::Tuple(tuple) => self.first_runtime_local(tuple.items.iter()),
+                    ExprCore::Array(array) => self.first_runtime_local(array.items.iter()),
+                    _ => None,
+                };
+
+                in_core.or_else(|| {
+                    unary.exts.iter().find_map(|ext| match ext {
+                        UnaryExprExt::Call(tuple) => self.first_runtime_local(tuple.items.iter()),
+                        UnaryExprExt::Index(array) => self.first_runtime_local(array.items.iter()),
+                        UnaryExprExt::Generics(generics) => self.first_runtime_local(generics.args.iter()),
+                        UnaryExprExt::Member(_) | UnaryExprExt::Construct(_) | UnaryExprExt::Question(_) => None,
+                    })
+                })
+            }
+        }
+    }
+
+    fn first_runtime_local<'e, T: ToExprTree + 'e>(&self, exprs: impl Iterator<Item = &'e T>) -> Option<Ident> {
+        exprs.filter_map(ToExprTree::tree).find_map(|tree| self.runtime_local(&tree))
+    }
+}
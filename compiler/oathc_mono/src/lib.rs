@@ -0,0 +1,14 @@
+use std::collections::{HashMap, HashSet};
+
+use oathc_ast::*;
+use oathc_comptime::{Comptime, Instance, Type, Value, generic_params, param_ident};
+use oathc_diagnostics::*;
+use oathc_interner::*;
+use oathc_res::{ItemId, Namespace};
+use oathc_span::*;
+use oathc_tokens::*;
+
+mod generic_uses;
+mod mono;
+use generic_uses::*;
+pub use mono::*;
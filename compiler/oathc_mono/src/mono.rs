@@ -0,0 +1,266 @@
+use super::*;
+
+/// How long a chain of instances instantiating each other may get before it's reported as infinite.
+pub const MAX_INSTANTIATION_DEPTH: usize = 64;
+/// The most instances a library may have.
+pub const MAX_INSTANCES: usize = 10_000;
+
+/// Instantiates generic items for every unique combination of generic arguments they're used with.
+///
+/// Starting from the items that aren't generic, the generic uses in every instance are evaluated at compile time
+/// with the instance's generic parameters bound to its arguments. Evaluating them normalizes the arguments,
+/// so `Polygon<1 + 2>` and `Polygon<3>` are the same instance. New instances are queued until there are none left,
+/// and a chain of instances that doesn't end is reported with the instantiations that led to it.
+#[derive(Debug)]
+pub struct Mono<'ctx, 'c> {
+    namespace: &'ctx Namespace<'ctx>,
+    interner: &'ctx Interner,
+    diagnostics: &'ctx Diagnostics,
+    comptime: &'c mut Comptime<'ctx>,
+    instances: Vec<InstanceInfo>,
+    ids: HashMap<Instance, InstanceId>,
+    /// Items whose generic uses were walked, so errors that don't depend on the instance are only reported once.
+    walked: HashSet<ItemId>,
+    /// Set once a limit is hit, after which nothing else is instantiated.
+    exhausted: bool,
+    errors: Vec<DiagnosticHandle>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct InstanceId(usize);
+
+#[derive(Debug, Clone)]
+pub struct InstanceInfo {
+    pub instance: Instance,
+    /// The instance that instantiated this one and where, `None` for items that aren't generic.
+    pub origin: Option<(InstanceId, Span)>,
+    pub depth: usize,
+}
+
+impl<'ctx, 'c> Mono<'ctx, 'c> {
+    pub fn new(comptime: &'c mut Comptime<'ctx>, interner: &'ctx Interner, diagnostics: &'ctx Diagnostics) -> Self {
+        Self {
+            namespace: comptime.namespace(),
+            interner,
+            diagnostics,
+            comptime,
+            instances: Vec::new(),
+            ids: HashMap::new(),
+            walked: HashSet::new(),
+            exhausted: false,
+            errors: Vec::new(),
+        }
+    }
+
+    pub fn instances(&self) -> &[InstanceInfo] {
+        &self.instances
+    }
+
+    pub fn instance(&self, id: InstanceId) -> &InstanceInfo {
+        &self.instances[id.0]
+    }
+
+    pub fn get(&self, instance: &Instance) -> Option<InstanceId> {
+        self.ids.get(instance).copied()
+    }
+
+    /// The generic parameters of an instance's item bound to its arguments, which is how they're substituted into the item.
+    pub fn bindings(&self, id: InstanceId) -> Vec<(StrId, Value)> {
+        let instance = &self.instances[id.0].instance;
+
+        generic_params(self.namespace.item(instance.item))
+            .iter()
+            .zip(&instance.args)
+            .filter_map(|(param, arg)| Some((param_ident(param)?.str_id(), arg.clone())))
+            .collect()
+    }
+
+    /// The instances of generic items, with the bindings of their generic parameters.
+    pub fn generic_instances(&self) -> Vec<(ItemId, Vec<(StrId, Value)>)> {
+        (0..self.instances.len())
+            .map(InstanceId)
+            .filter(|id| !self.instances[id.0].instance.args.is_empty())
+            .map(|id| (self.instances[id.0].instance.item, self.bindings(id)))
+            .collect()
+    }
+
+    /// Instantiates everything that's reachable from the items that aren't generic.
+    pub fn check_items(&mut self, errors: &mut Vec<DiagnosticHandle>) {
+        for (id, item) in self.namespace.items() {
+            let Try::Success(core) = &item.core else {
+                continue;
+            };
+            if !matches!(
+                core,
+                ItemCore::Fn(_) | ItemCore::Struct(_) | ItemCore::Enum(_) | ItemCore::Static(_)
+            ) {
+                continue;
+            }
+            let Some(name) = core.ident() else {
+                continue;
+            };
+
+            if generic_params(item).is_empty() {
+                let instance = Instance {
+                    item: id,
+                    name: name.str_id(),
+                    args: Vec::new(),
+                };
+
+                self.add(instance, None);
+            }
+        }
+
+        let mut next = 0;
+        while next < self.instances.len() && !self.exhausted {
+            self.instantiate_uses(InstanceId(next));
+            next += 1;
+        }
+
+        errors.append(&mut self.errors);
+    }
+
+    fn instantiate_uses(&mut self, id: InstanceId) {
+        let item_id = self.instances[id.0].instance.item;
+        let item = self.namespace.item(item_id);
+        let first_walk = self.walked.insert(item_id);
+        let bindings = self.bindings(id);
+
+        for use_ in GenericUses::of_item(item) {
+            if self.exhausted {
+                return;
+            }
+
+            if let Some(local) = use_.runtime_local {
+                if first_walk {
+                    let error = Error::NotComptime(local.span, local.str_id());
+                    self.errors.push(self.diagnostics.push_error(error));
+                }
+
+                continue;
+            }
+
+            match self.comptime.eval(&use_.tree, &bindings) {
+                Try::Success(value) => {
+                    let mut instances = Vec::new();
+                    instances_in_value(&value, &mut instances);
+
+                    for instance in instances {
+                        self.add(instance, Some((id, use_.tree.span())));
+                    }
+                }
+                Try::Failure(error) => self.errors.extend(error),
+            }
+        }
+    }
+
+    fn add(&mut self, instance: Instance, origin: Option<(InstanceId, Span)>) {
+        if self.ids.contains_key(&instance) {
+            return;
+        }
+
+        let depth = origin.map_or(0, |(parent, _)| self.instances[parent.0].depth + 1);
+        if let Some((parent, span)) = origin {
+            let error = if depth > MAX_INSTANTIATION_DEPTH {
+                Some(Error::InstantiationDepth(
+                    span,
+                    instance.to_string_interned(self.interner),
+                    self.backtrace(parent),
+                ))
+            } else if self.instances.len() >= MAX_INSTANCES {
+                Some(Error::InstanceLimit(
+                    span,
+                    instance.to_string_interned(self.interner),
+                    self.backtrace(parent),
+                ))
+            } else {
+                None
+            };
+
+            if let Some(error) = error {
+                self.errors.push(self.diagnostics.push_error(error));
+                self.exhausted = true;
+                return;
+            }
+        }
+
+        self.ids.insert(instance.clone(), InstanceId(self.instances.len()));
+        self.instances.push(InstanceInfo { instance, origin, depth });
+    }
+
+    /// Where each instance in the chain that led to an instance was instantiated, starting from the outermost one.
+    ///
+    /// Consecutive instantiations at the same place, which is what recursion looks like, are merged into one label.
+    fn backtrace(&self, id: InstanceId) -> Vec<Label> {
+        let mut chain = Vec::new();
+        let mut next = Some(id);
+        while let Some(id) = next {
+            let info = &self.instances[id.0];
+            if let Some((_, span)) = info.origin {
+                chain.push((span, &info.instance));
+            }
+            next = info.origin.map(|(parent, _)| parent);
+        }
+        chain.reverse();
+
+        let mut labels = Vec::<(Span, &Instance, &Instance)>::new();
+        for (span, instance) in chain {
+            match labels.last_mut() {
+                Some((last_span, _, last)) if *last_span == span => *last = instance,
+                _ => labels.push((span, instance, instance)),
+            }
+        }
+
+        labels
+            .into_iter()
+            .map(|(span, first, last)| Label {
+                span,
+                message: match first == last {
+                    true => format!("instantiates `{}`", Interned(first, self.interner)),
+                    false => format!(
+                        "instantiates `{}` through `{}`",
+                        Interned(first, self.interner),
+                        Interned(last, self.interner),
+                    ),
+                },
+            })
+            .collect()
+    }
+}
+
+/// The instances of generic items a value refers to.
+fn instances_in_value(value: &Value, output: &mut Vec<Instance>) {
+    match value {
+        Value::Int(_) | Value::Bool(_) | Value::Bound(_) | Value::Range(..) => {}
+        Value::Type(type_) => instances_in_type(type_, output),
+        Value::Tuple(values) | Value::Array(values) => values.iter().for_each(|value| instances_in_value(value, output)),
+        Value::Struct(instance, fields) => {
+            instances_in_instance(instance, output);
+            fields.iter().for_each(|(_, value)| instances_in_value(value, output));
+        }
+        Value::Fn(instance) => instances_in_instance(instance, output),
+    }
+}
+
+fn instances_in_type(type_: &Type, output: &mut Vec<Instance>) {
+    match type_ {
+        Type::Int(_) | Type::Bool | Type::Type => {}
+        Type::Tuple(types) => types.iter().for_each(|type_| instances_in_type(type_, output)),
+        Type::Array(item, _) => instances_in_type(item, output),
+        Type::Struct(instance) | Type::Enum(instance) => instances_in_instance(instance, output),
+        Type::Refined(base, _) => instances_in_type(base, output),
+        Type::Fn(inputs, output_type) => {
+            inputs.iter().for_each(|type_| instances_in_type(type_, output));
+            instances_in_type(output_type, output);
+        }
+    }
+}
+
+fn instances_in_instance(instance: &Instance, output: &mut Vec<Instance>) {
+    if instance.args.is_empty() {
+        return;
+    }
+
+    instance.args.iter().for_each(|arg| instances_in_value(arg, output));
+    output.push(instance.clone());
+}
@@ -0,0 +1,440 @@
+use std::collections::HashMap;
+
+use oathc_ast::{
+    AngleUnaryExpr, BinOp, ExprCore, ExprTree, Fn, FnBody, Impl, ItemCore, Param, RefModifier, Substituted, ToExprTree, Trait,
+    UnOp, UnaryExprExt, UnaryExprRef,
+};
+
+use super::*;
+
+impl<'m> Namespace<'m> {
+    /// Checks that every trait impl implements all the members of its trait, and nothing else,
+    /// with the signatures the trait declares.
+    pub fn check_impls(&self, interner: &Interner, diagnostics: &Diagnostics, errors: &mut Vec<DiagnosticHandle>) {
+        for (_, item) in self.items() {
+            let Try::Success(ItemCore::Impl(impl_)) = &item.core else {
+                continue;
+            };
+
+            let mut check = ImplCheck {
+                namespace: self,
+                interner,
+                diagnostics,
+                errors: &mut *errors,
+            };
+            check.impl_(impl_);
+        }
+    }
+
+    /// Resolves the trait a trait impl implements, along with the generic arguments it's implemented with.
+    ///
+    /// Returns `None` for inherent impls, and for impls of anything that isn't a trait.
+    pub fn impl_trait<'i>(&self, impl_: &'i Impl) -> Option<(ItemId, &'m Trait, Vec<ExprTree<'i>>)> {
+        let tree = impl_.trait_()?.tree()?;
+        let unary = tree.as_unary()?;
+        let ExprCore::Ident(ident) = unary.core else {
+            return None;
+        };
+
+        let id = self.get(ident.str_id())?;
+        let Try::Success(ItemCore::Trait(trait_)) = &self.item(id).core else {
+            return None;
+        };
+
+        let args = unary
+            .exts
+            .iter()
+            .find_map(|ext| match ext {
+                UnaryExprExt::Generics(generics) => Some(generics.args.iter().filter_map(ToExprTree::tree).collect()),
+                _ => None,
+            })
+            .unwrap_or_default();
+
+        Some((id, trait_, args))
+    }
+}
+
+struct ImplCheck<'a> {
+    namespace: &'a Namespace<'a>,
+    interner: &'a Interner,
+    diagnostics: &'a Diagnostics,
+    errors: &'a mut Vec<DiagnosticHandle>,
+}
+
+impl<'a> ImplCheck<'a> {
+    fn impl_(&mut self, impl_: &Impl) {
+        let Some(trait_tree) = impl_.trait_().and_then(ToExprTree::tree) else {
+            return;
+        };
+        let Some(ident) = trait_tree.as_unary().and_then(|unary| match unary.core {
+            ExprCore::Ident(ident) => Some(*ident),
+            _ => None,
+        }) else {
+            return;
+        };
+
+        let Some((_, trait_, args)) = self.namespace.impl_trait(impl_) else {
+            match self.namespace.get(ident.str_id()) {
+                Some(_) => self.error(Error::NotATrait(ident.span(), ident.str_id())),
+                None => self.error(Error::DoesntExist(ident.span(), ident.str_id())),
+            }
+            return;
+        };
+
+        // The trait's generic parameters and target stand for the impl's arguments and target type.
+        let mut substitutions = HashMap::new();
+        let params = trait_
+            .generics
+            .iter()
+            .flat_map(|generics| &generics.items)
+            .filter(|param| !param.is_lifetime());
+        for (param, arg) in params.zip(&args) {
+            if let Some(name) = param_ident(param) {
+                substitutions.insert(name, self.resolve(arg, &HashMap::new()));
+            }
+        }
+        let target = trait_.target.as_ref().and_then(|target| target.target.tree());
+        if let (Some(name), Some(type_)) = (
+            target
+                .as_ref()
+                .and_then(ExprTree::as_unary)
+                .and_then(|unary| unary.as_ident()),
+            impl_.target().tree(),
+        ) {
+            substitutions.insert(name.str_id(), self.resolve(&type_, &HashMap::new()));
+        }
+
+        let members = impl_
+            .items()
+            .iter()
+            .filter_map(|item| Some((item.core.success_ref()?.ident()?.str_id(), item.core.success_ref()?)))
+            .collect::<HashMap<_, _>>();
+
+        for member in trait_.items() {
+            let Some(expected) = member.core.success_ref() else {
+                continue;
+            };
+            let Some(name) = expected.ident() else {
+                continue;
+            };
+
+            match members.get(&name.str_id()) {
+                Some(found) => self.member(ident.str_id(), expected, found, &substitutions),
+                None if is_required(expected) => {
+                    self.error(Error::MissingTraitMember(trait_tree.span(), ident.str_id(), name.str_id()))
+                }
+                None => {}
+            }
+        }
+
+        for item in impl_.items() {
+            let Some(name) = item.core.success_ref().and_then(ItemCore::ident) else {
+                continue;
+            };
+
+            let in_trait = trait_.items().iter().any(|member| {
+                member
+                    .core
+                    .success_ref()
+                    .and_then(ItemCore::ident)
+                    .map(|ident| ident.str_id())
+                    == Some(name.str_id())
+            });
+            if !in_trait {
+                self.error(Error::NotATraitMember(name.span(), name.str_id(), ident.str_id()));
+            }
+        }
+    }
+
+    fn member(&mut self, trait_: StrId, expected: &ItemCore, found: &ItemCore, substitutions: &Substitutions) {
+        let Some(name) = found.ident() else {
+            return;
+        };
+
+        match (expected, found) {
+            (ItemCore::Fn(expected), ItemCore::Fn(found)) => self.fn_(name.span(), name.str_id(), expected, found, substitutions),
+            (ItemCore::Static(expected), ItemCore::Static(found)) => {
+                if let (Try::Success(expected), Try::Success(found)) = (&expected.param, &found.param) {
+                    self.param(expected, found, substitutions);
+                }
+            }
+            (ItemCore::Fn(_), _) => self.error(Error::TraitMemberKind(name.span(), name.str_id(), "a fn", trait_)),
+            (ItemCore::Static(_), _) => self.error(Error::TraitMemberKind(name.span(), name.str_id(), "a static", trait_)),
+            _ => {}
+        }
+    }
+
+    fn fn_(&mut self, span: Span, name: StrId, expected: &Fn, found: &Fn, substitutions: &Substitutions) {
+        let expected_generics = expected.generics.as_ref().map_or(&[][..], |generics| &generics.items);
+        let found_generics = found.generics.as_ref().map_or(&[][..], |generics| &generics.items);
+        if expected_generics.len() != found_generics.len() {
+            self.error(Error::TraitParamCount(
+                span,
+                name,
+                expected_generics.len(),
+                "generic parameters",
+                found_generics.len(),
+            ));
+            return;
+        }
+
+        // The generic parameters of the member itself may be named differently in the impl.
+        let mut substitutions = substitutions.clone();
+        for (expected, found) in expected_generics.iter().zip(found_generics) {
+            if let (Some(expected), Some(found)) = (param_ident(expected), param_ident(found)) {
+                substitutions.insert(expected, ResolvedType::Named(found, Vec::new()));
+            }
+        }
+        for (expected, found) in expected_generics.iter().zip(found_generics) {
+            self.param(expected, found, &substitutions);
+        }
+
+        let (Try::Success(expected_input), Try::Success(found_input)) = (&expected.input, &found.input) else {
+            return;
+        };
+        if expected_input.items.len() != found_input.items.len() {
+            self.error(Error::TraitParamCount(
+                span,
+                name,
+                expected_input.items.len(),
+                "parameters",
+                found_input.items.len(),
+            ));
+            return;
+        }
+        for (expected, found) in expected_input.items.iter().zip(&found_input.items) {
+            self.param(expected, found, &substitutions);
+        }
+
+        let expected_output = expected.output.as_ref().and_then(ToExprTree::tree).map(output_type);
+        let found_output = found.output.as_ref().and_then(ToExprTree::tree).map(output_type);
+        let span = found_output.as_ref().map_or(span, ExprTree::span);
+        self.type_(expected_output.as_ref(), found_output.as_ref(), span, &substitutions);
+    }
+
+    fn param(&mut self, expected: &Param, found: &Param, substitutions: &Substitutions) {
+        let expected_type = expected
+            .type_
+            .as_ref()
+            .and_then(AngleUnaryExpr::as_ref_expr)
+            .map(ExprTree::Unary);
+        let found_type = found
+            .type_
+            .as_ref()
+            .and_then(AngleUnaryExpr::as_ref_expr)
+            .map(ExprTree::Unary);
+        let Some(span) = found_type.as_ref().map(ExprTree::span).or(found.option_span()) else {
+            return;
+        };
+
+        self.type_(expected_type.as_ref(), found_type.as_ref(), span, substitutions);
+    }
+
+    fn type_(&mut self, expected: Option<&ExprTree>, found: Option<&ExprTree>, span: Span, substitutions: &Substitutions) {
+        let expected = match expected {
+            Some(expected) => self.resolve(expected, substitutions),
+            None => ResolvedType::Tuple(Vec::new()),
+        };
+        let found = match found {
+            Some(found) => self.resolve(found, &HashMap::new()),
+            None => ResolvedType::Tuple(Vec::new()),
+        };
+
+        if expected != found {
+            self.error(Error::TraitSignatureMismatch(
+                span,
+                expected.to_string_interned(self.interner),
+                found.to_string_interned(self.interner),
+            ));
+        }
+    }
+
+    /// Resolves a type, substituting the identifiers and following the `comptime static`s it names.
+    fn resolve(&self, tree: &ExprTree, substitutions: &Substitutions) -> ResolvedType {
+        self.resolve_at(tree, substitutions, 0)
+    }
+
+    fn resolve_at(&self, tree: &ExprTree, substitutions: &Substitutions, depth: usize) -> ResolvedType {
+        let other = || {
+            let substitutions = substitutions
+                .iter()
+                .map(|(name, type_)| (*name, type_.to_string_interned(self.interner)))
+                .collect();
+            ResolvedType::Other(Substituted(tree, &substitutions).to_string_interned(self.interner))
+        };
+        let Some(unary) = tree.as_unary() else {
+            return other();
+        };
+
+        if let Some((prefix, rest)) = unary.prefixes.split_first() {
+            let UnOp::Ref(ref_) = prefix else {
+                return other();
+            };
+            let kind = match &ref_.bounds {
+                None => "&".to_string(),
+                Some(RefModifier::Mut(_)) => "&mut ".to_string(),
+                Some(RefModifier::Sole(_)) => "&sole ".to_string(),
+                Some(RefModifier::SoleMut(_)) => "&smut ".to_string(),
+                Some(RefModifier::Lifetime(_)) => return other(),
+            };
+            let inner = UnaryExprRef {
+                prefixes: rest.to_vec(),
+                ..unary.clone()
+            };
+
+            return ResolvedType::Ref(kind, Box::new(self.resolve_at(&ExprTree::Unary(inner), substitutions, depth)));
+        }
+
+        let generics = match unary.exts.as_slice() {
+            [] => None,
+            [UnaryExprExt::Generics(generics)] => Some(generics),
+            _ => return other(),
+        };
+        let resolve_all = |exprs: &[oathc_ast::Expr]| {
+            exprs
+                .iter()
+                .map(|expr| match expr.tree() {
+                    Some(tree) => self.resolve_at(&tree, substitutions, depth),
+                    None => ResolvedType::Other("?".to_string()),
+                })
+                .collect::<Vec<_>>()
+        };
+
+        match (unary.core, generics) {
+            (ExprCore::Ident(ident), None) => {
+                if let Some(type_) = substitutions.get(&ident.str_id()) {
+                    return type_.clone();
+                }
+                match self.alias(ident.str_id()) {
+                    Some(value) if depth < MAX_ALIAS_DEPTH => self.resolve_at(&value, &HashMap::new(), depth + 1),
+                    _ => ResolvedType::Named(ident.str_id(), Vec::new()),
+                }
+            }
+            (ExprCore::Ident(ident), Some(generics)) => ResolvedType::Named(ident.str_id(), resolve_all(&generics.args)),
+            (ExprCore::Tuple(tuple), None) => {
+                let mut items = resolve_all(&tuple.items);
+                match items.len() {
+                    1 => items.pop().unwrap(),
+                    _ => ResolvedType::Tuple(items),
+                }
+            }
+            (ExprCore::Array(array), None) if array.items.len() == 1 => {
+                let item = resolve_all(&array.items).pop().unwrap();
+                let len = array
+                    .len
+                    .as_ref()
+                    .and_then(|len| len.len.tree())
+                    .map(|len| Box::new(self.resolve_at(&len, substitutions, depth)));
+
+                ResolvedType::Array(Box::new(item), len)
+            }
+            _ => other(),
+        }
+    }
+
+    /// The value of the `comptime static` with the name, which stands for the type it's set to.
+    fn alias(&self, name: StrId) -> Option<ExprTree<'a>> {
+        let item = self.namespace.item(self.namespace.get(name)?);
+        let Try::Success(ItemCore::Static(static_)) = &item.core else {
+            return None;
+        };
+        if !item.is_comptime() {
+            return None;
+        }
+
+        static_.value.as_ref()?.value.tree()
+    }
+
+    fn error(&mut self, error: Error) {
+        self.errors.push(self.diagnostics.push_error(error));
+    }
+}
+
+/// How many `comptime static`s a type is followed through, which stops ones that name each other.
+const MAX_ALIAS_DEPTH: usize = 16;
+
+type Substitutions = HashMap<StrId, ResolvedType>;
+
+/// A type with its generic parameters substituted, the `comptime static`s it names followed and its parentheses dropped,
+/// which the signatures of trait members and their impls are compared by.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ResolvedType {
+    Named(StrId, Vec<ResolvedType>),
+    Tuple(Vec<ResolvedType>),
+    /// `[T; N]`, or `[T]` without a length.
+    Array(Box<ResolvedType>, Option<Box<ResolvedType>>),
+    /// A reference, with how it's written up to its type, like `&mut `.
+    Ref(String, Box<ResolvedType>),
+    /// Any other expression, as it's written with its identifiers substituted.
+    Other(String),
+}
+
+impl InternedDisplay for ResolvedType {
+    fn interned_fmt(&self, f: &mut std::fmt::Formatter, interner: &Interner) -> std::fmt::Result {
+        let list = |f: &mut std::fmt::Formatter, types: &[ResolvedType]| {
+            for (index, type_) in types.iter().enumerate() {
+                if index > 0 {
+                    write!(f, ", ")?;
+                }
+                type_.interned_fmt(f, interner)?;
+            }
+
+            Ok(())
+        };
+
+        match self {
+            Self::Named(name, args) => {
+                name.interned_fmt(f, interner)?;
+                if !args.is_empty() {
+                    write!(f, "<")?;
+                    list(f, args)?;
+                    write!(f, ">")?;
+                }
+
+                Ok(())
+            }
+            Self::Tuple(items) => {
+                write!(f, "(")?;
+                list(f, items)?;
+                write!(f, ")")
+            }
+            Self::Array(item, len) => {
+                write!(f, "[")?;
+                item.interned_fmt(f, interner)?;
+                if let Some(len) = len {
+                    write!(f, "; ")?;
+                    len.interned_fmt(f, interner)?;
+                }
+                write!(f, "]")
+            }
+            Self::Ref(kind, type_) => {
+                write!(f, "{kind}")?;
+                type_.interned_fmt(f, interner)
+            }
+            Self::Other(text) => write!(f, "{text}"),
+        }
+    }
+}
+
+/// Whether a trait member has to be implemented, which is when the trait only declares it.
+fn is_required(member: &ItemCore) -> bool {
+    match member {
+        ItemCore::Fn(fn_) => matches!(fn_.body, Try::Success(FnBody::Semi(_))),
+        ItemCore::Static(static_) => static_.value.is_none(),
+        _ => false,
+    }
+}
+
+fn param_ident(param: &Param) -> Option<StrId> {
+    Some(param.body.success_ref()?.as_ident()?.str_id())
+}
+
+/// The type of an output, without its bounds.
+fn output_type(tree: ExprTree) -> ExprTree {
+    let mut tree = tree;
+    while let ExprTree::BinOp(lhs, BinOp::Bound(_), _) = tree {
+        tree = *lhs;
+    }
+
+    tree
+}
@@ -26,6 +26,7 @@ impl Item {
                     oathc_ast::ItemCore::Attr(core) => core.hash.span(),
                     oathc_ast::ItemCore::Enum(core) => core.ident.option_span().unwrap_or(core.keyword.span()),
                     oathc_ast::ItemCore::Fn(core) => core.ident.option_span().unwrap_or(core.keyword.span()),
+                    oathc_ast::ItemCore::Impl(core) => core.keyword.span(),
                     oathc_ast::ItemCore::Mod(core) => core.ident.option_span().unwrap_or(core.keyword.span()),
                     oathc_ast::ItemCore::Static(core) => core.keyword.span(),
                     oathc_ast::ItemCore::Struct(core) => core.ident.option_span().unwrap_or(core.keyword.span()),
@@ -39,4 +40,11 @@ impl Item {
             Try::Failure(_) => Self::Error(ast),
         }
     }
+
+    pub fn ast(&self) -> Option<&oathc_ast::Item> {
+        match self {
+            Self::ToDo(_, ast) => Some(ast),
+            Self::Mod(_) | Self::Error(_) => None,
+        }
+    }
 }
@@ -10,7 +10,10 @@ use oathc_parser::Leftovers;
 use oathc_span::*;
 use oathc_tokens::*;
 
+mod impls;
 mod item;
 mod mod_;
+mod namespace;
 pub use item::*;
 pub use mod_::*;
+pub use namespace::*;
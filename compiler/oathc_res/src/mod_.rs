@@ -31,13 +31,12 @@ pub enum ItemMod {
 #[derive(Debug)]
 pub struct ItemModBlock {
     _delims: delims!("{ }"),
-    _mod_: Mod,
+    mod_: Mod,
 }
 
 #[derive(Debug)]
 pub struct Mod {
     _submod_dir: Option<PathBuf>,
-    #[allow(dead_code)]
     items: Vec<Item>,
     #[allow(dead_code)]
     leftovers: Leftovers,
@@ -75,20 +74,26 @@ impl DiskMod {
         }
     }
 
-    pub fn check(&mut self, interner: &Interner, file_interner: &FileInterner, diagnostics: &Diagnostics) {
+    /// Reloads the module if its file changed, returns whether anything in the module tree was reloaded.
+    pub fn check(&mut self, interner: &Interner, file_interner: &FileInterner, diagnostics: &Diagnostics) -> bool {
         let raw_file = ModRawFile::try_new(&self.path_as_file, &self.path_as_dir, self.ident, diagnostics);
 
         if let (Some(raw_file), Some(previous_file)) = (raw_file.success_ref(), self.file.success_mut()) {
             if raw_file.is_unchanged_from(previous_file) {
-                if let Try::Success(content) = &mut previous_file.content {
-                    content.check_submods(interner, file_interner, diagnostics);
-                }
-
-                return;
+                return match &mut previous_file.content {
+                    Try::Success(content) => content.check_submods(interner, file_interner, diagnostics),
+                    Try::Failure(_) => false,
+                };
             }
         }
 
         self.file = raw_file.map(|raw_file| raw_file.resolve(interner, file_interner, diagnostics));
+
+        true
+    }
+
+    pub fn content(&self) -> Option<&Mod> {
+        self.file.success_ref().and_then(|file| file.content.success_ref())
     }
 
     pub fn get_highlights(&self) -> &[Highlight] {
@@ -213,6 +218,14 @@ impl ModRawFile {
 }
 
 impl ItemMod {
+    pub fn content(&self) -> Option<&Mod> {
+        match self {
+            Self::File(mod_) => mod_.content(),
+            Self::Block(block) => Some(&block.mod_),
+            Self::Failure(_) => None,
+        }
+    }
+
     pub(super) fn new(
         ast: oathc_ast::Mod,
         submod_dir: Option<&Path>,
@@ -223,7 +236,7 @@ impl ItemMod {
         match ast.body {
             Try::Success(oathc_ast::ModBody::Block(body_ast)) => Self::Block(ItemModBlock {
                 _delims: body_ast.frame.delims,
-                _mod_: Mod::new(
+                mod_: Mod::new(
                     None,
                     SyntaxTree {
                         items: body_ast.items,
@@ -287,11 +300,19 @@ impl Mod {
         }
     }
 
-    fn check_submods(&mut self, interner: &Interner, file_interner: &FileInterner, diagnostics: &Diagnostics) {
+    pub fn items(&self) -> &[Item] {
+        &self.items
+    }
+
+    fn check_submods(&mut self, interner: &Interner, file_interner: &FileInterner, diagnostics: &Diagnostics) -> bool {
+        let mut changed = false;
+
         for item in &mut self.items {
             if let Item::Mod(ItemMod::File(mod_)) = item {
-                mod_.check(interner, file_interner, diagnostics);
+                changed |= mod_.check(interner, file_interner, diagnostics);
             }
         }
+
+        changed
     }
 }
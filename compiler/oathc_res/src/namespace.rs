@@ -0,0 +1,96 @@
+use std::collections::{HashMap, hash_map::Entry};
+
+use super::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ItemId(usize);
+
+/// The items of a lib, indexed by name.
+///
+/// Modules don't introduce separate scopes yet, so every named item in the module tree is visible everywhere in its lib.
+#[derive(Debug)]
+pub struct Namespace<'m> {
+    items: Vec<&'m oathc_ast::Item>,
+    /// The inner attributes of the modules every item is in, like `#![warn(overflow)]`, from the outermost module in.
+    mod_attrs: Vec<Vec<&'m oathc_ast::AttrBody>>,
+    names: HashMap<StrId, ItemId>,
+}
+
+impl<'m> Namespace<'m> {
+    pub fn new(mod_: &'m Mod, diagnostics: &Diagnostics, errors: &mut Vec<DiagnosticHandle>) -> Self {
+        let mut output = Self {
+            items: Vec::new(),
+            mod_attrs: Vec::new(),
+            names: HashMap::new(),
+        };
+
+        output.insert_mod(mod_, &[], diagnostics, errors);
+
+        output
+    }
+
+    pub fn get(&self, name: StrId) -> Option<ItemId> {
+        self.names.get(&name).copied()
+    }
+
+    pub fn item(&self, id: ItemId) -> &'m oathc_ast::Item {
+        self.items[id.0]
+    }
+
+    pub fn items(&self) -> impl Iterator<Item = (ItemId, &'m oathc_ast::Item)> + '_ {
+        self.items.iter().enumerate().map(|(index, item)| (ItemId(index), *item))
+    }
+
+    pub fn mod_attrs(&self, id: ItemId) -> &[&'m oathc_ast::AttrBody] {
+        &self.mod_attrs[id.0]
+    }
+
+    fn insert_mod(
+        &mut self,
+        mod_: &'m Mod,
+        outer_attrs: &[&'m oathc_ast::AttrBody],
+        diagnostics: &Diagnostics,
+        errors: &mut Vec<DiagnosticHandle>,
+    ) {
+        let mut attrs = outer_attrs.to_vec();
+        attrs.extend(mod_.items().iter().filter_map(Item::ast).filter_map(|ast| match &ast.core {
+            Try::Success(oathc_ast::ItemCore::Attr(attr)) => attr.body.success_ref(),
+            _ => None,
+        }));
+
+        for item in mod_.items() {
+            match item {
+                Item::Mod(item_mod) => {
+                    if let Some(content) = item_mod.content() {
+                        self.insert_mod(content, &attrs, diagnostics, errors);
+                    }
+                }
+                Item::ToDo(_, ast) => self.insert(ast, attrs.clone(), diagnostics, errors),
+                Item::Error(_) => {}
+            }
+        }
+    }
+
+    fn insert(
+        &mut self,
+        ast: &'m oathc_ast::Item,
+        mod_attrs: Vec<&'m oathc_ast::AttrBody>,
+        diagnostics: &Diagnostics,
+        errors: &mut Vec<DiagnosticHandle>,
+    ) {
+        let id = ItemId(self.items.len());
+        self.items.push(ast);
+        self.mod_attrs.push(mod_attrs);
+
+        let Some(ident) = ast.core.success_ref().and_then(oathc_ast::ItemCore::ident) else {
+            return;
+        };
+
+        match self.names.entry(ident.str_id()) {
+            Entry::Occupied(_) => errors.push(diagnostics.push_error(Error::AlreadyExists(ident.span(), ident.str_id()))),
+            Entry::Vacant(entry) => {
+                entry.insert(id);
+            }
+        }
+    }
+}
@@ -352,6 +352,8 @@ impl<'ctx, 'parent> GroupTokenizer<'ctx, 'parent> {
     #[must_use]
     fn handle_close(&mut self, close: CloseDelimiter) -> Peek<CloseDelimiter> {
         Peek::Close(if close.kind == self.open().kind {
+            self.delims.close_span = close.span;
+
             match &mut self.parent {
                 ParentTokenizer::Group(parent) => {
                     parent.last_span = close.span;
@@ -44,7 +44,7 @@ define_tokens!(
         if, else, then, match,
         loop, while, until, for, in,
         eval, return, break, continue,
-        assume,
+        assume, all, any,
     ],
     delims: [
         "(" ")" Paren,
@@ -65,11 +65,15 @@ define_tokens!(
         "&=" AndAssign,
         "|=" OrAssign,
         "^=" CaretAssign,
+        "+%" PlusPercent,
+        "-%" MinusPercent,
+        "*%" StarPercent,
         "==" EqEq,
         "!=" NotEq,
         "::" ColonColon,
         ".." DotDot,
         "->" ArrowRight,
+        "=>" FatArrow,
         "<=" LessEq,
         ">=" MoreEq,
         "#!" HashExclamation,
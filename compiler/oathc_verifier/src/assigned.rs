@@ -0,0 +1,220 @@
+use super::*;
+
+/// What a block may assign to, so loops only forget what's known about those.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Assigned {
+    /// The names of the locals the block assigns to or borrows as `&mut`.
+    ///
+    /// Shadowing is ignored, so a name that's assigned anywhere in the block counts, whichever local it refers to.
+    pub locals: HashSet<StrId>,
+    /// Whether the block may write through a reference, by assigning through a dereference or by calling something,
+    /// which may assign any local that's borrowed as `&mut`.
+    pub through_refs: bool,
+}
+
+/// Collects what a block may assign to.
+pub(crate) fn assigned_in_block(block: &Block, output: &mut Assigned) {
+    for stmt in &block.stmts {
+        match stmt {
+            Stmt::Item(_) => {}
+            Stmt::Let(let_) => {
+                if let Some(assign) = &let_.value {
+                    assigned_in_expr(&assign.value, output);
+                }
+            }
+            Stmt::Eval(ControlStmt { value: Some(value), .. })
+            | Stmt::Return(ControlStmt { value: Some(value), .. })
+            | Stmt::Break(BreakStmt { value: Some(value), .. }) => assigned_in_expr(value, output),
+            Stmt::Eval(_) | Stmt::Return(_) | Stmt::Break(_) | Stmt::Continue(_) => {}
+            Stmt::Assume(_) => {}
+            Stmt::Expr(stmt) => {
+                assigned_in_expr(&stmt.expr, output);
+
+                if let Some(set) = &stmt.set {
+                    assigned_in_expr(&set.value, output);
+
+                    if let Some(tree) = stmt.expr.tree() {
+                        match place_root(&tree) {
+                            Some(ident) => {
+                                output.locals.insert(ident.str_id());
+                            }
+                            None => output.through_refs |= is_deref(&tree),
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The local a place expression like `a.b[c]` assigns to.
+pub(crate) fn place_root(tree: &ExprTree) -> Option<Ident> {
+    let unary = tree.as_unary()?;
+    if !unary.prefixes.is_empty() {
+        return None;
+    }
+
+    match unary.core {
+        ExprCore::Ident(ident) => Some(*ident),
+        _ => None,
+    }
+}
+
+/// Whether a place expression like `*a` or `*a.b` assigns through a reference.
+pub(crate) fn is_deref(tree: &ExprTree) -> bool {
+    tree.as_unary()
+        .is_some_and(|unary| unary.prefixes.iter().any(|prefix| matches!(prefix, UnOp::Deref(_))))
+}
+
+/// What a `&mut` or `&smut` borrow can be written through.
+pub(crate) enum MutBorrow {
+    /// A borrow of a local or a place in it, like `&mut a.b`.
+    Local(Ident),
+    /// A borrow through another reference, like `&mut *a`.
+    ThroughRef,
+}
+
+/// What a unary expression borrows as `&mut` or `&smut`, if it does and it isn't a temporary.
+pub(crate) fn mut_borrow(unary: &UnaryExprRef) -> Option<MutBorrow> {
+    let is_mut_ref = |prefix: &&UnOp| {
+        matches!(
+            prefix,
+            UnOp::Ref(Ref {
+                bounds: Some(RefModifier::Mut(_) | RefModifier::SoleMut(_)),
+                ..
+            })
+        )
+    };
+    if !unary.prefixes.iter().any(is_mut_ref) {
+        return None;
+    }
+    if unary.prefixes.iter().any(|prefix| matches!(prefix, UnOp::Deref(_))) {
+        return Some(MutBorrow::ThroughRef);
+    }
+
+    match unary.core {
+        ExprCore::Ident(ident) => Some(MutBorrow::Local(*ident)),
+        _ => None,
+    }
+}
+
+fn assigned_in_expr(expr: &impl ToExprTree, output: &mut Assigned) {
+    if let Some(tree) = expr.tree() {
+        assigned_in_tree(&tree, output);
+    }
+}
+
+fn assigned_in_tree(tree: &ExprTree, output: &mut Assigned) {
+    match tree {
+        ExprTree::BinOp(lhs, _, rhs) => {
+            assigned_in_tree(lhs, output);
+            assigned_in_tree(rhs, output);
+        }
+        ExprTree::Unary(unary) => {
+            assigned_in_core(unary.core, output);
+
+            match mut_borrow(unary) {
+                Some(MutBorrow::Local(ident)) => {
+                    output.locals.insert(ident.str_id());
+                }
+                Some(MutBorrow::ThroughRef) => output.through_refs = true,
+                None => {}
+            }
+
+            for ext in &unary.exts {
+                match ext {
+                    UnaryExprExt::Member(_) | UnaryExprExt::Question(_) => {}
+                    UnaryExprExt::Call(tuple) => {
+                        output.through_refs = true;
+                        tuple.items.iter().for_each(|item| assigned_in_expr(item, output));
+                    }
+                    UnaryExprExt::Index(array) => array.items.iter().for_each(|item| assigned_in_expr(item, output)),
+                    UnaryExprExt::Generics(generics) => generics.args.iter().for_each(|arg| assigned_in_expr(arg, output)),
+                    UnaryExprExt::Construct(construct) => {
+                        for field in &construct.items {
+                            if let Try::Success(set) = &field.set {
+                                assigned_in_expr(&set.value, output);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn assigned_in_core(core: &ExprCore, output: &mut Assigned) {
+    match core {
+        ExprCore::Ident(_) | ExprCore::Keyword(_) | ExprCore::Literal(_) => {}
+        ExprCore::Block(block) => assigned_in_block(block, output),
+        ExprCore::Tuple(tuple) => tuple.items.iter().for_each(|item| assigned_in_expr(item, output)),
+        ExprCore::Array(array) => {
+            array.items.iter().for_each(|item| assigned_in_expr(item, output));
+            if let Some(len) = &array.len {
+                assigned_in_expr(&len.len, output);
+            }
+        }
+        ExprCore::If(if_) => {
+            assigned_in_expr(&if_.condition, output);
+
+            let else_ = match &if_.body {
+                IfBody::Then(then) => {
+                    assigned_in_expr(&then.expr, output);
+                    &then.else_
+                }
+                IfBody::Block(block) => {
+                    if let Try::Success(block) = &block.block {
+                        assigned_in_block(block, output);
+                    }
+                    &block.else_
+                }
+            };
+            if let Some(else_) = else_ {
+                assigned_in_expr(&else_.expr, output);
+            }
+        }
+        ExprCore::Loop(loop_) => {
+            if let Try::Success(block) = &loop_.block {
+                assigned_in_block(block, output);
+            }
+        }
+        ExprCore::Try(try_) => {
+            if let Try::Success(block) = &try_.block {
+                assigned_in_block(block, output);
+            }
+        }
+        ExprCore::While(while_) => {
+            assigned_in_expr(&while_.condition, output);
+            if let Try::Success(block) = &while_.block {
+                assigned_in_block(block, output);
+            }
+        }
+        ExprCore::Until(until) => {
+            assigned_in_expr(&until.condition, output);
+            if let Try::Success(block) = &until.block {
+                assigned_in_block(block, output);
+            }
+        }
+        ExprCore::For(for_) => {
+            assigned_in_expr(&for_.iter, output);
+            if let Try::Success(block) = &for_.block {
+                assigned_in_block(block, output);
+            }
+        }
+        ExprCore::Quantifier(quantifier) => {
+            assigned_in_expr(&quantifier.iter, output);
+            if let Try::Success(block) = &quantifier.block {
+                assigned_in_block(block, output);
+            }
+        }
+        ExprCore::Match(match_) => {
+            assigned_in_expr(&match_.value, output);
+            for arm in match_.body.success_ref().into_iter().flat_map(|body| &body.arms) {
+                if let Some(guard) = &arm.guard {
+                    assigned_in_expr(&guard.condition, output);
+                }
+                assigned_in_expr(&arm.value, output);
+            }
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use super::*;
+
+/// An `assume` statement, whose fact is trusted without being proven.
+#[derive(Debug, Clone)]
+pub struct Assume {
+    pub span: Span,
+    /// The name of the item the statement is in.
+    pub item: String,
+    pub fact: String,
+}
+
+/// Lists the `assume` statements of a lib for audits, in the order of its items.
+pub fn assumes(namespace: &Namespace, interner: &Interner) -> Vec<Assume> {
+    let mut output = Vec::new();
+
+    for (_, item) in namespace.items() {
+        let mut stmts = Vec::new();
+        assumes_in_item(item, &mut stmts);
+
+        let name = match item.core.success_ref().and_then(ItemCore::ident) {
+            Some(ident) => ident.to_string_interned(interner),
+            None => "_".to_string(),
+        };
+        output.extend(stmts.into_iter().map(|stmt| Assume {
+            span: stmt.keyword.span().connect(stmt.value.option_span()),
+            item: name.clone(),
+            fact: match stmt.value.tree() {
+                Some(tree) => tree.to_string_interned(interner),
+                None => "_".to_string(),
+            },
+        }));
+    }
+
+    output
+}
+
+/// Collects the `assume` statements in the body of an item, including the ones in unreachable code and in closures.
+pub(crate) fn assumes_in_item<'a>(item: &'a Item, output: &mut Vec<&'a AssumeStmt>) {
+    match &item.core {
+        Try::Success(ItemCore::Fn(fn_)) => assumes_in_fn(fn_, output),
+        Try::Success(ItemCore::Impl(impl_)) => {
+            for item in impl_.items() {
+                if let Try::Success(ItemCore::Fn(fn_)) = &item.core {
+                    assumes_in_fn(fn_, output);
+                }
+            }
+        }
+        Try::Success(ItemCore::Static(Static { value: Some(assign), .. })) => assumes_in_expr(&assign.value, output),
+        _ => {}
+    }
+}
+
+fn assumes_in_fn<'a>(fn_: &'a Fn, output: &mut Vec<&'a AssumeStmt>) {
+    if let Try::Success(FnBody::Block(body)) = &fn_.body {
+        assumes_in_block(body, output);
+    }
+}
+
+fn assumes_in_block<'a>(block: &'a Block, output: &mut Vec<&'a AssumeStmt>) {
+    for stmt in &block.stmts {
+        match stmt {
+            Stmt::Item(_) => {}
+            Stmt::Let(let_) => {
+                if let Some(assign) = &let_.value {
+                    assumes_in_expr(&assign.value, output);
+                }
+            }
+            Stmt::Eval(ControlStmt { value: Some(value), .. })
+            | Stmt::Return(ControlStmt { value: Some(value), .. })
+            | Stmt::Break(BreakStmt { value: Some(value), .. }) => assumes_in_expr(value, output),
+            Stmt::Eval(_) | Stmt::Return(_) | Stmt::Break(_) | Stmt::Continue(_) => {}
+            Stmt::Assume(stmt) => output.push(stmt),
+            Stmt::Expr(stmt) => {
+                assumes_in_expr(&stmt.expr, output);
+                if let Some(set) = &stmt.set {
+                    assumes_in_expr(&set.value, output);
+                }
+            }
+        }
+    }
+}
+
+fn assumes_in_expr<'a>(expr: &'a impl ToExprTree, output: &mut Vec<&'a AssumeStmt>) {
+    if let Some(tree) = expr.tree() {
+        assumes_in_tree(&tree, output);
+    }
+}
+
+fn assumes_in_tree<'a>(tree: &ExprTree<'a>, output: &mut Vec<&'a AssumeStmt>) {
+    match tree {
+        ExprTree::BinOp(lhs, _, rhs) => {
+            assumes_in_tree(lhs, output);
+            assumes_in_tree(rhs, output);
+        }
+        ExprTree::Unary(unary) => {
+            assumes_in_core(unary.core, output);
+
+            for ext in &unary.exts {
+                match ext {
+                    UnaryExprExt::Member(_) | UnaryExprExt::Question(_) => {}
+                    UnaryExprExt::Call(tuple) => tuple.items.iter().for_each(|item| assumes_in_expr(item, output)),
+                    UnaryExprExt::Index(array) => array.items.iter().for_each(|item| assumes_in_expr(item, output)),
+                    UnaryExprExt::Generics(generics) => generics.args.iter().for_each(|arg| assumes_in_expr(arg, output)),
+                    UnaryExprExt::Construct(construct) => {
+                        for field in &construct.items {
+                            if let Try::Success(set) = &field.set {
+                                assumes_in_expr(&set.value, output);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn assumes_in_core<'a>(core: &'a ExprCore, output: &mut Vec<&'a AssumeStmt>) {
+    match core {
+        ExprCore::Keyword(ExprKeyword::Fn(fn_)) => {
+            if let Some(body) = &fn_.body {
+                assumes_in_block(body, output);
+            }
+        }
+        ExprCore::Ident(_) | ExprCore::Keyword(_) | ExprCore::Literal(_) => {}
+        ExprCore::Block(block) => assumes_in_block(block, output),
+        ExprCore::Tuple(tuple) => tuple.items.iter().for_each(|item| assumes_in_expr(item, output)),
+        ExprCore::Array(array) => {
+            array.items.iter().for_each(|item| assumes_in_expr(item, output));
+            if let Some(len) = &array.len {
+                assumes_in_expr(&len.len, output);
+            }
+        }
+        ExprCore::If(if_) => {
+            assumes_in_expr(&if_.condition, output);
+
+            let else_ = match &if_.body {
+                IfBody::Then(then) => {
+                    assumes_in_expr(&then.expr, output);
+                    &then.else_
+                }
+                IfBody::Block(block) => {
+                    if let Try::Success(block) = &block.block {
+                        assumes_in_block(block, output);
+                    }
+                    &block.else_
+                }
+            };
+            if let Some(else_) = else_ {
+                assumes_in_expr(&else_.expr, output);
+            }
+        }
+        ExprCore::Loop(loop_) => {
+            if let Try::Success(block) = &loop_.block {
+                assumes_in_block(block, output);
+            }
+        }
+        ExprCore::Try(try_) => {
+            if let Try::Success(block) = &try_.block {
+                assumes_in_block(block, output);
+            }
+        }
+        ExprCore::While(while_) => {
+            assumes_in_expr(&while_.condition, output);
+            if let Try::Success(block) = &while_.block {
+                assumes_in_block(block, output);
+            }
+        }
+        ExprCore::Until(until) => {
+            assumes_in_expr(&until.condition, output);
+            if let Try::Success(block) = &until.block {
+                assumes_in_block(block, output);
+            }
+        }
+        ExprCore::For(for_) => {
+            assumes_in_expr(&for_.iter, output);
+            if let Try::Success(block) = &for_.block {
+                assumes_in_block(block, output);
+            }
+        }
+        ExprCore::Quantifier(quantifier) => {
+            assumes_in_expr(&quantifier.iter, output);
+            if let Try::Success(block) = &quantifier.block {
+                assumes_in_block(block, output);
+            }
+        }
+        ExprCore::Match(match_) => {
+            assumes_in_expr(&match_.value, output);
+            for arm in match_.body.success_ref().into_iter().flat_map(|body| &body.arms) {
+                if let Some(guard) = &arm.guard {
+                    assumes_in_expr(&guard.condition, output);
+                }
+                assumes_in_expr(&arm.value, output);
+            }
+        }
+    }
+}
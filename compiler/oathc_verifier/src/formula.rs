@@ -0,0 +1,184 @@
+use super::*;
+
+/// A quantifier-free formula over integer variables.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Formula {
+    Bool(bool),
+    Atom(Atom),
+    Not(Box<Formula>),
+    And(Vec<Formula>),
+    Or(Vec<Formula>),
+}
+
+/// `linear <rel> 0`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Atom {
+    pub linear: Linear,
+    pub rel: Rel,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rel {
+    Eq,
+    NotEq,
+    MoreEq,
+}
+
+impl Formula {
+    pub const TRUE: Self = Self::Bool(true);
+    pub const FALSE: Self = Self::Bool(false);
+
+    /// Returns `None` if normalizing the comparison overflows.
+    pub fn cmp(lhs: &Linear, op: CmpOp, rhs: &Linear) -> Option<Self> {
+        let diff = lhs.sub(rhs)?;
+
+        let atom = match op {
+            CmpOp::Eq => Atom::new(diff, Rel::Eq),
+            CmpOp::NotEq => Atom::new(diff, Rel::NotEq),
+            CmpOp::MoreEq => Atom::new(diff, Rel::MoreEq),
+            CmpOp::More => Atom::new(diff.offset(-1)?, Rel::MoreEq),
+            CmpOp::LessEq => Atom::new(diff.scale(-1)?, Rel::MoreEq),
+            CmpOp::Less => Atom::new(diff.scale(-1)?.offset(-1)?, Rel::MoreEq),
+        };
+
+        Some(atom.into())
+    }
+
+    /// `min <= value <= max`, skipping a side if it's `None`.
+    pub fn range(value: &Linear, min: Option<i128>, max: Option<i128>) -> Option<Self> {
+        let mut output = Vec::new();
+        if let Some(min) = min {
+            output.push(Self::cmp(value, CmpOp::MoreEq, &Linear::constant(min))?);
+        }
+        if let Some(max) = max {
+            output.push(Self::cmp(value, CmpOp::LessEq, &Linear::constant(max))?);
+        }
+
+        Some(Self::and(output))
+    }
+
+    pub fn and(formulas: impl IntoIterator<Item = Self>) -> Self {
+        let mut output = Vec::new();
+        for formula in formulas {
+            match formula {
+                Self::Bool(true) => {}
+                Self::Bool(false) => return Self::FALSE,
+                Self::And(formulas) => output.extend(formulas),
+                formula => output.push(formula),
+            }
+        }
+
+        match output.len() {
+            0 => Self::TRUE,
+            1 => output.pop().unwrap(),
+            _ => Self::And(output),
+        }
+    }
+
+    pub fn or(formulas: impl IntoIterator<Item = Self>) -> Self {
+        let mut output = Vec::new();
+        for formula in formulas {
+            match formula {
+                Self::Bool(false) => {}
+                Self::Bool(true) => return Self::TRUE,
+                Self::Or(formulas) => output.extend(formulas),
+                formula => output.push(formula),
+            }
+        }
+
+        match output.len() {
+            0 => Self::FALSE,
+            1 => output.pop().unwrap(),
+            _ => Self::Or(output),
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(self) -> Self {
+        match self {
+            Self::Bool(value) => Self::Bool(!value),
+            Self::Not(formula) => *formula,
+            Self::Atom(atom) => match atom.negate() {
+                Some(atom) => atom.into(),
+                None => Self::Not(Box::new(Self::Atom(atom))),
+            },
+            formula => Self::Not(Box::new(formula)),
+        }
+    }
+
+    pub fn iff(lhs: Self, rhs: Self) -> Self {
+        Self::or([Self::and([lhs.clone(), rhs.clone()]), Self::and([lhs.not(), rhs.not()])])
+    }
+
+    pub fn xor(lhs: Self, rhs: Self) -> Self {
+        Self::iff(lhs, rhs).not()
+    }
+
+    pub fn vars(&self, output: &mut BTreeSet<Var>) {
+        match self {
+            Self::Bool(_) => {}
+            Self::Atom(atom) => output.extend(atom.linear.vars()),
+            Self::Not(formula) => formula.vars(output),
+            Self::And(formulas) | Self::Or(formulas) => formulas.iter().for_each(|formula| formula.vars(output)),
+        }
+    }
+
+    /// Whether the formula holds under a model, `None` if evaluating it overflows.
+    pub fn eval(&self, model: &Model) -> Option<bool> {
+        Some(match self {
+            Self::Bool(value) => *value,
+            Self::Atom(atom) => atom.eval(model)?,
+            Self::Not(formula) => !formula.eval(model)?,
+            Self::And(formulas) => formulas
+                .iter()
+                .map(|formula| formula.eval(model))
+                .collect::<Option<Vec<_>>>()?
+                .into_iter()
+                .all(|value| value),
+            Self::Or(formulas) => formulas
+                .iter()
+                .map(|formula| formula.eval(model))
+                .collect::<Option<Vec<_>>>()?
+                .into_iter()
+                .any(|value| value),
+        })
+    }
+}
+
+impl Atom {
+    pub fn new(linear: Linear, rel: Rel) -> Self {
+        Self { linear, rel }
+    }
+
+    pub fn eval(&self, model: &Model) -> Option<bool> {
+        let value = self.linear.eval(model)?;
+
+        Some(match self.rel {
+            Rel::Eq => value == 0,
+            Rel::NotEq => value != 0,
+            Rel::MoreEq => value >= 0,
+        })
+    }
+
+    /// Returns `None` if the negation overflows.
+    pub fn negate(&self) -> Option<Self> {
+        Some(match self.rel {
+            Rel::Eq => Self::new(self.linear.clone(), Rel::NotEq),
+            Rel::NotEq => Self::new(self.linear.clone(), Rel::Eq),
+            Rel::MoreEq => Self::new(self.linear.scale(-1)?.offset(-1)?, Rel::MoreEq),
+        })
+    }
+}
+
+impl From<Atom> for Formula {
+    fn from(value: Atom) -> Self {
+        match value.linear.as_constant() {
+            Some(constant) => Self::Bool(match value.rel {
+                Rel::Eq => constant == 0,
+                Rel::NotEq => constant != 0,
+                Rel::MoreEq => constant >= 0,
+            }),
+            None => Self::Atom(value),
+        }
+    }
+}
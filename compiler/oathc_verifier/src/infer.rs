@@ -0,0 +1,286 @@
+use super::*;
+
+/// What's known about the integer output of a function at its returns, for a function without `pub`,
+/// whose calls in the lib get it as if it were promised.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Inference {
+    pub min: Option<i128>,
+    pub max: Option<i128>,
+    /// The parameters the output is equal to, by their values on entry.
+    pub params: Vec<StrId>,
+}
+
+/// A contract inferred for a function without `pub`, which can be made explicit by writing it as a `promise`.
+#[derive(Debug, Clone)]
+pub struct InferredContract {
+    /// The name of the function.
+    pub span: Span,
+    /// Right before the body of the function, where the `promise` is written.
+    pub insert_at: Position,
+    pub item: String,
+    pub promises: Vec<String>,
+}
+
+impl InferredContract {
+    pub fn promise(&self) -> String {
+        format!("promise [{}]", self.promises.join(", "))
+    }
+}
+
+impl<'ctx, 'c> Verifier<'ctx, 'c> {
+    /// Infers what a function without `pub` promises about its integer output, once, by evaluating its body
+    /// where nothing is checked and joining what's known at its returns.
+    /// A function with output bounds or `promise`s is only known by them.
+    ///
+    /// A function that's being inferred has no inference, so recursive calls to it are only known by its contract.
+    pub(crate) fn infer(&mut self, id: ItemId) -> Option<Inference> {
+        if let Some(inference) = self.inferences.get(&id) {
+            return inference.clone();
+        }
+        self.inferences.insert(id, None);
+
+        let item = self.namespace.item(id);
+        let Try::Success(ItemCore::Fn(fn_)) = &item.core else {
+            return None;
+        };
+        let (Try::Success(input), Try::Success(FnBody::Block(body))) = (&fn_.input, &fn_.body) else {
+            return None;
+        };
+        if item.is_pub() {
+            return None;
+        }
+        let output = fn_
+            .output
+            .as_ref()
+            .and_then(ToExprTree::tree)
+            .map(|tree| self.read_type(&split_bounds(tree).0));
+        if !matches!(output, Some(Ty::Int(Some(_)))) {
+            return None;
+        }
+
+        let outer_scopes = replace(&mut self.scopes, vec![HashMap::new()]);
+        let outer_facts = take(&mut self.facts);
+        let outer_trace = take(&mut self.trace);
+        let outer_fn = self.fn_.take();
+        let outer_out = self.out.take();
+        let outer_reachable = replace(&mut self.reachable, true);
+        let outer_tries = take(&mut self.tries);
+        let outer_exit_contexts = take(&mut self.exit_contexts);
+        let outer_closures = take(&mut self.closures);
+        let outer_reads = take(&mut self.reads);
+        let outer_quantifiers = take(&mut self.quantifiers);
+        let outer_contract_depth = replace(&mut self.contract_depth, 1);
+
+        for param in fn_
+            .generics
+            .iter()
+            .flat_map(|generics| &generics.items)
+            .filter(|param| !param.is_lifetime())
+        {
+            self.bind_fresh_param(param);
+        }
+        self.enter_fn(input, &fn_.contract, fn_.output.as_ref());
+        if let Some(fn_) = self
+            .fn_
+            .as_mut()
+            .filter(|fn_| fn_.output_bounds.is_empty() && fn_.promises.is_empty())
+        {
+            fn_.returns = Some(Vec::new());
+        }
+
+        let (value, span) = self.verify_block(body);
+        if self.reachable {
+            self.check_return(value, span);
+        }
+        let returns = self.fn_.as_mut().and_then(|fn_| fn_.returns.take()).unwrap_or_default();
+
+        self.scopes = outer_scopes;
+        self.facts = outer_facts;
+        self.trace = outer_trace;
+        self.fn_ = outer_fn;
+        self.out = outer_out;
+        self.reachable = outer_reachable;
+        self.tries = outer_tries;
+        self.exit_contexts = outer_exit_contexts;
+        self.closures = outer_closures;
+        self.reads = outer_reads;
+        self.quantifiers = outer_quantifiers;
+        self.contract_depth = outer_contract_depth;
+
+        // What holds at every return: the loosest range, and the parameters the output is equal to at all of them.
+        let inference = returns.into_iter().reduce(|lhs, rhs| Inference {
+            min: lhs.min.zip(rhs.min).map(|(lhs, rhs)| lhs.min(rhs)),
+            max: lhs.max.zip(rhs.max).map(|(lhs, rhs)| lhs.max(rhs)),
+            params: lhs.params.into_iter().filter(|param| rhs.params.contains(param)).collect(),
+        });
+        let mut inference =
+            inference.filter(|inference| inference.min.is_some() || inference.max.is_some() || !inference.params.is_empty());
+        if let Some(inference) = &mut inference {
+            let order = |name: &StrId| {
+                input.items.iter().position(|param| {
+                    param
+                        .body
+                        .success_ref()
+                        .and_then(ParamBody::as_ident)
+                        .is_some_and(|ident| ident.str_id() == *name)
+                })
+            };
+            inference.params.sort_by_key(order);
+        }
+        self.inferences.insert(id, inference.clone());
+
+        inference
+    }
+
+    /// Records what's known about a returned value while inferring the contract of the function being verified.
+    ///
+    /// The bounds are the tightest constants that can be proven, leaving out the ones the output type already has.
+    pub(crate) fn infer_return(&mut self, value: &Sym) {
+        let Some(fn_) = &self.fn_ else {
+            return;
+        };
+        let (Sym::Int(value), Ty::Int(Some(int_type))) = (value, &fn_.output) else {
+            fn_returns(
+                &mut self.fn_,
+                Inference {
+                    min: None,
+                    max: None,
+                    params: Vec::new(),
+                },
+            );
+            return;
+        };
+        // A return on a path that can't be taken says nothing about the output.
+        if !self.reachable || contradictory(&self.facts) {
+            return;
+        }
+
+        let params = fn_
+            .params
+            .iter()
+            .filter_map(|(name, local)| match &local.value {
+                Sym::Int(param) => Some((*name, param.clone())),
+                _ => None,
+            })
+            .filter(|(_, param)| {
+                Formula::cmp(value, CmpOp::Eq, param).is_some_and(|formula| prove(&self.facts, &formula) == Verdict::Proven)
+            })
+            .map(|(name, _)| name)
+            .collect::<Vec<_>>();
+
+        let (type_min, type_max) = Ty::int_range(*int_type);
+        let min = type_min
+            .zip(type_max)
+            .map(|(min, max)| self.tightest(value, CmpOp::MoreEq, min, max))
+            .filter(|min| Some(*min) != type_min);
+        let max = type_min
+            .zip(type_max)
+            .map(|(min, max)| self.tightest(value, CmpOp::LessEq, min, max))
+            .filter(|max| Some(*max) != type_max);
+
+        fn_returns(&mut self.fn_, Inference { min, max, params });
+    }
+
+    /// The closest constant between `min` and `max` the value can be proven to be `>=` or `<=` to,
+    /// searching from the end of the range that holds for any value of the type.
+    fn tightest(&self, value: &Linear, op: CmpOp, mut min: i128, mut max: i128) -> i128 {
+        if let Some(constant) = value.as_constant() {
+            return constant.clamp(min, max);
+        }
+
+        let holds = |bound: i128| {
+            Formula::cmp(value, op, &Linear::constant(bound))
+                .is_some_and(|formula| prove(&self.facts, &formula) == Verdict::Proven)
+        };
+        while min < max {
+            match op {
+                CmpOp::MoreEq => {
+                    let mid = min + (max - min + 1) / 2;
+                    if holds(mid) { min = mid } else { max = mid - 1 }
+                }
+                _ => {
+                    let mid = min + (max - min) / 2;
+                    if holds(mid) { max = mid } else { min = mid + 1 }
+                }
+            }
+        }
+
+        min
+    }
+
+    /// The facts an inferred contract gives for the output of a call, with the callee's parameters in the innermost scope.
+    pub(crate) fn inferred_facts(&self, inference: &Inference, output: &Sym) -> Vec<Formula> {
+        let Sym::Int(output) = output else {
+            return Vec::new();
+        };
+
+        let params = inference
+            .params
+            .iter()
+            .filter_map(|name| match self.scopes.last()?.get(name).map(|local| &local.value) {
+                Some(Sym::Int(param)) => Some(param.clone()),
+                _ => None,
+            });
+
+        Formula::range(output, inference.min, inference.max)
+            .into_iter()
+            .chain(params.filter_map(|param| Formula::cmp(output, CmpOp::Eq, &param)))
+            .collect()
+    }
+
+    /// The contracts inferred for the functions of the lib without `pub`, in the order of its items,
+    /// written as they'd be promised.
+    pub fn inferred_contracts(&self) -> Vec<InferredContract> {
+        let mut output = Vec::new();
+
+        for (id, item) in self.namespace.items() {
+            let Some(Some(inference)) = self.inferences.get(&id) else {
+                continue;
+            };
+            let Try::Success(ItemCore::Fn(fn_)) = &item.core else {
+                continue;
+            };
+            let (Try::Success(ident), Try::Success(FnBody::Block(body))) = (&fn_.ident, &fn_.body) else {
+                continue;
+            };
+
+            output.push(InferredContract {
+                span: ident.span(),
+                insert_at: body.frame.delims.open_span.start(),
+                item: ident.to_string_interned(self.interner),
+                promises: inference.promises(self.interner),
+            });
+        }
+
+        output
+    }
+}
+
+impl Inference {
+    /// How the inference is written in a `promise`.
+    pub fn promises(&self, interner: &Interner) -> Vec<String> {
+        let range = match (self.min, self.max) {
+            (Some(min), Some(max)) if min == max => vec![format!("out: == {min}")],
+            (min, max) => min
+                .map(|min| format!("out: >= {min}"))
+                .into_iter()
+                .chain(max.map(|max| format!("out: <= {max}")))
+                .collect(),
+        };
+
+        range
+            .into_iter()
+            .chain(
+                self.params
+                    .iter()
+                    .map(|param| format!("out: == {}", Interned(param, interner))),
+            )
+            .collect()
+    }
+}
+
+fn fn_returns(fn_: &mut Option<FnContext>, inference: Inference) {
+    if let Some(returns) = fn_.as_mut().and_then(|fn_| fn_.returns.as_mut()) {
+        returns.push(inference);
+    }
+}
@@ -0,0 +1,54 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    fmt::Display,
+    mem::{replace, take},
+};
+
+use oathc_ast::*;
+use oathc_comptime::{CmpOp, Comptime, IntType, Type, Value};
+use oathc_diagnostics::*;
+use oathc_interner::*;
+use oathc_res::{ItemId, Namespace};
+use oathc_span::*;
+use oathc_tokens::*;
+
+mod assigned;
+mod assumes;
+mod formula;
+mod infer;
+mod linear;
+mod lint;
+mod pure;
+mod solver;
+mod sym;
+mod verifier;
+mod verify_arith;
+mod verify_block;
+mod verify_closure;
+mod verify_enum;
+mod verify_exit;
+mod verify_expr;
+mod verify_impl;
+mod verify_index;
+mod verify_loop_contract;
+mod verify_match;
+mod verify_quantifier;
+mod verify_struct;
+mod verify_try;
+use assigned::*;
+pub use assumes::*;
+pub use formula::*;
+pub use infer::InferredContract;
+use infer::*;
+pub use linear::*;
+use lint::*;
+use pure::*;
+pub use solver::*;
+pub use sym::*;
+pub use verifier::*;
+use verify_block::*;
+use verify_exit::*;
+use verify_expr::*;
+use verify_loop_contract::*;
+use verify_quantifier::*;
+use verify_try::*;
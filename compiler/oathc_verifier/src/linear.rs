@@ -0,0 +1,115 @@
+use super::*;
+
+/// A symbolic integer the verifier knows nothing about, except for the facts it collected about it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Var(pub(crate) usize);
+
+/// `constant + sum(coefficient * var)`.
+///
+/// Arithmetic is checked, so operations return `None` instead of overflowing.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Linear {
+    pub constant: i128,
+    pub terms: BTreeMap<Var, i128>,
+}
+
+impl Linear {
+    pub fn constant(value: i128) -> Self {
+        Self {
+            constant: value,
+            terms: BTreeMap::new(),
+        }
+    }
+
+    pub fn var(var: Var) -> Self {
+        Self {
+            constant: 0,
+            terms: [(var, 1)].into(),
+        }
+    }
+
+    pub fn as_constant(&self) -> Option<i128> {
+        if self.terms.is_empty() { Some(self.constant) } else { None }
+    }
+
+    pub fn coefficient(&self, var: Var) -> i128 {
+        self.terms.get(&var).copied().unwrap_or(0)
+    }
+
+    pub fn vars(&self) -> impl Iterator<Item = Var> + '_ {
+        self.terms.keys().copied()
+    }
+
+    pub fn add(&self, other: &Self) -> Option<Self> {
+        let mut output = self.clone();
+        output.constant = output.constant.checked_add(other.constant)?;
+
+        for (var, coefficient) in &other.terms {
+            let sum = output.coefficient(*var).checked_add(*coefficient)?;
+            if sum == 0 {
+                output.terms.remove(var);
+            } else {
+                output.terms.insert(*var, sum);
+            }
+        }
+
+        Some(output)
+    }
+
+    pub fn sub(&self, other: &Self) -> Option<Self> {
+        self.add(&other.scale(-1)?)
+    }
+
+    pub fn scale(&self, factor: i128) -> Option<Self> {
+        if factor == 0 {
+            return Some(Self::default());
+        }
+
+        Some(Self {
+            constant: self.constant.checked_mul(factor)?,
+            terms: self
+                .terms
+                .iter()
+                .map(|(var, coefficient)| Some((*var, coefficient.checked_mul(factor)?)))
+                .collect::<Option<_>>()?,
+        })
+    }
+
+    pub fn offset(&self, value: i128) -> Option<Self> {
+        self.add(&Self::constant(value))
+    }
+
+    /// Replaces `var` with `value`.
+    pub fn substitute(&self, var: Var, value: &Self) -> Option<Self> {
+        let coefficient = self.coefficient(var);
+        if coefficient == 0 {
+            return Some(self.clone());
+        }
+
+        let mut output = self.clone();
+        output.terms.remove(&var);
+
+        output.add(&value.scale(coefficient)?)
+    }
+
+    /// The value under a model, where variables the model leaves out are `0`.
+    pub fn eval(&self, model: &Model) -> Option<i128> {
+        self.terms.iter().try_fold(self.constant, |sum, (var, coefficient)| {
+            sum.checked_add(coefficient.checked_mul(model.get(var).copied().unwrap_or(0))?)
+        })
+    }
+
+    /// The gcd of the coefficients, `0` if there are none.
+    pub(crate) fn coefficient_gcd(&self) -> i128 {
+        self.terms.values().fold(0, |gcd, coefficient| self::gcd(gcd, *coefficient))
+    }
+}
+
+pub(crate) fn gcd(a: i128, b: i128) -> i128 {
+    let (mut a, mut b) = (a.unsigned_abs(), b.unsigned_abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+
+    a.min(i128::MAX as u128) as i128
+}
@@ -0,0 +1,161 @@
+use super::*;
+
+/// How a lint is reported, which attributes like `#[deny(assume)]` set for an item.
+///
+/// For lints over proof obligations, `deny` makes them errors unless they're proven,
+/// and `warn` and `allow` leave them unverified, like `assume`s of them, where `warn` reports that they are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LintLevel {
+    Allow,
+    Warn,
+    Deny,
+}
+
+/// The levels of the lints in the item being verified.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Lints {
+    /// `assume` statements, which are allowed by default since audits list them anyway.
+    pub assume: LintLevel,
+    /// Array indices that aren't proven to be in bounds, which are denied by default.
+    pub index: LintLevel,
+    /// Arithmetic that may overflow its integer type, and shifts that may be by its width or more.
+    pub overflow: LintLevel,
+    /// Divisions and remainders by a divisor that may be zero.
+    pub div_by_zero: LintLevel,
+}
+
+impl Default for Lints {
+    fn default() -> Self {
+        Self {
+            assume: LintLevel::Allow,
+            index: LintLevel::Deny,
+            overflow: LintLevel::Deny,
+            div_by_zero: LintLevel::Deny,
+        }
+    }
+}
+
+impl Lints {
+    /// The levels the inner attributes of modules and the attributes of nested items set,
+    /// where the attributes of inner modules and items override outer ones.
+    pub(crate) fn of(mod_attrs: &[&AttrBody], items: &[&Item], interner: &Interner) -> Self {
+        let mut lints = Self::default();
+
+        let item_attrs = items
+            .iter()
+            .flat_map(|item| &item.attrs)
+            .filter_map(|attr| attr.body.success_ref());
+        for attr in mod_attrs.iter().copied().chain(item_attrs) {
+            let AttrBody {
+                ident: Try::Success(ident),
+                value: Some(AttrInput::Fn(args)),
+                ..
+            } = attr
+            else {
+                continue;
+            };
+            let level = match interner.unintern(ident.str_id()).as_str() {
+                "allow" => LintLevel::Allow,
+                "warn" => LintLevel::Warn,
+                "deny" => LintLevel::Deny,
+                _ => continue,
+            };
+
+            for arg in &args.items {
+                match arg {
+                    AttrArg::Keyword(Keyword {
+                        kind: KeywordKind::Assume,
+                        ..
+                    }) => lints.assume = level,
+                    AttrArg::Keyword(_) => {}
+                    AttrArg::Expr(expr) => {
+                        let Some(ident) = expr
+                            .tree()
+                            .as_ref()
+                            .and_then(ExprTree::as_unary)
+                            .and_then(UnaryExprRef::as_ident)
+                        else {
+                            continue;
+                        };
+                        match interner.unintern(ident.str_id()).as_str() {
+                            "index" => lints.index = level,
+                            "overflow" => lints.overflow = level,
+                            "div_by_zero" => lints.div_by_zero = level,
+                            _ => {}
+                        }
+                    }
+                }
+            }
+        }
+
+        lints
+    }
+}
+
+impl<'ctx, 'c> Verifier<'ctx, 'c> {
+    /// Requires a formula at the level of its lint, like `obligate` when it's denied.
+    ///
+    /// Otherwise a formula that isn't proven is assumed, which `warn` reports.
+    /// Returns whether the obligation didn't fail.
+    pub(crate) fn obligate_at(
+        &mut self,
+        level: LintLevel,
+        formula: Option<Formula>,
+        error: impl FnOnce(&Self, Counterexample) -> Error,
+        warning: impl FnOnce() -> Warning,
+    ) -> bool {
+        if level == LintLevel::Deny {
+            return self.obligate(formula, error);
+        }
+        let checked = level == LintLevel::Warn && self.contract_depth == 0 && self.reachable;
+        let Some(formula) = formula else {
+            if checked {
+                self.errors.push(self.diagnostics.push_warning(warning()));
+            }
+            return true;
+        };
+
+        if checked && prove(&self.facts, &formula) != Verdict::Proven {
+            self.errors.push(self.diagnostics.push_warning(warning()));
+        }
+        self.facts.push(formula);
+
+        true
+    }
+
+    /// The lint levels of an item, or of an item nested in it, inside the modules the item is in.
+    pub(crate) fn lints_of(&self, id: ItemId, items: &[&Item]) -> Lints {
+        Lints::of(self.namespace.mod_attrs(id), items, self.interner)
+    }
+
+    /// Reports the `assume` statements of an item at the level it, the impl it's in and its modules set for them.
+    pub(crate) fn lint_item(&mut self, id: ItemId, item: &'ctx Item) {
+        match &item.core {
+            Try::Success(ItemCore::Impl(impl_)) => {
+                for inner in impl_.items() {
+                    let mut stmts = Vec::new();
+                    assumes_in_item(inner, &mut stmts);
+                    self.lint_assumes(stmts, self.lints_of(id, &[item, inner]));
+                }
+            }
+            _ => {
+                let mut stmts = Vec::new();
+                assumes_in_item(item, &mut stmts);
+                self.lint_assumes(stmts, self.lints_of(id, &[item]));
+            }
+        }
+    }
+
+    fn lint_assumes(&mut self, stmts: Vec<&AssumeStmt>, lints: Lints) {
+        for stmt in stmts {
+            let span = stmt.keyword.span().connect(stmt.value.option_span());
+            match lints.assume {
+                LintLevel::Allow => {}
+                LintLevel::Warn => self
+                    .errors
+                    .push(self.diagnostics.push_warning(Warning::UncheckedAssume(span))),
+                LintLevel::Deny => self.errors.push(self.diagnostics.push_error(Error::DeniedAssume(span))),
+            }
+        }
+    }
+}
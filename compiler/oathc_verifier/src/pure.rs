@@ -0,0 +1,411 @@
+use super::*;
+
+/// Whether a function can be used in a constraint, like `is_sorted` in `items: is_sorted()`.
+#[derive(Debug, Clone)]
+pub(crate) enum Purity {
+    /// Being checked, so a call to it from its own body is recursion.
+    Checking,
+    /// It has no effects and always ends. `unfold` is whether its body can stand in for a call to it,
+    /// which it can't when the body returns early.
+    Pure { unfold: bool },
+    /// What makes it impure, and whether that was reported.
+    Impure { effects: Vec<Label>, reported: bool },
+}
+
+/// What a function body was found to do while checking its purity.
+#[derive(Debug, Default)]
+struct PurityCheck {
+    effects: Vec<Label>,
+    exits_early: bool,
+}
+
+impl<'ctx, 'c> Verifier<'ctx, 'c> {
+    /// Checks whether a function is pure, once: it has a body and no mutable reference parameters,
+    /// and the body doesn't borrow mutably, loop without an end, recurse, or call anything but pure functions.
+    pub(crate) fn purity(&mut self, id: ItemId) -> Purity {
+        if let Some(purity) = self.purities.get(&id) {
+            return purity.clone();
+        }
+        self.purities.insert(id, Purity::Checking);
+
+        let mut check = PurityCheck::default();
+        if let Try::Success(ItemCore::Fn(fn_)) = &self.namespace.item(id).core {
+            for param in fn_.input.success_ref().into_iter().flat_map(|input| &input.items) {
+                if let (true, Some(span)) = (param_is_mut_ref(param), param.option_span()) {
+                    check.effects.push(Label {
+                        span,
+                        message: format!("`{}` is a mutable reference", param_name(param, self.interner)),
+                    });
+                }
+            }
+
+            match &fn_.body {
+                Try::Success(FnBody::Block(body)) => self.pure_block(body, &mut check),
+                _ => check.effects.push(Label {
+                    span: fn_.keyword.span(),
+                    message: "it has no body to check".to_string(),
+                }),
+            }
+        }
+
+        let purity = match check.effects.is_empty() {
+            true => Purity::Pure {
+                unfold: !check.exits_early,
+            },
+            false => Purity::Impure {
+                effects: check.effects,
+                reported: false,
+            },
+        };
+        self.purities.insert(id, purity.clone());
+
+        purity
+    }
+
+    fn pure_block(&mut self, block: &'ctx Block, check: &mut PurityCheck) {
+        for stmt in &block.stmts {
+            match stmt {
+                Stmt::Item(_) | Stmt::Continue(_) | Stmt::Assume(_) => {}
+                Stmt::Let(let_) => {
+                    if let Some(assign) = &let_.value {
+                        self.pure_expr(&assign.value, check);
+                    }
+                }
+                Stmt::Return(stmt) => {
+                    check.exits_early = true;
+                    if let Some(value) = &stmt.value {
+                        self.pure_expr(value, check);
+                    }
+                }
+                Stmt::Eval(ControlStmt { value: Some(value), .. }) | Stmt::Break(BreakStmt { value: Some(value), .. }) => {
+                    self.pure_expr(value, check)
+                }
+                Stmt::Eval(_) | Stmt::Break(_) => {}
+                Stmt::Expr(stmt) => {
+                    self.pure_expr(&stmt.expr, check);
+                    if let Some(set) = &stmt.set {
+                        self.pure_expr(&set.value, check);
+                    }
+                }
+            }
+        }
+    }
+
+    fn pure_expr(&mut self, expr: &'ctx impl ToExprTree, check: &mut PurityCheck) {
+        if let Some(tree) = expr.tree() {
+            self.pure_tree(&tree, check);
+        }
+    }
+
+    fn pure_tree(&mut self, tree: &ExprTree<'ctx>, check: &mut PurityCheck) {
+        let unary = match tree {
+            ExprTree::BinOp(lhs, _, rhs) => {
+                self.pure_tree(lhs, check);
+                self.pure_tree(rhs, check);
+                return;
+            }
+            ExprTree::Unary(unary) => unary,
+        };
+
+        for prefix in &unary.prefixes {
+            if let UnOp::Ref(Ref {
+                bounds: Some(modifier @ (RefModifier::Mut(_) | RefModifier::SoleMut(_))),
+                ..
+            }) = prefix
+            {
+                check.effects.push(Label {
+                    span: modifier.span(),
+                    message: "this borrows mutably".to_string(),
+                });
+            }
+        }
+
+        self.pure_core(unary.core, check);
+
+        // Only a call to a named function, optionally with generic arguments, is known to be pure.
+        let callee = match unary.core {
+            ExprCore::Ident(ident) => self.namespace.get(ident.str_id()).map(|id| (id, *ident)),
+            _ => None,
+        };
+        let mut direct = true;
+        for ext in &unary.exts {
+            match ext {
+                UnaryExprExt::Call(tuple) => {
+                    tuple.items.iter().for_each(|item| self.pure_expr(item, check));
+                    if let Some(message) = self.impure_call(callee.filter(|_| direct)) {
+                        check.effects.push(Label {
+                            span: tuple.span(),
+                            message,
+                        });
+                    }
+                    direct = false;
+                }
+                UnaryExprExt::Index(array) => array.items.iter().for_each(|item| self.pure_expr(item, check)),
+                UnaryExprExt::Generics(generics) => generics.args.iter().for_each(|arg| self.pure_expr(arg, check)),
+                UnaryExprExt::Construct(construct) => {
+                    for field in &construct.items {
+                        if let Try::Success(set) = &field.set {
+                            self.pure_expr(&set.value, check);
+                        }
+                    }
+                }
+                UnaryExprExt::Member(_) => {
+                    // Variants are called through their enum, like `Parsed.Ok(1)`.
+                    if !matches!(
+                        callee.map(|(id, _)| &self.namespace.item(id).core),
+                        Some(Try::Success(ItemCore::Enum(_)))
+                    ) {
+                        direct = false;
+                    }
+                }
+                UnaryExprExt::Question(_) => check.exits_early = true,
+            }
+        }
+    }
+
+    /// Why a call makes a function impure, if it does.
+    fn impure_call(&mut self, callee: Option<(ItemId, Ident)>) -> Option<String> {
+        let Some((id, ident)) = callee else {
+            return Some("this calls a value that may have effects".to_string());
+        };
+        let name = Interned(&ident.str_id(), self.interner);
+
+        match &self.namespace.item(id).core {
+            Try::Success(ItemCore::Fn(_)) => match self.purity(id) {
+                Purity::Pure { .. } => None,
+                Purity::Checking => Some(format!("this calls `{name}` recursively, so it may not end")),
+                Purity::Impure { .. } => Some(format!("`{name}` isn't pure")),
+            },
+            Try::Success(ItemCore::Struct(_) | ItemCore::Enum(_)) => None,
+            _ => Some(format!("`{name}` may have effects")),
+        }
+    }
+
+    fn pure_core(&mut self, core: &'ctx ExprCore, check: &mut PurityCheck) {
+        match core {
+            ExprCore::Ident(_) | ExprCore::Literal(_) => {}
+            ExprCore::Keyword(ExprKeyword::Fn(fn_)) => {
+                if let Some(body) = &fn_.body {
+                    // A closure's `return` doesn't return from the function it's in.
+                    let exits_early = check.exits_early;
+                    self.pure_block(body, check);
+                    check.exits_early = exits_early;
+                }
+            }
+            ExprCore::Keyword(_) => {}
+            ExprCore::Block(block) => self.pure_block(block, check),
+            ExprCore::Tuple(tuple) => tuple.items.iter().for_each(|item| self.pure_expr(item, check)),
+            ExprCore::Array(array) => {
+                array.items.iter().for_each(|item| self.pure_expr(item, check));
+                if let Some(len) = &array.len {
+                    self.pure_expr(&len.len, check);
+                }
+            }
+            ExprCore::If(if_) => {
+                self.pure_expr(&if_.condition, check);
+
+                let else_ = match &if_.body {
+                    IfBody::Then(then) => {
+                        self.pure_expr(&then.expr, check);
+                        &then.else_
+                    }
+                    IfBody::Block(block) => {
+                        if let Try::Success(block) = &block.block {
+                            self.pure_block(block, check);
+                        }
+                        &block.else_
+                    }
+                };
+                if let Some(else_) = else_ {
+                    self.pure_expr(&else_.expr, check);
+                }
+            }
+            ExprCore::Loop(loop_) => {
+                check.effects.push(Label {
+                    span: loop_.keyword.span(),
+                    message: "this `loop` may not end".to_string(),
+                });
+                if let Try::Success(block) = &loop_.block {
+                    self.pure_block(block, check);
+                }
+            }
+            ExprCore::While(while_) => {
+                check.effects.push(Label {
+                    span: while_.keyword.span(),
+                    message: "this `while` may not end".to_string(),
+                });
+                self.pure_expr(&while_.condition, check);
+                if let Try::Success(block) = &while_.block {
+                    self.pure_block(block, check);
+                }
+            }
+            ExprCore::Until(until) => {
+                check.effects.push(Label {
+                    span: until.keyword.span(),
+                    message: "this `until` may not end".to_string(),
+                });
+                self.pure_expr(&until.condition, check);
+                if let Try::Success(block) = &until.block {
+                    self.pure_block(block, check);
+                }
+            }
+            // A `for` loop ends with what it iterates over.
+            ExprCore::For(for_) => {
+                self.pure_expr(&for_.iter, check);
+                if let Try::Success(block) = &for_.block {
+                    self.pure_block(block, check);
+                }
+            }
+            ExprCore::Quantifier(quantifier) => {
+                self.pure_expr(&quantifier.iter, check);
+                if let Try::Success(block) = &quantifier.block {
+                    self.pure_block(block, check);
+                }
+            }
+            ExprCore::Try(try_) => {
+                // `?` inside a `try` block ends the block, not the function.
+                let exits_early = check.exits_early;
+                if let Try::Success(block) = &try_.block {
+                    self.pure_block(block, check);
+                }
+                check.exits_early = exits_early;
+            }
+            ExprCore::Match(match_) => {
+                self.pure_expr(&match_.value, check);
+                for arm in match_.body.success_ref().into_iter().flat_map(|body| &body.arms) {
+                    if let Some(guard) = &arm.guard {
+                        self.pure_expr(&guard.condition, check);
+                    }
+                    self.pure_expr(&arm.value, check);
+                }
+            }
+        }
+    }
+
+    /// Applies a predicate like `is_sorted()` in `items: is_sorted()`, whose first argument is the subject.
+    ///
+    /// The call is checked like any other, and gives the predicate's `promise`s. When the predicate's body can be
+    /// unfolded, the value it evaluates to for the arguments is the predicate's value.
+    pub(crate) fn apply_predicate(
+        &mut self,
+        id: ItemId,
+        generics: &[Sym],
+        subject: &Sym,
+        args: &[(Sym, Span)],
+        span: Span,
+    ) -> Option<Formula> {
+        let Try::Success(ItemCore::Fn(fn_)) = &self.namespace.item(id).core else {
+            return None;
+        };
+        let Try::Success(name) = &fn_.ident else {
+            return None;
+        };
+
+        let unfold = match self.purity(id) {
+            Purity::Pure { unfold } => unfold,
+            Purity::Checking => return None,
+            Purity::Impure { effects, reported } => {
+                if !reported {
+                    let error = Error::ImpurePredicate(span, name.str_id(), effects.clone());
+                    self.errors.push(self.diagnostics.push_error(error));
+                    self.purities.insert(id, Purity::Impure { effects, reported: true });
+                }
+
+                return None;
+            }
+        };
+
+        let args = [(subject.clone(), span)]
+            .into_iter()
+            .chain(args.iter().cloned())
+            .collect::<Vec<_>>();
+        let output = self.call(id, generics.to_vec(), args.clone(), span).as_formula()?;
+        // The output stands for the bound, which the diagnostic already shows, so counterexamples leave it out.
+        let mut vars = BTreeSet::new();
+        output.vars(&mut vars);
+        for var in vars {
+            if self.vars[var.0].is_output {
+                self.vars[var.0].name = None;
+            }
+        }
+
+        let unfolded = match unfold {
+            true => self.unfold(fn_, generics, args),
+            false => None,
+        };
+        let Some(unfolded) = unfolded else {
+            return Some(output);
+        };
+
+        self.facts.push(Formula::iff(output, unfolded.clone()));
+        Some(unfolded)
+    }
+
+    /// Evaluates the body of a pure function for the arguments, where nothing is checked.
+    fn unfold(&mut self, fn_: &'ctx Fn, generics: &[Sym], args: Vec<(Sym, Span)>) -> Option<Formula> {
+        let (Try::Success(input), Try::Success(FnBody::Block(body))) = (&fn_.input, &fn_.body) else {
+            return None;
+        };
+        if self.contract_depth >= MAX_CONTRACT_DEPTH {
+            return None;
+        }
+
+        let outer_scopes = replace(&mut self.scopes, vec![HashMap::new()]);
+        let outer_fn = self.fn_.take();
+        let outer_out = self.out.take();
+        let outer_reachable = replace(&mut self.reachable, true);
+        let outer_tries = take(&mut self.tries);
+        let outer_exit_contexts = take(&mut self.exit_contexts);
+        let trace_len = self.trace.len();
+        self.contract_depth += 1;
+
+        let params = fn_.generics.as_ref().map_or(&[][..], |generics| &generics.items);
+        let generics = generics.iter().map(|generic| (generic.clone(), fn_.keyword.span()));
+        self.bind_generics(params, generics, false);
+        for (param, (arg, span)) in input.items.iter().zip(args) {
+            let ty = self.param_type(param);
+            let arg = self.materialize(arg, &ty, None, span);
+            self.bind_param(param, arg, ty);
+        }
+
+        let (value, _) = self.verify_block(body);
+
+        self.scopes = outer_scopes;
+        self.fn_ = outer_fn;
+        self.out = outer_out;
+        self.reachable = outer_reachable;
+        self.tries = outer_tries;
+        self.exit_contexts = outer_exit_contexts;
+        self.trace.truncate(trace_len);
+        self.contract_depth -= 1;
+
+        value.as_formula()
+    }
+
+    /// The number of parameters of a function that can be a predicate, counting the subject.
+    pub(crate) fn predicate_arity(&self, id: ItemId) -> Option<usize> {
+        match &self.namespace.item(id).core {
+            Try::Success(ItemCore::Fn(Fn {
+                input: Try::Success(input),
+                ..
+            })) => Some(input.items.len()),
+            _ => None,
+        }
+    }
+}
+
+fn param_is_mut_ref(param: &Param) -> bool {
+    let Some(type_) = param.type_.as_ref().and_then(AngleUnaryExpr::as_ref_expr) else {
+        return false;
+    };
+
+    type_.prefixes.iter().any(|prefix| {
+        matches!(
+            prefix,
+            UnOp::Ref(Ref {
+                bounds: Some(RefModifier::Mut(_) | RefModifier::SoleMut(_)),
+                ..
+            })
+        )
+    })
+}
@@ -0,0 +1,628 @@
+use super::*;
+
+/// The most conjunctive cases a goal is split into before the solver gives up.
+pub const MAX_CASES: usize = 1024;
+/// The most inequalities a single case may grow to during elimination before the solver gives up.
+pub const MAX_CONSTRAINTS: usize = 4096;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Proven,
+    /// The goal may not hold, or the solver gave up.
+    Unproven,
+}
+
+/// Values for variables.
+pub type Model = BTreeMap<Var, i128>;
+
+/// What the solver found out about a goal it couldn't prove.
+#[derive(Debug, Clone, Default)]
+pub struct Refutation {
+    /// The variables of the goal and of the facts it depends on.
+    pub vars: BTreeSet<Var>,
+    /// Values that satisfy the facts but not the goal, if the solver found any.
+    pub model: Option<Model>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Feasibility {
+    Infeasible,
+    Feasible,
+    Unknown,
+}
+
+/// Decides whether the facts imply the goal over the integers.
+///
+/// The negated goal is conjoined with the facts that share variables with it, split into conjunctive cases,
+/// and every case is refuted with Fourier-Motzkin elimination. Inequalities are tightened to their integer hull
+/// after every step, so this is complete for most linear integer constraints that show up in contracts,
+/// and it's always sound: `Proven` is only returned if no counterexample exists.
+pub fn prove(facts: &[Formula], goal: &Formula) -> Verdict {
+    let mut vars = BTreeSet::new();
+    goal.vars(&mut vars);
+
+    let facts = relevant_facts(facts, vars);
+    let formula = Formula::and(facts.into_iter().cloned().chain([goal.clone().not()]));
+
+    match unsatisfiable(&formula) {
+        true => Verdict::Proven,
+        false => Verdict::Unproven,
+    }
+}
+
+/// Whether the facts contradict each other, so the path they're known on can't be taken.
+///
+/// Unlike `prove`, this considers every fact, since none of them is about a goal.
+pub fn contradictory(facts: &[Formula]) -> bool {
+    unsatisfiable(&Formula::and(facts.iter().cloned()))
+}
+
+/// Whether no integer values satisfy the formula, `false` if the solver gives up.
+fn unsatisfiable(formula: &Formula) -> bool {
+    let Some(cases) = nnf(formula, false).and_then(|formula| dnf(&propagate_units(formula))) else {
+        return false;
+    };
+
+    let mut budget = MAX_CASES;
+    cases
+        .into_iter()
+        .all(|case| feasibility(case, &mut budget) == Feasibility::Infeasible)
+}
+
+/// Searches for values that satisfy the facts but not the goal.
+///
+/// Each case is projected with Fourier-Motzkin elimination, and values are picked for the eliminated variables
+/// in reverse, as close to zero as their bounds allow. This can miss integer solutions where the projection
+/// only has rational ones, so a model is only returned after checking it against the facts and the goal.
+pub fn refute(facts: &[Formula], goal: &Formula) -> Refutation {
+    let mut vars = BTreeSet::new();
+    goal.vars(&mut vars);
+
+    let facts = relevant_facts(facts, vars.clone());
+    for fact in &facts {
+        fact.vars(&mut vars);
+    }
+
+    let formula = Formula::and(facts.into_iter().cloned().chain([goal.clone().not()]));
+    let Some(cases) = nnf(&formula, false).and_then(|formula| dnf(&propagate_units(formula))) else {
+        return Refutation { vars, model: None };
+    };
+
+    let mut budget = MAX_CASES;
+    let model = cases
+        .into_iter()
+        .filter_map(|case| case_model(case, &mut budget))
+        .find(|model| formula.eval(model) == Some(true));
+
+    Refutation { vars, model }
+}
+
+/// The facts that are transitively connected to the variables through shared variables.
+fn relevant_facts(facts: &[Formula], mut vars: BTreeSet<Var>) -> Vec<&Formula> {
+    let fact_vars = facts
+        .iter()
+        .map(|fact| {
+            let mut vars = BTreeSet::new();
+            fact.vars(&mut vars);
+            vars
+        })
+        .collect::<Vec<_>>();
+
+    let mut included = vec![false; facts.len()];
+    loop {
+        let mut changed = false;
+        for (index, fact_vars) in fact_vars.iter().enumerate() {
+            if !included[index] && (fact_vars.is_empty() || !fact_vars.is_disjoint(&vars)) {
+                included[index] = true;
+                vars.extend(fact_vars);
+                changed = true;
+            }
+        }
+
+        if !changed {
+            break;
+        }
+    }
+
+    facts
+        .iter()
+        .zip(included)
+        .filter_map(|(fact, included)| included.then_some(fact))
+        .collect()
+}
+
+/// Pushes negations into the atoms.
+fn nnf(formula: &Formula, negated: bool) -> Option<Formula> {
+    Some(match formula {
+        Formula::Bool(value) => Formula::Bool(*value != negated),
+        Formula::Atom(atom) if negated => atom.negate()?.into(),
+        Formula::Atom(atom) => Formula::Atom(atom.clone()),
+        Formula::Not(formula) => nnf(formula, !negated)?,
+        Formula::And(formulas) => {
+            let formulas = formulas
+                .iter()
+                .map(|formula| nnf(formula, negated))
+                .collect::<Option<Vec<_>>>()?;
+            if negated {
+                Formula::or(formulas)
+            } else {
+                Formula::and(formulas)
+            }
+        }
+        Formula::Or(formulas) => {
+            let formulas = formulas
+                .iter()
+                .map(|formula| nnf(formula, negated))
+                .collect::<Option<Vec<_>>>()?;
+            if negated {
+                Formula::and(formulas)
+            } else {
+                Formula::or(formulas)
+            }
+        }
+    })
+}
+
+/// Simplifies a conjunction in negation normal form with the atoms that are among its conjuncts, until nothing changes,
+/// so that facts like `x == 1` don't split every disjunction that mentions `x != 1` into more cases.
+fn propagate_units(formula: Formula) -> Formula {
+    let Formula::And(mut conjuncts) = formula else {
+        return formula;
+    };
+
+    loop {
+        let units = conjuncts
+            .iter()
+            .filter_map(|conjunct| match conjunct {
+                Formula::Atom(atom) => Some(atom.clone()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        let mut changed = false;
+        let simplified = conjuncts.into_iter().map(|conjunct| match conjunct {
+            Formula::Atom(_) => conjunct,
+            _ => {
+                let simplified = simplify(&conjunct, &units);
+                changed |= simplified != conjunct;
+                simplified
+            }
+        });
+
+        match Formula::and(simplified.collect::<Vec<_>>()) {
+            Formula::And(next) if changed => conjuncts = next,
+            formula => return formula,
+        }
+    }
+}
+
+/// Replaces the atoms that are known to hold with `true`, and the ones whose negation is with `false`.
+fn simplify(formula: &Formula, units: &[Atom]) -> Formula {
+    match formula {
+        Formula::Atom(atom) if units.contains(atom) => Formula::TRUE,
+        Formula::Atom(atom) if atom.negate().is_some_and(|negated| units.contains(&negated)) => Formula::FALSE,
+        Formula::And(formulas) => Formula::and(formulas.iter().map(|formula| simplify(formula, units))),
+        Formula::Or(formulas) => Formula::or(formulas.iter().map(|formula| simplify(formula, units))),
+        formula => formula.clone(),
+    }
+}
+
+/// Splits a formula in negation normal form into conjunctive cases, returns `None` if there are too many.
+fn dnf(formula: &Formula) -> Option<Vec<Vec<Atom>>> {
+    Some(match formula {
+        Formula::Bool(true) => vec![Vec::new()],
+        Formula::Bool(false) => Vec::new(),
+        Formula::Atom(atom) => vec![vec![atom.clone()]],
+        Formula::Not(_) => unreachable!(),
+        Formula::And(formulas) => {
+            let mut cases = vec![Vec::new()];
+            for formula in formulas {
+                let rhs = dnf(formula)?;
+                if cases.len().saturating_mul(rhs.len()) > MAX_CASES {
+                    return None;
+                }
+
+                cases = cases
+                    .iter()
+                    .flat_map(|lhs| {
+                        rhs.iter().map(move |rhs| {
+                            let mut case = lhs.clone();
+                            case.extend(rhs.iter().cloned());
+                            case
+                        })
+                    })
+                    .collect();
+            }
+
+            cases
+        }
+        Formula::Or(formulas) => {
+            let mut cases = Vec::new();
+            for formula in formulas {
+                cases.extend(dnf(formula)?);
+                if cases.len() > MAX_CASES {
+                    return None;
+                }
+            }
+
+            cases
+        }
+    })
+}
+
+fn feasibility(atoms: Vec<Atom>, budget: &mut usize) -> Feasibility {
+    // `x != 0` over the integers is `x >= 1 | x <= -1`.
+    if let Some(index) = atoms.iter().position(|atom| atom.rel == Rel::NotEq) {
+        let Some(budget_left) = budget.checked_sub(1) else {
+            return Feasibility::Unknown;
+        };
+        *budget = budget_left;
+
+        let Some((more_atoms, less_atoms)) = split_not_eq(atoms, index) else {
+            return Feasibility::Unknown;
+        };
+
+        return match (feasibility(more_atoms, budget), feasibility(less_atoms, budget)) {
+            (Feasibility::Infeasible, Feasibility::Infeasible) => Feasibility::Infeasible,
+            (Feasibility::Feasible, _) | (_, Feasibility::Feasible) => Feasibility::Feasible,
+            _ => Feasibility::Unknown,
+        };
+    }
+
+    let (eqs, mut ges) = split_eqs(atoms);
+
+    match eliminate_eqs(eqs, &mut ges) {
+        Some(Feasibility::Feasible) => {}
+        Some(feasibility) => return feasibility,
+        None => return Feasibility::Unknown,
+    }
+
+    fourier_motzkin(ges).unwrap_or(Feasibility::Unknown)
+}
+
+fn case_model(atoms: Vec<Atom>, budget: &mut usize) -> Option<Model> {
+    if let Some(index) = atoms.iter().position(|atom| atom.rel == Rel::NotEq) {
+        *budget = budget.checked_sub(1)?;
+
+        let (more_atoms, less_atoms) = split_not_eq(atoms, index)?;
+        return case_model(more_atoms, budget).or_else(|| case_model(less_atoms, budget));
+    }
+
+    let (eqs, mut ges) = split_eqs(atoms);
+    for eq in eqs {
+        ges.push(eq.scale(-1)?);
+        ges.push(eq);
+    }
+
+    fourier_motzkin_model(ges)
+}
+
+/// Splits `x != 0` out of the atoms into the cases `x >= 1` and `x <= -1`.
+fn split_not_eq(mut atoms: Vec<Atom>, index: usize) -> Option<(Vec<Atom>, Vec<Atom>)> {
+    let atom = atoms.swap_remove(index);
+    let more = atom.linear.offset(-1)?;
+    let less = atom.linear.scale(-1)?.offset(-1)?;
+
+    let mut more_atoms = atoms.clone();
+    more_atoms.push(Atom::new(more, Rel::MoreEq));
+    atoms.push(Atom::new(less, Rel::MoreEq));
+
+    Some((more_atoms, atoms))
+}
+
+/// Splits atoms without `NotEq`s into equalities and inequalities.
+fn split_eqs(atoms: Vec<Atom>) -> (Vec<Linear>, Vec<Linear>) {
+    let mut eqs = Vec::new();
+    let mut ges = Vec::new();
+    for atom in atoms {
+        match atom.rel {
+            Rel::Eq => eqs.push(atom.linear),
+            Rel::MoreEq => ges.push(atom.linear),
+            Rel::NotEq => unreachable!(),
+        }
+    }
+
+    (eqs, ges)
+}
+
+/// Substitutes away equalities that have a unit coefficient, and turns the rest into pairs of inequalities.
+///
+/// Returns `Feasible` if the remaining inequalities still need to be checked.
+fn eliminate_eqs(mut eqs: Vec<Linear>, ges: &mut Vec<Linear>) -> Option<Feasibility> {
+    while let Some(eq) = eqs.pop() {
+        let Some(eq) = normalize_eq(eq) else {
+            return Some(Feasibility::Infeasible);
+        };
+        let Some(eq) = eq else {
+            continue;
+        };
+
+        let unit = eq.terms.iter().find(|(_, coefficient)| coefficient.abs() == 1);
+        match unit {
+            Some((&var, &coefficient)) => {
+                // `coefficient * var + rest == 0` => `var == -coefficient * rest`.
+                let mut rest = eq.clone();
+                rest.terms.remove(&var);
+                let value = rest.scale(-coefficient)?;
+
+                for other in eqs.iter_mut().chain(ges.iter_mut()) {
+                    *other = other.substitute(var, &value)?;
+                }
+            }
+            None => {
+                ges.push(eq.scale(-1)?);
+                ges.push(eq);
+            }
+        }
+    }
+
+    Some(Feasibility::Feasible)
+}
+
+fn fourier_motzkin(ges: Vec<Linear>) -> Option<Feasibility> {
+    let mut constraints = HashSet::new();
+    for ge in ges {
+        match normalize_ge(ge) {
+            Err(()) => return Some(Feasibility::Infeasible),
+            Ok(Some(ge)) => {
+                constraints.insert(ge);
+            }
+            Ok(None) => {}
+        }
+    }
+
+    loop {
+        if constraints.len() > MAX_CONSTRAINTS {
+            return Some(Feasibility::Unknown);
+        }
+
+        let Some(var) = elimination_var(&constraints) else {
+            return Some(Feasibility::Feasible);
+        };
+
+        let (bounds, rest) = constraints
+            .into_iter()
+            .partition::<Vec<_>, _>(|constraint| constraint.coefficient(var) != 0);
+        constraints = rest.into_iter().collect();
+
+        let (lower, upper) = bounds
+            .into_iter()
+            .partition::<Vec<_>, _>(|constraint| constraint.coefficient(var) > 0);
+
+        for lower in &lower {
+            for upper in &upper {
+                let combined = lower
+                    .scale(-upper.coefficient(var))?
+                    .add(&upper.scale(lower.coefficient(var))?)?;
+
+                match normalize_ge(combined) {
+                    Err(()) => return Some(Feasibility::Infeasible),
+                    Ok(Some(combined)) => {
+                        constraints.insert(combined);
+                    }
+                    Ok(None) => {}
+                }
+            }
+        }
+    }
+}
+
+/// The variable whose elimination adds the fewest constraints.
+fn elimination_var<'a>(constraints: impl IntoIterator<Item = &'a Linear>) -> Option<Var> {
+    let mut counts = BTreeMap::<Var, (usize, usize)>::new();
+    for constraint in constraints {
+        for (var, coefficient) in &constraint.terms {
+            let count = counts.entry(*var).or_default();
+            if *coefficient > 0 {
+                count.0 += 1;
+            } else {
+                count.1 += 1;
+            }
+        }
+    }
+
+    counts
+        .into_iter()
+        .min_by_key(|(_, (lower, upper))| lower * upper)
+        .map(|(var, _)| var)
+}
+
+/// Eliminates variables like `fourier_motzkin`, then picks their values in reverse.
+fn fourier_motzkin_model(ges: Vec<Linear>) -> Option<Model> {
+    let mut constraints = Vec::new();
+    for ge in ges {
+        if let Some(ge) = normalize_ge(ge).ok()? {
+            constraints.push(ge);
+        }
+    }
+    if constraints.len() > MAX_CONSTRAINTS {
+        return None;
+    }
+
+    let Some(var) = elimination_var(&constraints) else {
+        return Some(Model::new());
+    };
+
+    let (bounds, rest) = constraints
+        .into_iter()
+        .partition::<Vec<_>, _>(|constraint| constraint.coefficient(var) != 0);
+
+    let (lower, upper) = bounds
+        .iter()
+        .partition::<Vec<_>, _>(|constraint| constraint.coefficient(var) > 0);
+
+    let mut projected = rest;
+    for lower in &lower {
+        for upper in &upper {
+            projected.push(
+                lower
+                    .scale(-upper.coefficient(var))?
+                    .add(&upper.scale(lower.coefficient(var))?)?,
+            );
+        }
+    }
+
+    let mut model = fourier_motzkin_model(projected)?;
+
+    // `coefficient * var + rest >= 0` bounds `var` from below if the coefficient is positive, and from above otherwise.
+    let mut min = None::<i128>;
+    let mut max = None::<i128>;
+    for bound in &bounds {
+        let coefficient = bound.coefficient(var);
+        let mut rest = bound.clone();
+        rest.terms.remove(&var);
+        let rest = rest.eval(&model)?;
+
+        if coefficient > 0 {
+            let bound = rest.div_euclid(coefficient).checked_neg()?;
+            min = Some(min.map_or(bound, |min| min.max(bound)));
+        } else {
+            let bound = rest.div_euclid(coefficient.checked_neg()?);
+            max = Some(max.map_or(bound, |max| max.min(bound)));
+        }
+    }
+
+    let value = match (min, max) {
+        (Some(min), Some(max)) if min > max => return None,
+        (Some(min), _) if min > 0 => min,
+        (_, Some(max)) if max < 0 => max,
+        _ => 0,
+    };
+    model.insert(var, value);
+
+    Some(model)
+}
+
+/// Divides by the gcd of the coefficients, rounding the constant down.
+///
+/// Returns `Err` if the constraint is a false constant, `None` if it's a true constant.
+fn normalize_ge(linear: Linear) -> Result<Option<Linear>, ()> {
+    let gcd = linear.coefficient_gcd();
+    if gcd == 0 {
+        return if linear.constant >= 0 { Ok(None) } else { Err(()) };
+    }
+
+    Ok(Some(Linear {
+        constant: linear.constant.div_euclid(gcd),
+        terms: linear
+            .terms
+            .into_iter()
+            .map(|(var, coefficient)| (var, coefficient / gcd))
+            .collect(),
+    }))
+}
+
+/// Like `normalize_ge`, but an equality with a constant that isn't divisible by the gcd has no integer solutions.
+fn normalize_eq(linear: Linear) -> Option<Option<Linear>> {
+    let gcd = linear.coefficient_gcd();
+    if gcd == 0 {
+        return if linear.constant == 0 { Some(None) } else { None };
+    }
+    if linear.constant % gcd != 0 {
+        return None;
+    }
+
+    Some(Some(Linear {
+        constant: linear.constant / gcd,
+        terms: linear
+            .terms
+            .into_iter()
+            .map(|(var, coefficient)| (var, coefficient / gcd))
+            .collect(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::slice;
+
+    use super::*;
+
+    const X: Var = Var(0);
+    const Y: Var = Var(1);
+
+    /// `coefficient * var <op> rhs`.
+    fn cmp(coefficient: i128, var: Var, op: CmpOp, rhs: i128) -> Formula {
+        let lhs = Linear::var(var).scale(coefficient).unwrap();
+        Formula::cmp(&lhs, op, &Linear::constant(rhs)).unwrap()
+    }
+
+    fn x(op: CmpOp, rhs: i128) -> Formula {
+        cmp(1, X, op, rhs)
+    }
+
+    #[test]
+    fn proves_implied_goal() {
+        assert_eq!(prove(&[x(CmpOp::More, 5)], &x(CmpOp::MoreEq, 0)), Verdict::Proven);
+        assert_eq!(prove(&[x(CmpOp::Eq, 3)], &x(CmpOp::NotEq, 5)), Verdict::Proven);
+
+        let facts = [
+            x(CmpOp::MoreEq, 0),
+            Formula::cmp(&Linear::var(Y), CmpOp::More, &Linear::var(X)).unwrap(),
+        ];
+        assert_eq!(prove(&facts, &cmp(1, Y, CmpOp::MoreEq, 1)), Verdict::Proven);
+    }
+
+    #[test]
+    fn refutes_goal_that_may_fail() {
+        let facts = [x(CmpOp::More, -5)];
+        let goal = x(CmpOp::MoreEq, 0);
+        assert_eq!(prove(&facts, &goal), Verdict::Unproven);
+
+        let model = refute(&facts, &goal).model.unwrap();
+        assert!(model[&X] > -5 && model[&X] < 0);
+    }
+
+    #[test]
+    fn splits_disjunctions() {
+        let either_side = Formula::or([x(CmpOp::Less, 0), x(CmpOp::More, 0)]);
+        assert_eq!(prove(slice::from_ref(&either_side), &x(CmpOp::NotEq, 0)), Verdict::Proven);
+        assert_eq!(prove(&[x(CmpOp::NotEq, 0)], &either_side), Verdict::Proven);
+
+        let facts = [x(CmpOp::MoreEq, 0)];
+        assert_eq!(prove(&facts, &either_side), Verdict::Unproven);
+        assert_eq!(refute(&facts, &either_side).model.unwrap()[&X], 0);
+    }
+
+    #[test]
+    fn tightens_to_integers() {
+        // `1 <= 2x - 2y <= 1` holds for `x = -1, y = -3/2`, but not for any integers, so it implies anything about `x`.
+        let diff = Linear::var(X)
+            .scale(2)
+            .unwrap()
+            .sub(&Linear::var(Y).scale(2).unwrap())
+            .unwrap();
+        let facts = [
+            Formula::cmp(&diff, CmpOp::MoreEq, &Linear::constant(1)).unwrap(),
+            Formula::cmp(&diff, CmpOp::LessEq, &Linear::constant(1)).unwrap(),
+        ];
+        assert_eq!(prove(&facts, &x(CmpOp::MoreEq, 0)), Verdict::Proven);
+
+        // `2x == 2y + 1` has no integer solutions, so it implies anything about `x`.
+        let odd = Formula::cmp(
+            &Linear::var(X).scale(2).unwrap(),
+            CmpOp::Eq,
+            &Linear::var(Y).scale(2).unwrap().offset(1).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(prove(&[odd], &x(CmpOp::MoreEq, 0)), Verdict::Proven);
+    }
+
+    #[test]
+    fn elimination_leaves_nothing() {
+        // Without facts, eliminating `x` from the negated goal leaves no constraint to contradict.
+        assert_eq!(prove(&[], &x(CmpOp::MoreEq, 0)), Verdict::Unproven);
+        assert!(refute(&[], &x(CmpOp::MoreEq, 0)).model.is_some());
+
+        // A goal whose variables cancel out is a constant, so there's nothing left to prove.
+        let goal = Formula::cmp(&Linear::var(X).offset(1).unwrap(), CmpOp::More, &Linear::var(X)).unwrap();
+        assert_eq!(goal, Formula::TRUE);
+        assert_eq!(prove(&[], &goal), Verdict::Proven);
+
+        // Facts that don't share variables with the goal can't help prove it, even contradictory ones.
+        let facts = [cmp(1, Y, CmpOp::MoreEq, 1), cmp(1, Y, CmpOp::LessEq, 0)];
+        assert_eq!(prove(&facts, &x(CmpOp::MoreEq, 0)), Verdict::Unproven);
+        assert!(contradictory(&facts));
+        assert!(!contradictory(&facts[..1]));
+    }
+}
@@ -0,0 +1,147 @@
+use super::*;
+
+/// What the verifier knows about a value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Sym {
+    Int(Linear),
+    Bool(Formula),
+    Bound(SymBound),
+    Tuple(Vec<Sym>),
+    /// The generic arguments of a struct and its fields, in the order they're declared.
+    Struct(ItemId, Vec<Sym>, Vec<Sym>),
+    /// The variant's index, and a payload for every variant, of which only the one at that index is meaningful.
+    Enum(ItemId, Linear, Vec<Sym>),
+    /// A variant with a payload, which constructs the enum when called.
+    Variant(ItemId, usize),
+    Fn(ItemId, Vec<Sym>),
+    /// A closure, by its index in the closures of the function being verified.
+    Closure(usize),
+    /// A type that's used as a value, like the `Range` in `Range { start = 0, end = 1 }`.
+    Type(Ty),
+    /// A type that's only known to implement a trait, with the trait's generic arguments, like `S` in `S type: Shape`.
+    Impl(ItemId, Vec<Sym>),
+    /// A function of a trait, called through a type that's only known to implement it, like `S.area`.
+    TraitFn(ItemId, Vec<Sym>, StrId),
+    /// A function of an impl of a type that's known, by the impl and the function's name, like `Square.area`.
+    ImplFn(ItemId, StrId),
+    /// The integers from the first up to but not including the second, like `0..n`.
+    Range(Linear, Linear),
+    /// An array whose items are known, like `[a, b, c]`.
+    Array(Vec<Sym>),
+    /// An array whose items aren't known, by the type of its items, its length,
+    /// and the array it is among those of the function being verified, which its reads are remembered by.
+    ArrayOf(Ty, Linear, usize),
+    /// A value the verifier doesn't track.
+    Opaque,
+}
+
+/// A bound over a symbolic value, like `!= 5` or `>= start & < end`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SymBound {
+    Cmp(CmpOp, Box<Sym>),
+    /// A call to a pure function that's missing its first argument, like `is_sorted()`, which is the subject.
+    /// Holds the function, its generic arguments, the other arguments and where the call is.
+    Pred(ItemId, Vec<Sym>, Vec<(Sym, Span)>, Span),
+    Not(Box<SymBound>),
+    And(Box<SymBound>, Box<SymBound>),
+    Or(Box<SymBound>, Box<SymBound>),
+}
+
+/// The part of a type the verifier cares about.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Ty {
+    /// `None` if the width of the integer isn't known.
+    Int(Option<IntType>),
+    Bool,
+    Tuple(Vec<Ty>),
+    /// A struct with its generic arguments, which are only known if there are as many as it has generic parameters
+    /// and none of them is opaque.
+    Struct(ItemId, Vec<Sym>),
+    /// An enum that isn't generic.
+    Enum(ItemId),
+    /// `[T; N]`, with `None` if the length isn't an integer the verifier knows.
+    Array(Box<Ty>, Option<Linear>),
+    Opaque,
+}
+
+impl Sym {
+    pub fn as_formula(&self) -> Option<Formula> {
+        match self {
+            Self::Bool(formula) => Some(formula.clone()),
+            _ => None,
+        }
+    }
+
+    pub fn vars(&self, output: &mut BTreeSet<Var>) {
+        match self {
+            Self::Int(linear) => output.extend(linear.vars()),
+            Self::Bool(formula) => formula.vars(output),
+            Self::Tuple(values) | Self::Array(values) => values.iter().for_each(|value| value.vars(output)),
+            Self::Struct(_, generics, fields) => generics.iter().chain(fields).for_each(|value| value.vars(output)),
+            Self::ArrayOf(_, len, _) => output.extend(len.vars()),
+            Self::Range(start, end) => {
+                output.extend(start.vars());
+                output.extend(end.vars());
+            }
+            Self::Enum(_, tag, payloads) => {
+                output.extend(tag.vars());
+                payloads.iter().for_each(|payload| payload.vars(output));
+            }
+            Self::Bound(_)
+            | Self::Variant(..)
+            | Self::Fn(..)
+            | Self::Closure(_)
+            | Self::Type(_)
+            | Self::Impl(..)
+            | Self::TraitFn(..)
+            | Self::ImplFn(..)
+            | Self::Opaque => {}
+        }
+    }
+}
+
+impl SymBound {
+    /// Returns `None` if the bound doesn't translate into a formula over the subject.
+    /// Predicates are applied by the verifier, which calls them.
+    pub fn apply(&self, subject: &Sym) -> Option<Formula> {
+        match self {
+            Self::Cmp(op, rhs) => match (subject, &**rhs) {
+                (Sym::Int(lhs), Sym::Int(rhs)) => Formula::cmp(lhs, *op, rhs),
+                (Sym::Bool(lhs), Sym::Bool(rhs)) => match op {
+                    CmpOp::Eq => Some(Formula::iff(lhs.clone(), rhs.clone())),
+                    CmpOp::NotEq => Some(Formula::xor(lhs.clone(), rhs.clone())),
+                    _ => None,
+                },
+                _ => None,
+            },
+            Self::Pred(..) => None,
+            Self::Not(bound) => bound.apply(subject).map(Formula::not),
+            Self::And(lhs, rhs) => Some(Formula::and([lhs.apply(subject)?, rhs.apply(subject)?])),
+            Self::Or(lhs, rhs) => Some(Formula::or([lhs.apply(subject)?, rhs.apply(subject)?])),
+        }
+    }
+}
+
+impl Ty {
+    /// The range an integer type adds as a fact.
+    ///
+    /// 128-bit bounds are left out where `overflow_range` narrows them, since values past them exist.
+    pub fn int_range(int_type: IntType) -> (Option<i128>, Option<i128>) {
+        match int_type {
+            IntType::I128 => (None, None),
+            IntType::U128 => (Some(0), None),
+            int_type => (Some(int_type.min()), Some(int_type.max())),
+        }
+    }
+
+    /// The range arithmetic on an integer type has to stay in.
+    ///
+    /// The solver's numbers are `i128`s, so `i128` is narrowed to start at `i128::MIN + 1` and `u128` to end at `i128::MAX`,
+    /// which reports arithmetic that reaches the ends of their ranges even where it doesn't overflow.
+    pub fn overflow_range(int_type: IntType) -> (i128, i128) {
+        match int_type {
+            IntType::I128 => (i128::MIN + 1, i128::MAX),
+            int_type => (int_type.min(), int_type.max()),
+        }
+    }
+}
@@ -0,0 +1,1010 @@
+use super::*;
+
+/// How deep contracts are followed when a contract calls a function that has its own contract.
+pub const MAX_CONTRACT_DEPTH: usize = 4;
+
+/// Checks constraints: parameter bounds and `require`s at call sites, output bounds and `promise`s at returns,
+/// and the bounds of `let`s and statics.
+///
+/// Function bodies are evaluated symbolically. Every constraint that's checked becomes a proof obligation
+/// that has to follow from the facts known at that point, which come from the function's own parameter bounds
+/// and `require`s, from the `promise`s of the functions it calls, and from the ranges of integer types.
+#[derive(Debug)]
+pub struct Verifier<'ctx, 'c> {
+    pub(crate) namespace: &'ctx Namespace<'ctx>,
+    pub(crate) interner: &'ctx Interner,
+    pub(crate) diagnostics: &'ctx Diagnostics,
+    pub(crate) comptime: &'c mut Comptime<'ctx>,
+    pub(crate) vars: Vec<VarInfo>,
+    pub(crate) facts: Vec<Formula>,
+    pub(crate) scopes: Vec<HashMap<StrId, Local<'ctx>>>,
+    /// Whether the code being verified can be reached, `false` after `return`, `break` and `continue`.
+    pub(crate) reachable: bool,
+    /// The steps that led to the code being verified.
+    pub(crate) trace: Vec<Step>,
+    pub(crate) fn_: Option<FnContext<'ctx>>,
+    /// The `try` blocks around the code being verified.
+    pub(crate) tries: Vec<TryContext<'ctx>>,
+    /// The loops and labeled blocks around the code being verified.
+    pub(crate) exit_contexts: Vec<ExitContext<'ctx>>,
+    /// The closures of the function being verified, which `Sym::Closure` refers to.
+    pub(crate) closures: Vec<&'ctx FnExpr>,
+    /// The value `out` refers to while verifying output bounds and `promise`s.
+    pub(crate) out: Option<Sym>,
+    /// Greater than zero while evaluating the contract of a called function, where nothing is checked.
+    pub(crate) contract_depth: usize,
+    /// The integer type the value being evaluated is bound to, which is the width of arithmetic on literals alone.
+    pub(crate) width: Option<IntType>,
+    /// The structs fresh values are being made for, so a struct that contains itself doesn't recurse forever.
+    pub(crate) structs: Vec<ItemId>,
+    /// The lint levels of the item being verified.
+    pub(crate) lints: Lints,
+    /// The functions used as predicates in constraints, by whether they're pure.
+    pub(crate) purities: HashMap<ItemId, Purity>,
+    /// The contracts inferred for functions without `pub`, `None` while being inferred or if nothing was.
+    pub(crate) inferences: HashMap<ItemId, Option<Inference>>,
+    /// How many arrays with unknown items there are in the function being verified, which `Sym::ArrayOf` counts with.
+    pub(crate) arrays: usize,
+    /// The items of arrays with unknown items that were read in the function being verified.
+    pub(crate) reads: Vec<Read>,
+    /// The quantifiers of the function being verified that are instantiated at later reads.
+    pub(crate) quantifiers: Vec<Quantified<'ctx>>,
+    /// Whether a quantifier is being instantiated, when reads don't instantiate quantifiers.
+    pub(crate) instantiating: bool,
+    /// Where a value of a generic struct is constructed with generic arguments the verifier doesn't know,
+    /// which are checked for every instance of the function instead.
+    pub(crate) instance_sites: HashSet<Span>,
+    /// The errors to keep while verifying an instance of a generic function, whose other errors are the function's own.
+    pub(crate) instance: Option<Vec<DiagnosticHandle>>,
+    pub(crate) errors: Vec<DiagnosticHandle>,
+}
+
+#[derive(Debug, Clone)]
+#[allow(dead_code)]
+pub(crate) struct VarInfo {
+    pub name: Option<StrId>,
+    pub span: Span,
+    /// Boolean variables are `0` or `1`, and are shown as `false` or `true` in counterexamples.
+    pub is_bool: bool,
+    /// The type of an integer variable, which arithmetic on it mustn't overflow.
+    pub int_type: Option<IntType>,
+    /// Whether it's the output of a call, which is shown by the name of the local it's bound to once it is.
+    pub is_output: bool,
+}
+
+/// A branch taken or an assignment made on the path to the code being verified,
+/// reported with failed constraints that involve its variables.
+#[derive(Debug, Clone)]
+pub(crate) struct Step {
+    pub span: Span,
+    pub message: String,
+    pub vars: BTreeSet<Var>,
+    /// What has to hold for the step to be on the path, for steps from branches that were joined.
+    pub guard: Option<Formula>,
+}
+
+impl Step {
+    pub fn guarded(self, guard: Formula) -> Self {
+        Self {
+            guard: Some(match self.guard {
+                Some(outer) => Formula::and([outer, guard]),
+                None => guard,
+            }),
+            ..self
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Local<'ctx> {
+    pub value: Sym,
+    pub ty: Ty,
+    /// Checked whenever the local is assigned, and assumed whenever what's known about it is forgotten.
+    pub bounds: Option<ExprTree<'ctx>>,
+    /// Whether a `&mut` or `&smut` reference to the local has been taken,
+    /// so anything that may write through a reference forgets what's known about it.
+    pub borrowed_mut: bool,
+}
+
+/// The function whose body is being verified.
+#[derive(Debug, Clone)]
+pub(crate) struct FnContext<'ctx> {
+    /// The parameters as they were on entry, which is what the output's constraints refer to,
+    /// together with the locals a closure captures.
+    pub params: HashMap<StrId, Local<'ctx>>,
+    pub output: Ty,
+    /// Where the output type is written, if it is.
+    pub output_span: Option<Span>,
+    pub output_bounds: Vec<ExprTree<'ctx>>,
+    pub promises: Vec<ExprTree<'ctx>>,
+    /// The measures the function's calls to itself have to decrease.
+    pub recursion: Option<Recursion<'ctx>>,
+    /// What's known at each return, while the function's contract is inferred.
+    pub returns: Option<Vec<Inference>>,
+}
+
+impl<'ctx, 'c> Verifier<'ctx, 'c> {
+    pub fn new(comptime: &'c mut Comptime<'ctx>, interner: &'ctx Interner, diagnostics: &'ctx Diagnostics) -> Self {
+        Self {
+            namespace: comptime.namespace(),
+            interner,
+            diagnostics,
+            comptime,
+            vars: Vec::new(),
+            facts: Vec::new(),
+            scopes: Vec::new(),
+            reachable: true,
+            trace: Vec::new(),
+            fn_: None,
+            tries: Vec::new(),
+            exit_contexts: Vec::new(),
+            closures: Vec::new(),
+            out: None,
+            contract_depth: 0,
+            width: None,
+            structs: Vec::new(),
+            lints: Lints::default(),
+            purities: HashMap::new(),
+            inferences: HashMap::new(),
+            arrays: 0,
+            reads: Vec::new(),
+            quantifiers: Vec::new(),
+            instantiating: false,
+            instance_sites: HashSet::new(),
+            instance: None,
+            errors: Vec::new(),
+        }
+    }
+
+    /// Verifies every function, static and impl in the namespace,
+    /// and the instances of generic functions with the generic parameters bound to the generic arguments.
+    pub fn check_items(&mut self, instances: &[(ItemId, Vec<(StrId, Value)>)], errors: &mut Vec<DiagnosticHandle>) {
+        for (id, item) in self.namespace.items() {
+            self.lint_item(id, item);
+            self.lints = self.lints_of(id, &[item]);
+
+            match &item.core {
+                Try::Success(ItemCore::Fn(fn_)) => {
+                    // Inferred up front, so the contract of every function without `pub` can be shown.
+                    self.infer(id);
+                    self.instance_sites.clear();
+                    self.verify_fn(item, fn_, None);
+
+                    for (_, bindings) in instances.iter().filter(|(instance, _)| *instance == id) {
+                        if self.instance_sites.is_empty() {
+                            break;
+                        }
+                        self.verify_instance(item, fn_, bindings);
+                    }
+                }
+                Try::Success(ItemCore::Impl(impl_)) => self.verify_impl(id, item, impl_),
+                Try::Success(ItemCore::Static(static_)) => self.verify_static(id, item, static_),
+                _ => {}
+            }
+        }
+
+        errors.append(&mut self.errors);
+    }
+
+    pub(crate) fn reset(&mut self) {
+        self.vars.clear();
+        self.facts.clear();
+        self.scopes = vec![HashMap::new()];
+        self.reachable = true;
+        self.trace.clear();
+        self.fn_ = None;
+        self.tries.clear();
+        self.closures.clear();
+        self.out = None;
+        self.contract_depth = 0;
+        self.width = None;
+        self.arrays = 0;
+        self.reads.clear();
+        self.quantifiers.clear();
+    }
+
+    /// Verifies a function, with the generic parameters and `require`s of the impl it's in.
+    pub(crate) fn verify_fn(&mut self, item: &'ctx oathc_ast::Item, fn_: &'ctx Fn, impl_: Option<&'ctx Impl>) {
+        self.verify_fn_with(item, fn_, impl_, &[]);
+    }
+
+    /// Verifies an instance of a generic function, only reporting what the function's own verification couldn't check.
+    fn verify_instance(&mut self, item: &'ctx oathc_ast::Item, fn_: &'ctx Fn, bindings: &[(StrId, Value)]) {
+        let outer_errors = take(&mut self.errors);
+        self.instance = Some(Vec::new());
+
+        self.verify_fn_with(item, fn_, None, bindings);
+
+        // Dropping the other errors removes them, since they're the function's own.
+        let kept = self.instance.take().unwrap_or_default();
+        self.errors = outer_errors;
+        self.errors.extend(kept);
+    }
+
+    /// Verifies a function with the generic parameters that have bindings bound to them, and fresh values for the others.
+    fn verify_fn_with(
+        &mut self,
+        item: &'ctx oathc_ast::Item,
+        fn_: &'ctx Fn,
+        impl_: Option<&'ctx Impl>,
+        bindings: &[(StrId, Value)],
+    ) {
+        let (Try::Success(input), Try::Success(FnBody::Block(body))) = (&fn_.input, &fn_.body) else {
+            return;
+        };
+
+        self.reset();
+
+        if let Some(impl_) = impl_ {
+            self.bind_impl(impl_);
+        }
+
+        for param in fn_
+            .generics
+            .iter()
+            .flat_map(|generics| &generics.items)
+            .filter(|param| !param.is_lifetime())
+        {
+            let name = param
+                .body
+                .success_ref()
+                .and_then(ParamBody::as_ident)
+                .map(|ident| ident.str_id());
+            match bindings.iter().find(|(binding, _)| Some(*binding) == name) {
+                Some((_, value)) => {
+                    let ty = self.param_type(param);
+                    self.bind_param(param, sym_of_value(value), ty);
+                }
+                None => self.bind_fresh_param(param),
+            }
+        }
+        self.enter_fn(input, &fn_.contract, fn_.output.as_ref());
+        self.enter_recursion(&item.attrs, fn_);
+
+        let (value, span) = self.verify_block(body);
+        if self.reachable {
+            self.check_return(value, span);
+        }
+    }
+
+    /// Binds the parameters of a function to fresh values in the innermost scope and assumes its `require`s,
+    /// then makes it the function whose returns are checked.
+    pub(crate) fn enter_fn(
+        &mut self,
+        input: &'ctx FramedParams<delims!("( )")>,
+        contract: &'ctx Contract,
+        output: Option<&'ctx BraceExpr>,
+    ) {
+        for param in &input.items {
+            self.bind_fresh_param(param);
+        }
+
+        for require in requires(&[&input.contract, contract]) {
+            if let Some(formula) = self.sym_contract(&require).as_formula() {
+                self.facts.push(formula);
+            }
+        }
+
+        let output_span = output.and_then(OptionSpanned::option_span);
+        let (output, output_bounds) = match output.and_then(ToExprTree::tree) {
+            Some(tree) => {
+                let (type_, bounds) = split_bounds(tree);
+                (self.read_type(&type_), bounds)
+            }
+            None => (Ty::Tuple(Vec::new()), Vec::new()),
+        };
+
+        self.fn_ = Some(FnContext {
+            params: self
+                .scopes
+                .iter()
+                .flat_map(|scope| scope.iter())
+                .map(|(name, local)| (*name, local.clone()))
+                .collect(),
+            output,
+            output_span,
+            output_bounds,
+            promises: promises(&[&input.contract, contract]),
+            recursion: None,
+            returns: None,
+        });
+    }
+
+    fn verify_static(&mut self, id: ItemId, item: &'ctx oathc_ast::Item, static_: &'ctx Static) {
+        let (Try::Success(param), Some(assign)) = (&static_.param, &static_.value) else {
+            return;
+        };
+        let Some(bounds) = param.bounds.as_ref().and_then(|bounds| bounds.expr.tree()) else {
+            return;
+        };
+
+        self.reset();
+
+        let value = if item.is_comptime() {
+            self.static_value(id, static_.keyword.span())
+        } else {
+            self.sym_expr(&assign.value)
+        };
+        let ty = self.param_type(param);
+        let value = self.materialize(value, &ty, None, assign.eq.span());
+
+        let name = param_name(param, self.interner);
+        let span = assign.value.option_span().unwrap_or(assign.eq.span());
+        self.check_bound(&value, &bounds, |verifier, bounds, counterexample| {
+            Error::BoundViolated(
+                span,
+                format!("{name}: {}", bounds.to_string_interned(verifier.interner)),
+                counterexample,
+            )
+        });
+    }
+
+    /// Checks a returned value against the output bounds and `promise`s of the function being verified.
+    pub(crate) fn check_return(&mut self, value: Sym, span: Span) {
+        let Some(fn_) = self.fn_.clone() else {
+            return;
+        };
+        if fn_.returns.is_some() {
+            self.infer_return(&value);
+        }
+
+        let value = self.materialize(value, &fn_.output, None, span);
+        if let Some((in_range, text)) = self.type_range(&value, &fn_.output, "out") {
+            self.obligate(in_range, |_, counterexample| {
+                Error::PromiseViolated(span, text, counterexample)
+            });
+        }
+        if fn_.output_bounds.is_empty() && fn_.promises.is_empty() {
+            return;
+        }
+
+        let outer_scopes = replace(&mut self.scopes, vec![fn_.params]);
+        let outer_out = self.out.replace(value.clone());
+
+        for bound in &fn_.output_bounds {
+            self.check_bound(&value, bound, |verifier, bound, counterexample| {
+                Error::PromiseViolated(
+                    span,
+                    format!("out: {}", bound.to_string_interned(verifier.interner)),
+                    counterexample,
+                )
+            });
+        }
+
+        for promise in &fn_.promises {
+            let formula = self.sym_contract(promise).as_formula();
+            self.obligate(formula, |verifier, counterexample| {
+                Error::PromiseViolated(span, promise.to_string_interned(verifier.interner), counterexample)
+            });
+        }
+
+        self.scopes = outer_scopes;
+        self.out = outer_out;
+    }
+
+    /// Checks a call against the callee's parameter bounds and `require`s, and returns its output with the callee's
+    /// output bounds and `promise`s as facts.
+    pub(crate) fn call(&mut self, item: ItemId, generics: Vec<Sym>, args: Vec<(Sym, Span)>, span: Span) -> Sym {
+        let Try::Success(ItemCore::Fn(fn_)) = &self.namespace.item(item).core else {
+            return Sym::Opaque;
+        };
+        if self.contract_depth >= MAX_CONTRACT_DEPTH {
+            return Sym::Opaque;
+        }
+        let inference = self.infer(item);
+
+        self.call_fn(fn_, inference, generics, args, span)
+    }
+
+    /// Checks a call to the function, which may be an impl's and has the inferred contract if it has one.
+    pub(crate) fn call_fn(
+        &mut self,
+        fn_: &'ctx Fn,
+        inference: Option<Inference>,
+        generics: Vec<Sym>,
+        args: Vec<(Sym, Span)>,
+        span: Span,
+    ) -> Sym {
+        let (Try::Success(name), Try::Success(input)) = (&fn_.ident, &fn_.input) else {
+            return Sym::Opaque;
+        };
+        if self.contract_depth >= MAX_CONTRACT_DEPTH {
+            return Sym::Opaque;
+        }
+
+        let outer_scopes = replace(&mut self.scopes, vec![HashMap::new()]);
+        let outer_out = self.out.take();
+        self.contract_depth += 1;
+
+        // Generic arguments that are known are checked when the function is instantiated,
+        // so only the ones that depend on the caller's values are required here.
+        let generics = generics.into_iter().map(|generic| (generic, span));
+        let params = fn_.generics.as_ref().map_or(&[][..], |generics| &generics.items);
+        let generic_requirements = self.bind_generics(params, generics, true);
+
+        let (output, requirements, mut promises) =
+            self.callee_contract(input, &fn_.contract, fn_.output.as_ref(), Some(name.str_id()), args, span);
+        let mut promised = self.promised(input, &fn_.contract, fn_.output.as_ref());
+        if let Some(inference) = &inference {
+            promises.extend(self.inferred_facts(inference, &output));
+            promised.extend(inference.promises(self.interner));
+        }
+        let recursion = self.recursive_measures(fn_);
+
+        self.scopes = outer_scopes;
+        self.out = outer_out;
+        self.contract_depth -= 1;
+
+        self.require_call(name.str_id(), generic_requirements.into_iter().chain(requirements));
+        if let Some((measures, values)) = recursion {
+            self.decrease(&measures, values, Some(span), None);
+        }
+        // A function with an inferred contract has none of its own.
+        let verb = match inference {
+            Some(_) => "is inferred to promise",
+            None => "promises",
+        };
+        self.promise_call(name.str_id(), promises, &promised, verb, &output, span);
+
+        output
+    }
+
+    /// Binds generic parameters to their arguments in the innermost scope, and returns what the parameters' bounds
+    /// require of the arguments, with where and how it's reported.
+    ///
+    /// With `symbolic_only`, the bounds of arguments that don't depend on any value aren't required,
+    /// for items whose instances are checked where they're instantiated.
+    pub(crate) fn bind_generics(
+        &mut self,
+        params: &'ctx [Param],
+        args: impl IntoIterator<Item = (Sym, Span)>,
+        symbolic_only: bool,
+    ) -> Vec<Requirement> {
+        let mut requirements = Vec::new();
+        let params = params.iter().filter(|param| !param.is_lifetime());
+        for (param, (arg, span)) in params.zip(args) {
+            let ty = self.param_type(param);
+            let arg = self.materialize(arg, &ty, None, span);
+            self.bind_param(param, arg.clone(), ty);
+
+            let Some(bounds) = param.bounds.as_ref().and_then(|bounds| bounds.expr.tree()) else {
+                continue;
+            };
+            let mut vars = BTreeSet::new();
+            arg.vars(&mut vars);
+            if symbolic_only && vars.is_empty() {
+                continue;
+            }
+
+            let formula = self.bound_formula(&arg, &bounds);
+            let text = format!(
+                "{}: {}",
+                param_name(param, self.interner),
+                bounds.to_string_interned(self.interner)
+            );
+            requirements.push((formula, span, text));
+        }
+
+        requirements
+    }
+
+    /// Checks what a call to a function requires.
+    pub(crate) fn require_call(&mut self, name: StrId, requirements: impl IntoIterator<Item = Requirement>) {
+        for (formula, span, text) in requirements {
+            self.obligate(formula, |_, counterexample| {
+                Error::RequireViolated(span, name, text, counterexample)
+            });
+        }
+    }
+
+    /// Assumes what a call to a function promises, and adds a step that says so to the trace.
+    pub(crate) fn promise_call(
+        &mut self,
+        name: StrId,
+        promises: Vec<Formula>,
+        promised: &[String],
+        verb: &str,
+        output: &Sym,
+        span: Span,
+    ) {
+        self.facts.extend(promises);
+
+        if !promised.is_empty() {
+            let message = format!(
+                "fn `{}` {verb} `{}`",
+                Interned(&name, self.interner),
+                promised.join("` and `")
+            );
+            self.push_step(span, message, output);
+        }
+    }
+
+    /// How the output bounds and `promise`s of a function are written, for the steps of the calls to it.
+    pub(crate) fn promised(
+        &self,
+        input: &'ctx FramedParams<delims!("( )")>,
+        contract: &'ctx Contract,
+        output: Option<&'ctx BraceExpr>,
+    ) -> Vec<String> {
+        let bounds = match output.and_then(ToExprTree::tree) {
+            Some(tree) => split_bounds(tree).1,
+            None => Vec::new(),
+        };
+
+        bounds
+            .iter()
+            .map(|bound| format!("out: {}", bound.to_string_interned(self.interner)))
+            .chain(
+                promises(&[&input.contract, contract])
+                    .iter()
+                    .map(|promise| promise.to_string_interned(self.interner)),
+            )
+            .collect()
+    }
+
+    /// Binds the parameters of a called function to the arguments in the innermost scope, and returns its output with
+    /// the facts its output bounds give, what the call requires with where and how it's reported, and its `promise`s.
+    /// The call requires the arguments to be in the ranges of the parameters' types, as well as their bounds.
+    pub(crate) fn callee_contract(
+        &mut self,
+        input: &'ctx FramedParams<delims!("( )")>,
+        contract: &'ctx Contract,
+        output: Option<&'ctx BraceExpr>,
+        name: Option<StrId>,
+        args: Vec<(Sym, Span)>,
+        span: Span,
+    ) -> (Sym, Vec<Requirement>, Vec<Formula>) {
+        let mut requirements = Vec::new();
+        for (param, (arg, arg_span)) in input.items.iter().zip(args) {
+            let ty = self.param_type(param);
+            let arg = self.materialize(arg, &ty, None, arg_span);
+            requirements.extend(
+                self.type_range(&arg, &ty, &param_name(param, self.interner))
+                    .map(|(in_range, text)| (in_range, arg_span, text)),
+            );
+            self.bind_param(param, arg.clone(), ty);
+
+            if let Some(bounds) = param.bounds.as_ref().and_then(|bounds| bounds.expr.tree()) {
+                let formula = self.bound_formula(&arg, &bounds);
+                let text = format!(
+                    "{}: {}",
+                    param_name(param, self.interner),
+                    bounds.to_string_interned(self.interner)
+                );
+
+                requirements.push((formula, arg_span, text));
+            }
+        }
+
+        for require in requires(&[&input.contract, contract]) {
+            let formula = self.sym_tree(&require).as_formula();
+            requirements.push((formula, span, require.to_string_interned(self.interner)));
+        }
+
+        let output = match output.and_then(ToExprTree::tree) {
+            Some(tree) => {
+                let (type_, bounds) = split_bounds(tree);
+                let ty = self.read_type(&type_);
+                // Shown like the call, as in `half(..) = 0`.
+                let name = name.map(|name| self.interner.intern(&format!("{}(..)", Interned(&name, self.interner))));
+                let output = self.fresh(&ty, name, span);
+                let mut vars = BTreeSet::new();
+                output.vars(&mut vars);
+                for var in vars {
+                    self.vars[var.0].is_output = true;
+                }
+
+                self.out = Some(output.clone());
+                for bound in &bounds {
+                    if let Some(formula) = self.bound_formula(&output, bound) {
+                        self.facts.push(formula);
+                    }
+                }
+
+                output
+            }
+            None => Sym::Tuple(Vec::new()),
+        };
+        self.out = Some(output.clone());
+
+        let promises = promises(&[&input.contract, contract])
+            .iter()
+            .filter_map(|promise| self.sym_tree(promise).as_formula())
+            .collect::<Vec<_>>();
+
+        (output, requirements, promises)
+    }
+
+    /// Reports an error, unless it's inside a callee's contract.
+    pub(crate) fn error(&mut self, error: Error) {
+        if self.contract_depth == 0 {
+            self.errors.push(self.diagnostics.push_error(error));
+        }
+    }
+
+    /// Requires the formula to follow from the known facts, then assumes it.
+    ///
+    /// Nothing is checked inside a callee's contract or in unreachable code, and a formula the verifier couldn't
+    /// build fails as one it couldn't verify. Failed obligations aren't assumed, since they may contradict the facts.
+    /// Returns whether the obligation didn't fail.
+    pub(crate) fn obligate(&mut self, formula: Option<Formula>, error: impl FnOnce(&Self, Counterexample) -> Error) -> bool {
+        let Some(formula) = formula else {
+            if self.contract_depth > 0 || !self.reachable {
+                return true;
+            }
+
+            let counterexample = Counterexample {
+                unverifiable: true,
+                ..Counterexample::default()
+            };
+            let error = error(self, counterexample);
+            self.errors.push(self.diagnostics.push_error(error));
+            return false;
+        };
+
+        if self.contract_depth > 0 || !self.reachable || prove(&self.facts, &formula) == Verdict::Proven {
+            self.facts.push(formula);
+            return true;
+        }
+
+        let counterexample = self.counterexample(&formula);
+        let error = error(self, counterexample);
+        self.errors.push(self.diagnostics.push_error(error));
+
+        false
+    }
+
+    pub(crate) fn check_bound(
+        &mut self,
+        value: &Sym,
+        bound: &ExprTree<'ctx>,
+        error: impl FnOnce(&Self, &ExprTree, Counterexample) -> Error,
+    ) {
+        let formula = self.bound_formula(value, bound);
+        self.obligate(formula, |verifier, counterexample| error(verifier, bound, counterexample));
+    }
+
+    /// Values the goal fails for, named after the locals they were assigned to,
+    /// and the steps on the current path that involve them.
+    fn counterexample(&self, goal: &Formula) -> Counterexample {
+        let refutation = refute(&self.facts, goal);
+
+        // The latest variable of every local is its value at this point.
+        let mut values = Vec::<(StrId, String)>::new();
+        for (var, value) in refutation.model.iter().flatten() {
+            let info = &self.vars[var.0];
+            let Some(name) = info.name else {
+                continue;
+            };
+
+            let value = match info.is_bool {
+                true => (*value != 0).to_string(),
+                false => value.to_string(),
+            };
+            match values.iter_mut().find(|(other, _)| *other == name) {
+                Some((_, other)) => *other = value,
+                None => values.push((name, value)),
+            }
+        }
+
+        let values = values
+            .into_iter()
+            .map(|(name, value)| format!("{} = {value}", Interned(&name, self.interner)))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let trace = self
+            .trace
+            .iter()
+            .filter(|step| !step.vars.is_disjoint(&refutation.vars))
+            .filter(|step| match (&step.guard, &refutation.model) {
+                (None, _) => true,
+                (Some(guard), Some(model)) => guard.eval(model) == Some(true),
+                (Some(_), None) => false,
+            })
+            .map(|step| Label {
+                span: step.span,
+                message: step.message.clone(),
+            })
+            .collect();
+
+        Counterexample {
+            values,
+            trace,
+            unverifiable: false,
+        }
+    }
+
+    /// Evaluates a bound like `!= 5` and applies it to the value.
+    pub(crate) fn bound_formula(&mut self, value: &Sym, bound: &ExprTree<'ctx>) -> Option<Formula> {
+        match self.sym_contract(bound) {
+            Sym::Bound(bound) => self.apply_bound(&bound, value),
+            // A condition, like a quantifier over the subject.
+            Sym::Bool(formula) => Some(formula),
+            _ => None,
+        }
+    }
+
+    /// Applies a bound to the value, like `SymBound::apply`, calling the predicates in it.
+    pub(crate) fn apply_bound(&mut self, bound: &SymBound, subject: &Sym) -> Option<Formula> {
+        match bound {
+            SymBound::Pred(id, generics, args, span) => self.apply_predicate(*id, generics, subject, args, *span),
+            SymBound::Not(bound) => self.apply_bound(bound, subject).map(Formula::not),
+            SymBound::And(lhs, rhs) => Some(Formula::and([
+                self.apply_bound(lhs, subject)?,
+                self.apply_bound(rhs, subject)?,
+            ])),
+            SymBound::Or(lhs, rhs) => Some(Formula::or([
+                self.apply_bound(lhs, subject)?,
+                self.apply_bound(rhs, subject)?,
+            ])),
+            SymBound::Cmp(..) => bound.apply(subject),
+        }
+    }
+
+    /// Evaluates an expression that's part of a contract, where calls aren't checked.
+    pub(crate) fn sym_contract(&mut self, tree: &ExprTree<'ctx>) -> Sym {
+        self.contract_depth += 1;
+        let value = self.sym_tree(tree);
+        self.contract_depth -= 1;
+
+        value
+    }
+
+    pub(crate) fn new_var(&mut self, name: Option<StrId>, span: Span, is_bool: bool, int_type: Option<IntType>) -> Var {
+        self.vars.push(VarInfo {
+            name,
+            span,
+            is_bool,
+            int_type,
+            is_output: false,
+        });
+
+        Var(self.vars.len() - 1)
+    }
+
+    /// A value of the type that nothing is known about, except for the range of its type.
+    pub(crate) fn fresh(&mut self, ty: &Ty, name: Option<StrId>, span: Span) -> Sym {
+        match ty {
+            Ty::Int(int_type) => {
+                let var = Linear::var(self.new_var(name, span, false, *int_type));
+                if let Some(int_type) = int_type {
+                    let (min, max) = Ty::int_range(*int_type);
+                    self.facts.extend(Formula::range(&var, min, max));
+                }
+
+                Sym::Int(var)
+            }
+            Ty::Bool => {
+                let var = Linear::var(self.new_var(name, span, true, None));
+                self.facts.extend(Formula::range(&var, Some(0), Some(1)));
+
+                Sym::Bool(Formula::cmp(&var, CmpOp::Eq, &Linear::constant(1)).unwrap())
+            }
+            Ty::Tuple(tys) => Sym::Tuple(
+                tys.iter()
+                    .enumerate()
+                    .map(|(index, ty)| {
+                        let name = self.member_name(name, index);
+                        self.fresh(ty, name, span)
+                    })
+                    .collect(),
+            ),
+            Ty::Struct(id, generics) => self.fresh_struct(*id, generics.clone(), name, span),
+            Ty::Enum(id) => self.fresh_enum(*id, span),
+            Ty::Array(ty, Some(len)) => self.array_of((**ty).clone(), len.clone()),
+            Ty::Array(_, None) | Ty::Opaque => Sym::Opaque,
+        }
+    }
+
+    /// The name a part of a named value is shown with in counterexamples, like `r.end` for the field `end` of `r`.
+    pub(crate) fn member_name(&self, name: Option<StrId>, member: impl Display) -> Option<StrId> {
+        name.map(|name| self.interner.intern(&format!("{}.{member}", Interned(&name, self.interner))))
+    }
+
+    /// Replaces values the verifier doesn't track with fresh values of the type.
+    pub(crate) fn materialize(&mut self, value: Sym, ty: &Ty, name: Option<StrId>, span: Span) -> Sym {
+        match (value, ty) {
+            (Sym::Opaque, ty) => self.fresh(ty, name, span),
+            (Sym::Tuple(values), Ty::Tuple(tys)) if values.len() == tys.len() => Sym::Tuple(
+                values
+                    .into_iter()
+                    .zip(tys)
+                    .map(|(value, ty)| self.materialize(value, ty, None, span))
+                    .collect(),
+            ),
+            (value, _) => value,
+        }
+    }
+
+    /// Binds a parameter to a fresh value of its type, and assumes its bounds.
+    pub(crate) fn bind_fresh_param(&mut self, param: &'ctx Param) {
+        let ty = self.param_type(param);
+        let name = param
+            .body
+            .success_ref()
+            .and_then(ParamBody::as_ident)
+            .map(|ident| ident.str_id());
+        let Some(span) = param.option_span() else {
+            return;
+        };
+        let value = self.fresh(&ty, name, span);
+        // A type bounded by a trait, like `S type: Shape`, is only known by the trait.
+        let value = match param.bounds.as_ref().and_then(|bounds| bounds.expr.tree()) {
+            Some(bounds) if value == Sym::Opaque => self.implemented_trait(&bounds).unwrap_or(value),
+            _ => value,
+        };
+
+        self.bind_param(param, value.clone(), ty);
+
+        if let Some(bounds) = param.bounds.as_ref().and_then(|bounds| bounds.expr.tree()) {
+            let formula = self.bound_formula(&value, &bounds);
+            self.facts.extend(formula);
+
+            // Shown with failed constraints on the parameter, next to the constraint that failed.
+            let name = param_name(param, self.interner);
+            let message = format!(
+                "`{name}` is declared as `{name}: {}`",
+                bounds.to_string_interned(self.interner)
+            );
+            self.push_step(span, message, &value);
+        }
+    }
+
+    /// Binds a parameter pattern in the innermost scope.
+    pub(crate) fn bind_param(&mut self, param: &'ctx Param, value: Sym, ty: Ty) {
+        match &param.body {
+            Try::Success(ParamBody::Pattern(pattern)) => match pattern.as_ident() {
+                Some(ident) => {
+                    let local = Local {
+                        value,
+                        ty,
+                        bounds: param.bounds.as_ref().and_then(|bounds| bounds.expr.tree()),
+                        borrowed_mut: false,
+                    };
+
+                    self.scopes.last_mut().unwrap().insert(ident.str_id(), local);
+                }
+                None => self.bind_pattern(pattern, value),
+            },
+            Try::Success(ParamBody::Tuple(params)) => {
+                let values = match value {
+                    Sym::Tuple(values) if values.len() == params.items.len() => values,
+                    _ => vec![Sym::Opaque; params.items.len()],
+                };
+
+                for (param, value) in params.items.iter().zip(values) {
+                    let ty = self.param_type(param);
+                    if let Some(span) = param.option_span() {
+                        let value = self.materialize(value, &ty, None, span);
+                        self.bind_param(param, value, ty);
+                    }
+                }
+            }
+            Try::Success(ParamBody::Lifetime(_)) | Try::Failure(_) => {}
+        }
+    }
+
+    pub(crate) fn param_type(&mut self, param: &'ctx Param) -> Ty {
+        match param.type_.as_ref().and_then(AngleUnaryExpr::as_ref_expr) {
+            Some(type_) => self.read_type(&ExprTree::Unary(type_)),
+            None => Ty::Opaque,
+        }
+    }
+
+    /// Reads the parts of a type expression the verifier tracks.
+    pub(crate) fn read_type(&mut self, tree: &ExprTree<'ctx>) -> Ty {
+        let Some(unary) = tree.as_unary() else {
+            return Ty::Opaque;
+        };
+
+        if let Some(ident) = unary.as_ident() {
+            // A generic parameter that's a type, like `T`, stands for the type it's bound to.
+            if let Some(local) = self.scopes.iter().rev().find_map(|scope| scope.get(&ident.str_id())) {
+                return match &local.value {
+                    Sym::Type(ty) => ty.clone(),
+                    _ => Ty::Opaque,
+                };
+            }
+
+            return match self.interner.unintern(ident.str_id()).as_str() {
+                "bool" => Ty::Bool,
+                name => match IntType::from_name(name) {
+                    Some(int_type) => Ty::Int(Some(int_type)),
+                    None => self.struct_ty(ident.str_id()),
+                },
+            };
+        }
+
+        match unary.core {
+            ExprCore::Tuple(tuple) if unary.prefixes.is_empty() && unary.exts.is_empty() => {
+                let mut tys = tuple
+                    .items
+                    .iter()
+                    .map(|item| item.tree().map_or(Ty::Opaque, |tree| self.read_type(&tree)))
+                    .collect::<Vec<_>>();
+
+                if tys.len() == 1 { tys.pop().unwrap() } else { Ty::Tuple(tys) }
+            }
+            ExprCore::Array(Array {
+                items,
+                len: Some(ArrayLen {
+                    len: Try::Success(len), ..
+                }),
+                ..
+            }) if unary.prefixes.is_empty() && unary.exts.is_empty() && items.len() == 1 => {
+                let ty = items[0].tree().map_or(Ty::Opaque, |tree| self.read_type(&tree));
+                let len = match len.tree().map(|tree| self.sym_contract(&tree)) {
+                    Some(Sym::Int(len)) => Some(len),
+                    _ => None,
+                };
+
+                Ty::Array(Box::new(ty), len)
+            }
+            // A generic struct with its arguments, like `Buffer<4>`.
+            ExprCore::Ident(ident) if unary.prefixes.is_empty() => match (self.struct_ty(ident.str_id()), &unary.exts[..]) {
+                (Ty::Struct(id, _), [UnaryExprExt::Generics(generics)]) => {
+                    let generics = generics
+                        .args
+                        .iter()
+                        .map(|arg| arg.tree().map_or(Sym::Opaque, |tree| self.sym_contract(&tree)))
+                        .collect();
+
+                    Ty::Struct(id, generics)
+                }
+                _ => Ty::Opaque,
+            },
+            _ => Ty::Opaque,
+        }
+    }
+}
+
+/// Splits `type: bound: bound` into the type and its bounds.
+pub(crate) fn split_bounds(tree: ExprTree) -> (ExprTree, Vec<ExprTree>) {
+    let mut bounds = Vec::new();
+    let mut tree = tree;
+
+    while let ExprTree::BinOp(lhs, BinOp::Bound(_), rhs) = tree {
+        bounds.push(*rhs);
+        tree = *lhs;
+    }
+
+    bounds.reverse();
+    (tree, bounds)
+}
+
+pub(crate) fn requires<'a>(contracts: &[&'a Contract]) -> Vec<ExprTree<'a>> {
+    contracts
+        .iter()
+        .flat_map(|contract| &contract.segments)
+        .filter_map(|segment| match segment {
+            ContractSegment::Require(require) => require.items.success_ref(),
+            ContractSegment::Promise(_) => None,
+        })
+        .flat_map(|items| &items.items)
+        .filter_map(ToExprTree::tree)
+        .collect()
+}
+
+pub(crate) fn promises<'a>(contracts: &[&'a Contract]) -> Vec<ExprTree<'a>> {
+    contracts
+        .iter()
+        .flat_map(|contract| &contract.segments)
+        .filter_map(|segment| match segment {
+            ContractSegment::Promise(promise) => promise.items.success_ref(),
+            ContractSegment::Require(_) => None,
+        })
+        .flat_map(|items| &items.items)
+        .filter_map(ToExprTree::tree)
+        .collect()
+}
+
+pub(crate) fn param_name(param: &Param, interner: &Interner) -> String {
+    match param.body.success_ref().and_then(ParamBody::as_ident) {
+        Some(ident) => ident.to_string_interned(interner),
+        None => "_".to_string(),
+    }
+}
+
+/// A formula a call requires, or `None` if it can't be expressed, with where it's reported and how it's written.
+pub(crate) type Requirement = (Option<Formula>, Span, String);
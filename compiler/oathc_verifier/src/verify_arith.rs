@@ -0,0 +1,108 @@
+use super::*;
+
+impl<'ctx, 'c> Verifier<'ctx, 'c> {
+    /// Requires arithmetic on fixed-width integers not to overflow and shifts to be by less than the width,
+    /// at the level of the `overflow` lint, and divisors not to be zero, at the level of the `div_by_zero` lint.
+    ///
+    /// The width is the type of the variables the operands are made of, or for arithmetic on literals alone,
+    /// the type of what it's bound to.
+    /// Products of two variables aren't linear, so they're reported as unverifiable at the lint's level.
+    /// The wrapping operators have no obligations.
+    ///
+    /// Returns whether the result can be used, which it can't if it always overflows.
+    pub(crate) fn check_arithmetic(&mut self, lhs: &Sym, op: BinOp, rhs: &Sym, tree: &ExprTree<'ctx>) -> bool {
+        let (Sym::Int(lhs), Sym::Int(rhs)) = (lhs, rhs) else {
+            return true;
+        };
+        let span = tree.span();
+        let text = tree.to_string_interned(self.interner);
+
+        if let BinOp::Div(_) | BinOp::Rem(_) = op {
+            let nonzero = Formula::cmp(rhs, CmpOp::NotEq, &Linear::constant(0));
+            self.obligate_at(
+                self.lints.div_by_zero,
+                nonzero,
+                |_, counterexample| Error::DivByZero(span, text, counterexample),
+                || Warning::UnverifiedDivision(span),
+            );
+            return true;
+        }
+
+        let Some(int_type) = self.int_type_of(lhs).or_else(|| self.int_type_of(rhs)).or(self.width) else {
+            return true;
+        };
+        let (min, max) = Ty::overflow_range(int_type);
+
+        if let BinOp::Shl(_) | BinOp::Shr(_) = op {
+            let in_width = Formula::range(rhs, Some(0), Some(int_type.bits() as i128 - 1));
+            self.obligate_at(
+                self.lints.overflow,
+                in_width,
+                |_, counterexample| Error::ShiftOverflow(span, text.clone(), int_type.name(), counterexample),
+                || Warning::UnverifiedOverflow(span),
+            );
+        }
+
+        let value = match op {
+            BinOp::Add(_) => lhs.add(rhs),
+            BinOp::Sub(_) => lhs.sub(rhs),
+            BinOp::Mul(_) => match (lhs.as_constant(), rhs.as_constant()) {
+                (Some(lhs_value), _) => rhs.scale(lhs_value),
+                (_, Some(rhs_value)) => lhs.scale(rhs_value),
+                _ => None,
+            },
+            BinOp::Shl(_) => match rhs.as_constant().and_then(|rhs| u32::try_from(rhs).ok()) {
+                Some(rhs) => 1i128.checked_shl(rhs).and_then(|factor| lhs.scale(factor)),
+                None => None,
+            },
+            _ => return true,
+        };
+        let in_range = value.and_then(|value| Formula::range(&value, Some(min), Some(max)));
+        let held = self.obligate_at(
+            self.lints.overflow,
+            in_range.clone(),
+            |_, counterexample| Error::Overflow(span, text, int_type.name(), counterexample),
+            || Warning::UnverifiedOverflow(span),
+        );
+        let Some(in_range) = in_range.filter(|_| !held) else {
+            return true;
+        };
+
+        // A reported overflow is assumed not to happen from here on, so what the result is used for isn't reported too,
+        // unless it always happens.
+        let mut facts = self.facts.clone();
+        facts.push(in_range.clone());
+        if contradictory(&facts) {
+            return false;
+        }
+        self.facts.push(in_range);
+
+        true
+    }
+
+    /// Whether a value is proven to be in the range of an integer type, so wrapping arithmetic that results in it doesn't wrap.
+    pub(crate) fn proven_in_range(&self, value: &Linear, int_type: IntType) -> bool {
+        let (min, max) = Ty::overflow_range(int_type);
+        Formula::range(value, Some(min), Some(max)).is_some_and(|in_range| prove(&self.facts, &in_range) == Verdict::Proven)
+    }
+
+    /// The type of the first integer variable with one in the value.
+    pub(crate) fn int_type_of(&self, value: &Linear) -> Option<IntType> {
+        value.vars().find_map(|var| self.vars[var.0].int_type)
+    }
+
+    /// `name: type` for an integer bound to a local, parameter or output of a fixed-width type, with the formula for it,
+    /// since every value of the type is assumed to be in its range. `None` for other values, and for integers made of
+    /// variables of unknown types, whose arithmetic isn't checked either.
+    pub(crate) fn type_range(&self, value: &Sym, ty: &Ty, name: &str) -> Option<(Option<Formula>, String)> {
+        let (Sym::Int(value), Ty::Int(Some(int_type))) = (value, ty) else {
+            return None;
+        };
+        if value.vars().any(|var| self.vars[var.0].int_type.is_none()) {
+            return None;
+        }
+        let (min, max) = Ty::int_range(*int_type);
+
+        Some((Formula::range(value, min, max), format!("{name}: {}", int_type.name())))
+    }
+}
@@ -0,0 +1,791 @@
+use super::*;
+
+/// The state at the end of one branch of an `if` or a `match`.
+#[derive(Debug)]
+pub(crate) struct Branch<'ctx> {
+    scopes: Vec<HashMap<StrId, Local<'ctx>>>,
+    trace: Vec<Step>,
+    /// The facts that were learned in the branch, including its condition.
+    facts: Vec<Formula>,
+    reachable: bool,
+    value: Sym,
+}
+
+impl<'ctx, 'c> Verifier<'ctx, 'c> {
+    /// Returns the value of the block and the span it came from.
+    pub(crate) fn verify_block(&mut self, block: &'ctx Block) -> (Sym, Span) {
+        self.scopes.push(HashMap::new());
+
+        let mut output = (Sym::Tuple(Vec::new()), block.span());
+        for stmt in &block.stmts {
+            if !self.reachable {
+                break;
+            }
+
+            if let Some(value) = self.verify_stmt(stmt) {
+                output = value;
+                break;
+            }
+        }
+
+        self.scopes.pop();
+
+        output
+    }
+
+    /// Returns the value of the block if the statement is an `eval`.
+    fn verify_stmt(&mut self, stmt: &'ctx Stmt) -> Option<(Sym, Span)> {
+        match stmt {
+            Stmt::Item(_) => {}
+            Stmt::Let(let_) => self.verify_let(let_),
+            Stmt::Eval(stmt) => {
+                return Some(match &stmt.value {
+                    Some(value) => (self.sym_expr(value), value.option_span().unwrap_or(stmt.keyword.span())),
+                    None => (Sym::Tuple(Vec::new()), stmt.keyword.span()),
+                });
+            }
+            Stmt::Return(stmt) => {
+                let (value, span) = match &stmt.value {
+                    Some(value) => (self.sym_expr(value), value.option_span().unwrap_or(stmt.keyword.span())),
+                    None => (Sym::Tuple(Vec::new()), stmt.keyword.span()),
+                };
+
+                self.check_return(value, span);
+                self.reachable = false;
+            }
+            Stmt::Break(stmt) => self.verify_break(stmt),
+            Stmt::Continue(stmt) => self.verify_continue(stmt),
+            Stmt::Assume(stmt) => {
+                let span = stmt.keyword.span().connect(stmt.value.option_span());
+                let value = stmt.value.tree().map_or(Sym::Opaque, |tree| self.sym_contract(&tree));
+
+                if let Some(formula) = value.as_formula() {
+                    self.push_step(span, "assumed here".to_string(), &value);
+                    self.facts.push(formula);
+                }
+            }
+            Stmt::Expr(stmt) => match &stmt.set {
+                Some(set) => {
+                    let span = set.value.option_span().unwrap_or(set.eq.span());
+                    let ty = self.place_ty(&stmt.expr);
+                    let value = self.sym_expr_as(&set.value, &ty);
+
+                    self.assign(&stmt.expr, value, span);
+                }
+                None => {
+                    self.sym_expr(&stmt.expr);
+                }
+            },
+        }
+
+        None
+    }
+
+    fn verify_let(&mut self, let_: &'ctx LetStmt) {
+        let Try::Success(param) = &let_.name else {
+            return;
+        };
+
+        let ty = self.param_type(param);
+        let name = param
+            .body
+            .success_ref()
+            .and_then(ParamBody::as_ident)
+            .map(|ident| ident.str_id());
+
+        let (value, span) = match &let_.value {
+            Some(assign) => (
+                self.sym_expr_as(&assign.value, &ty),
+                assign.value.option_span().unwrap_or(assign.eq.span()),
+            ),
+            None => (Sym::Opaque, let_.keyword.span()),
+        };
+        let mut value = self.materialize(value, &ty, name, span);
+        let ty = match ty {
+            Ty::Opaque => ty_of_sym(&value),
+            ty => ty,
+        };
+        // Checked for the value before it's named, since the variable it's named with has the local's type.
+        let in_range = match &let_.value {
+            Some(_) => self.type_range(&value, &ty, &param_name(param, self.interner)),
+            None => None,
+        };
+        if let (Some(name), Some(_)) = (name, &let_.value) {
+            value = self.name_value(value, &ty, name, span);
+        }
+
+        if let Some((in_range, text)) = in_range {
+            self.obligate(in_range, |_, counterexample| Error::BoundViolated(span, text, counterexample));
+        }
+        if let (Some(bounds), Some(_)) = (param.bounds.as_ref().and_then(|bounds| bounds.expr.tree()), &let_.value) {
+            let name = param_name(param, self.interner);
+            self.check_bound(&value, &bounds, |verifier, bounds, counterexample| {
+                Error::BoundViolated(
+                    span,
+                    format!("{name}: {}", bounds.to_string_interned(verifier.interner)),
+                    counterexample,
+                )
+            });
+        }
+
+        // A constrained local is shown with its bounds, like a parameter.
+        if let (Some(name), Some(_)) = (name, &let_.value) {
+            let message = match param.bounds.as_ref().and_then(|bounds| bounds.expr.tree()) {
+                Some(bounds) => {
+                    let name = Interned(&name, self.interner);
+                    format!(
+                        "`{name}` is declared as `{name}: {}`",
+                        bounds.to_string_interned(self.interner)
+                    )
+                }
+                None => format!("`{}` is defined here", Interned(&name, self.interner)),
+            };
+            self.push_step(span, message, &value);
+        }
+
+        self.bind_param(param, value, ty);
+    }
+
+    /// Assigning to a local replaces what's known about it, and has to satisfy its bounds.
+    /// Assigning to a field of it also has to satisfy the invariants of the structs it's in.
+    /// Assigning to a part of it the verifier can't follow forgets what's known about it.
+    fn assign(&mut self, place: &'ctx Expr, value: Sym, span: Span) {
+        let Some(tree) = place.tree() else {
+            return;
+        };
+        let Some(ident) = place_root(&tree) else {
+            if is_deref(&tree) {
+                self.forget_borrowed(span);
+            }
+            return;
+        };
+
+        let name = ident.str_id();
+        let Some(local) = self.local(name).cloned() else {
+            return;
+        };
+
+        let path = tree
+            .as_unary()
+            .into_iter()
+            .flat_map(|unary| &unary.exts)
+            .map(|ext| match ext {
+                UnaryExprExt::Member(member) => member.member.success_ref(),
+                _ => None,
+            });
+        let mut in_range = None;
+        let value = match path.collect::<Option<Vec<_>>>() {
+            Some(path) if path.is_empty() => {
+                let value = self.materialize(value, &local.ty, Some(name), span);
+                in_range = self.type_range(&value, &local.ty, &Interned(&name, self.interner).to_string());
+                Some(self.name_value(value, &local.ty, name, span))
+            }
+            Some(path) => self.assign_member(local.value.clone(), &path, value, span),
+            None => None,
+        };
+        let Some(value) = value else {
+            if let Ty::Struct(id, generics) = &local.ty
+                && !self.knows_generics(*id, generics)
+            {
+                self.instance_sites.insert(span);
+            }
+            self.forget(name, &local, span);
+            // The local may be a reference that's assigned through.
+            if local.ty == Ty::Opaque {
+                self.forget_borrowed(span);
+            }
+            return;
+        };
+
+        if let Some((in_range, text)) = in_range {
+            self.obligate(in_range, |_, counterexample| Error::BoundViolated(span, text, counterexample));
+        }
+        if let Some(bounds) = &local.bounds {
+            self.check_bound(&value, bounds, |verifier, bounds, counterexample| {
+                Error::BoundViolated(
+                    span,
+                    format!(
+                        "{}: {}",
+                        Interned(&name, verifier.interner),
+                        Interned(bounds, verifier.interner)
+                    ),
+                    counterexample,
+                )
+            });
+        }
+
+        let message = format!("`{}` is assigned here", Interned(&name, self.interner));
+        self.push_step(span, message, &value);
+
+        self.set_local(name, value);
+    }
+
+    /// The type of a place that's a local, `Ty::Opaque` for other places.
+    fn place_ty(&self, place: &'ctx Expr) -> Ty {
+        let ident = place
+            .tree()
+            .as_ref()
+            .and_then(ExprTree::as_unary)
+            .and_then(UnaryExprRef::as_ident);
+
+        match ident.and_then(|ident| self.local(ident.str_id())) {
+            Some(local) => local.ty.clone(),
+            None => Ty::Opaque,
+        }
+    }
+
+    /// Gives an integer or boolean value its own variable, named after the local of the type it's assigned to,
+    /// so counterexamples can refer to it.
+    fn name_value(&mut self, value: Sym, ty: &Ty, name: StrId, span: Span) -> Sym {
+        match &value {
+            Sym::Int(linear) => {
+                let int_type = match ty {
+                    Ty::Int(Some(int_type)) => Some(*int_type),
+                    _ => self.int_type_of(linear),
+                };
+                // The output of a call takes the local's name rather than being shown next to it.
+                if let Some(var) = linear.vars().next()
+                    && *linear == Linear::var(var)
+                    && self.vars[var.0].is_output
+                    && self.vars[var.0].int_type == int_type
+                {
+                    self.vars[var.0].name = Some(name);
+                    self.vars[var.0].is_output = false;
+                    return Sym::Int(linear.clone());
+                }
+                let named = Linear::var(self.new_var(Some(name), span, false, int_type));
+                self.facts.extend(Formula::cmp(&named, CmpOp::Eq, linear));
+
+                Sym::Int(named)
+            }
+            Sym::Bool(formula) => {
+                let named = self.fresh(&Ty::Bool, Some(name), span);
+                self.facts.extend(equate(&named, &Sym::Bool(formula.clone())));
+
+                named
+            }
+            _ => value,
+        }
+    }
+
+    pub(crate) fn push_step(&mut self, span: Span, message: String, value: &Sym) {
+        let mut vars = BTreeSet::new();
+        value.vars(&mut vars);
+
+        if !vars.is_empty() {
+            self.trace.push(Step {
+                span,
+                message,
+                vars,
+                guard: None,
+            });
+        }
+    }
+
+    pub(crate) fn local(&self, name: StrId) -> Option<&Local<'ctx>> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(&name))
+    }
+
+    pub(crate) fn set_local(&mut self, name: StrId, value: Sym) {
+        if let Some(local) = self.scopes.iter_mut().rev().find_map(|scope| scope.get_mut(&name)) {
+            local.value = value;
+        }
+    }
+
+    /// Replaces a local with a fresh value that only satisfies its type and bounds.
+    pub(crate) fn forget(&mut self, name: StrId, local: &Local<'ctx>, span: Span) {
+        let value = self.fresh(&local.ty, Some(name), span);
+        if let Some(bounds) = &local.bounds {
+            let formula = self.bound_formula(&value, bounds);
+            self.facts.extend(formula);
+        }
+
+        self.set_local(name, value);
+    }
+
+    /// Forgets what's known about the locals that may be assigned, and returns the values they get instead.
+    pub(crate) fn havoc(&mut self, assigned: &Assigned, span: Span) -> Sym {
+        let locals = self
+            .scopes
+            .iter()
+            .flat_map(|scope| scope.iter())
+            .filter(|(name, local)| assigned.locals.contains(name) || assigned.through_refs && local.borrowed_mut)
+            .map(|(name, local)| (*name, local.clone()))
+            .collect::<Vec<_>>();
+
+        let mut values = Vec::new();
+        for (name, local) in locals {
+            self.forget(name, &local, span);
+            values.extend(self.local(name).map(|local| local.value.clone()));
+        }
+
+        Sym::Tuple(values)
+    }
+
+    /// Forgets what's known about the locals borrowed as `&mut` or `&smut`, after something may have written through a reference.
+    pub(crate) fn forget_borrowed(&mut self, span: Span) {
+        let assigned = Assigned {
+            through_refs: true,
+            ..Assigned::default()
+        };
+        self.havoc(&assigned, span);
+    }
+
+    /// Takes a `&mut` or `&smut` reference, after which the local it's into can be assigned through it.
+    pub(crate) fn borrow_mut(&mut self, borrow: MutBorrow, span: Span) {
+        let ident = match borrow {
+            MutBorrow::Local(ident) => ident,
+            MutBorrow::ThroughRef => return self.forget_borrowed(span),
+        };
+        let Some(local) = self.scopes.iter_mut().rev().find_map(|scope| scope.get_mut(&ident.str_id())) else {
+            return;
+        };
+        local.borrowed_mut = true;
+
+        let local = local.clone();
+        self.forget(ident.str_id(), &local, span);
+    }
+
+    pub(crate) fn verify_if(&mut self, if_: &'ctx If) -> Sym {
+        let condition = match &if_.condition {
+            Try::Success(condition) => self.sym_expr(&**condition).as_formula(),
+            Try::Failure(_) => None,
+        };
+
+        let span = if_.keyword.span();
+        let condition_span = if_.condition.option_span().unwrap_or(span);
+        let facts_len = self.facts.len();
+        let trace_len = self.trace.len();
+        let scopes = self.scopes.clone();
+
+        self.assume_condition(condition.clone(), condition_span, true);
+        let then_value = match &if_.body {
+            IfBody::Then(then) => self.sym_expr(&then.expr),
+            IfBody::Block(block) => match &block.block {
+                Try::Success(block) => self.verify_block(block).0,
+                Try::Failure(_) => Sym::Opaque,
+            },
+        };
+        let then = self.take_branch(facts_len, trace_len, scopes.clone(), then_value);
+
+        self.assume_condition(condition, condition_span, false);
+        let else_ = match &if_.body {
+            IfBody::Then(then) => then.else_.as_ref(),
+            IfBody::Block(block) => block.else_.as_ref(),
+        };
+        let else_value = match else_ {
+            Some(else_) => self.sym_expr(&else_.expr),
+            None => Sym::Tuple(Vec::new()),
+        };
+        let else_ = self.take_branch(facts_len, trace_len, scopes, else_value);
+
+        self.join(then, else_, span)
+    }
+
+    /// Assumes a condition holds, or doesn't, and records that as a step.
+    pub(crate) fn assume_condition(&mut self, condition: Option<Formula>, span: Span, holds: bool) {
+        let Some(condition) = condition else {
+            return;
+        };
+        let condition = if holds { condition } else { condition.not() };
+
+        let message = format!("when this is `{holds}`");
+        self.push_step(span, message, &Sym::Bool(condition.clone()));
+        self.facts.push(condition);
+    }
+
+    pub(crate) fn take_branch(
+        &mut self,
+        facts_len: usize,
+        trace_len: usize,
+        scopes: Vec<HashMap<StrId, Local<'ctx>>>,
+        value: Sym,
+    ) -> Branch<'ctx> {
+        Branch {
+            scopes: replace(&mut self.scopes, scopes),
+            trace: self.trace.split_off(trace_len),
+            facts: self.facts.split_off(facts_len),
+            reachable: replace(&mut self.reachable, true),
+            value,
+        }
+    }
+
+    /// A branch that leaves the code being verified from here, to code that's in the outermost `depth` scopes.
+    pub(crate) fn branch_here(&self, depth: usize, facts_len: usize, trace_len: usize, value: Sym) -> Branch<'ctx> {
+        Branch {
+            scopes: self.scopes[..depth].to_vec(),
+            trace: self.trace[trace_len..].to_vec(),
+            facts: self.facts[facts_len..].to_vec(),
+            reachable: true,
+            value,
+        }
+    }
+
+    /// Continues from whichever branches are reachable.
+    ///
+    /// If both are, locals that differ between them get fresh values that equal the value from either branch,
+    /// and the facts of the branches are joined into a disjunction.
+    pub(crate) fn join(&mut self, lhs: Branch<'ctx>, rhs: Branch<'ctx>, span: Span) -> Sym {
+        match (lhs.reachable, rhs.reachable) {
+            (false, false) => {
+                self.scopes = lhs.scopes;
+                self.reachable = false;
+
+                Sym::Opaque
+            }
+            (true, false) => self.resume(lhs),
+            (false, true) => self.resume(rhs),
+            (true, true) => {
+                let mut lhs_facts = lhs.facts;
+                let mut rhs_facts = rhs.facts;
+
+                let mut scopes = lhs.scopes;
+                for (scope, rhs_scope) in scopes.iter_mut().zip(&rhs.scopes) {
+                    for (name, local) in scope.iter_mut() {
+                        let Some(rhs_local) = rhs_scope.get(name) else {
+                            continue;
+                        };
+
+                        if local.value != rhs_local.value {
+                            let ty = local.ty.clone();
+                            let (lhs_value, rhs_value) = (&local.value, &rhs_local.value);
+
+                            local.value =
+                                self.merge(lhs_value, rhs_value, &ty, Some(*name), span, &mut lhs_facts, &mut rhs_facts);
+                        }
+                    }
+                }
+                self.scopes = scopes;
+
+                let value = if lhs.value == rhs.value {
+                    lhs.value
+                } else {
+                    let ty = ty_of_sym(&lhs.value);
+                    self.merge(&lhs.value, &rhs.value, &ty, None, span, &mut lhs_facts, &mut rhs_facts)
+                };
+
+                // Steps from either branch only apply when its facts hold.
+                for (trace, facts) in [(lhs.trace, &lhs_facts), (rhs.trace, &rhs_facts)] {
+                    let guard = Formula::and(facts.iter().cloned());
+                    self.trace.extend(trace.into_iter().map(|step| step.guarded(guard.clone())));
+                }
+
+                if !lhs_facts.is_empty() || !rhs_facts.is_empty() {
+                    self.facts
+                        .push(Formula::or([Formula::and(lhs_facts), Formula::and(rhs_facts)]));
+                }
+
+                value
+            }
+        }
+    }
+
+    /// Continues from the end of a branch.
+    pub(crate) fn resume(&mut self, branch: Branch<'ctx>) -> Sym {
+        self.scopes = branch.scopes;
+        self.trace.extend(branch.trace);
+        self.facts.extend(branch.facts);
+        self.reachable = branch.reachable;
+
+        branch.value
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn merge(
+        &mut self,
+        lhs: &Sym,
+        rhs: &Sym,
+        ty: &Ty,
+        name: Option<StrId>,
+        span: Span,
+        lhs_facts: &mut Vec<Formula>,
+        rhs_facts: &mut Vec<Formula>,
+    ) -> Sym {
+        let ty = match ty {
+            Ty::Opaque => ty_of_sym(lhs),
+            ty => ty.clone(),
+        };
+        let merged = self.fresh(&ty, name, span);
+
+        lhs_facts.extend(equate(&merged, lhs));
+        rhs_facts.extend(equate(&merged, rhs));
+
+        merged
+    }
+
+    pub(crate) fn verify_loop(&mut self, loop_: &'ctx Loop, label: Option<StrId>, attrs: &'ctx [Attr]) -> Sym {
+        let context = self.verify_loop_body(&loop_.block, loop_.keyword.span(), None, None, label, None, attrs);
+
+        // A loop without a `break` only exits by returning.
+        if !context.broke {
+            self.reachable = false;
+        }
+
+        match context.value {
+            Some((ty, _)) => self.fresh(&ty, None, loop_.keyword.span()),
+            None => Sym::Opaque,
+        }
+    }
+
+    pub(crate) fn verify_while(&mut self, while_: &'ctx While, label: Option<StrId>, attrs: &'ctx [Attr]) -> Sym {
+        let span = while_.keyword.span();
+        self.verify_loop_body(
+            &while_.block,
+            span,
+            Some((&while_.condition, true)),
+            None,
+            label,
+            Some(span),
+            attrs,
+        );
+
+        Sym::Tuple(Vec::new())
+    }
+
+    pub(crate) fn verify_until(&mut self, until: &'ctx Until, label: Option<StrId>, attrs: &'ctx [Attr]) -> Sym {
+        let span = until.keyword.span();
+        self.verify_loop_body(
+            &until.block,
+            span,
+            Some((&until.condition, false)),
+            None,
+            label,
+            Some(span),
+            attrs,
+        );
+
+        Sym::Tuple(Vec::new())
+    }
+
+    pub(crate) fn verify_for(&mut self, for_: &'ctx For, label: Option<StrId>, attrs: &'ctx [Attr]) -> Sym {
+        let iter = self.sym_expr(&for_.iter);
+        let item = for_.item.success_ref().map(|item| (&**item, iter));
+
+        let span = for_.keyword.span();
+        self.verify_loop_body(&for_.block, span, None, item, label, Some(span), attrs);
+
+        Sym::Tuple(Vec::new())
+    }
+
+    /// Binds the item of a `for` loop to a value of the range or array it iterates over,
+    /// which is between the ends of a range or one of the items of an array. Returns the value.
+    fn bind_item(&mut self, item: &'ctx Param, iter: &Sym, span: Span) -> Sym {
+        let ty = match (self.param_type(item), iter) {
+            (Ty::Opaque, Sym::Range(..)) => Ty::Int(None),
+            (Ty::Opaque, Sym::Array(items)) => items.first().map_or(Ty::Opaque, ty_of_sym),
+            (ty, _) => ty,
+        };
+        let name = item
+            .body
+            .success_ref()
+            .and_then(ParamBody::as_ident)
+            .map(|ident| ident.str_id());
+        let value = self.fresh(&ty, name, span);
+
+        let fact = match (&value, iter) {
+            (Sym::Int(value), Sym::Range(start, end)) => Formula::cmp(start, CmpOp::LessEq, value)
+                .zip(Formula::cmp(value, CmpOp::Less, end))
+                .map(|(start, end)| Formula::and([start, end])),
+            (value, Sym::Array(items)) => items
+                .iter()
+                .map(|item| equate(value, item))
+                .collect::<Option<Vec<_>>>()
+                .map(Formula::or),
+            _ => None,
+        };
+        self.facts.extend(fact);
+
+        self.bind_param(item, value.clone(), ty);
+
+        value
+    }
+
+    /// Verifies a single iteration that starts from an unknown state, since any number of iterations may have run before it.
+    ///
+    /// The condition holds inside the body when it's `true`, or doesn't hold when it's `false`. Unless the loop breaks,
+    /// it exits at the head of an iteration where that's reversed. The item of a `for` loop is bound in every iteration
+    /// to a value of what it iterates over. Loops that can end without a `break` evaluate to `()` when they do,
+    /// which `end` is where of. Returns the context of the loop, to tell whether the body can `break`.
+    ///
+    /// The `#[invariant(..)]`s of the loop are checked before it, assumed at the head of the iteration and checked
+    /// where it continues, which is also where its `#[decreases(..)]` have to decrease. For a `for` loop over a range,
+    /// they're checked for the start of the range, and for the item after the current one, and they hold for the end
+    /// of the range after the loop.
+    #[allow(clippy::too_many_arguments)]
+    fn verify_loop_body(
+        &mut self,
+        block: &'ctx Try<Block>,
+        span: Span,
+        condition: Option<(&'ctx Try<Box<BraceExpr>>, bool)>,
+        item: Option<(&'ctx Param, Sym)>,
+        label: Option<StrId>,
+        end: Option<Span>,
+        attrs: &'ctx [Attr],
+    ) -> ExitContext<'ctx> {
+        let invariants = attr_exprs(attrs, "invariant", self.interner);
+        let measures = attr_exprs(attrs, "decreases", self.interner);
+        let range = match &item {
+            Some((param, Sym::Range(start, end))) => Some((*param, start.clone(), end.clone())),
+            _ => None,
+        };
+        self.establish_loop_invariants(
+            &invariants,
+            range
+                .as_ref()
+                .map(|(param, start, _)| (*param, Sym::Int(start.clone())))
+                .as_ref(),
+        );
+
+        let mut assigned = Assigned::default();
+        if let Try::Success(block) = block {
+            assigned_in_block(block, &mut assigned);
+        }
+
+        let values = self.havoc(&assigned, span);
+        self.push_step(span, "after any number of iterations".to_string(), &values);
+        if range.is_none() {
+            self.assume_loop_invariants(&invariants, None);
+        }
+
+        let (condition, condition_span, continue_if) = match condition {
+            Some((condition, continue_if)) => (
+                self.sym_expr(condition).as_formula(),
+                condition.option_span().unwrap_or(span),
+                continue_if,
+            ),
+            None => (None, span, true),
+        };
+
+        let head_scopes = self.scopes.clone();
+        let facts_len = self.facts.len();
+        let trace_len = self.trace.len();
+
+        self.assume_condition(condition.clone(), condition_span, continue_if);
+        self.scopes.push(HashMap::new());
+        let mut next_item = None;
+        if let Some((item, iter)) = item {
+            let value = self.bind_item(item, &iter, span);
+            if let (Some(_), Sym::Int(value)) = (&range, value) {
+                self.assume_loop_invariants(&invariants, None);
+                next_item = value.offset(1).map(|next| (item, Sym::Int(next)));
+            }
+        }
+        let measures = measures
+            .into_iter()
+            .map(|measure| {
+                let value = self.sym_tree(&measure);
+                (measure, value)
+            })
+            .collect::<Vec<_>>();
+
+        self.push_exit_context(label, true, end.map(|end| (Ty::Tuple(Vec::new()), end)));
+        if !invariants.is_empty() || !measures.is_empty() {
+            self.exit_contexts.last_mut().unwrap().contract = Some(LoopContract {
+                invariants: invariants.clone(),
+                measures,
+                next_item,
+            });
+        }
+        if let Try::Success(block) = block {
+            self.verify_block(block);
+            if self.reachable {
+                self.continue_loop(self.exit_contexts.len() - 1, block.frame.delims.close_span);
+            }
+        }
+
+        let context = self.exit_contexts.pop().unwrap();
+        self.facts.truncate(facts_len);
+        self.trace.truncate(trace_len);
+        self.scopes = head_scopes;
+        self.reachable = true;
+
+        if context.broke {
+            let values = self.havoc(&assigned, span);
+            self.push_step(span, "after breaking out of this loop".to_string(), &values);
+        } else {
+            self.assume_condition(condition, condition_span, !continue_if);
+
+            // The loop only runs to the end of a range that doesn't end before it starts.
+            if let Some((param, start, end)) = range {
+                let facts_len = self.facts.len();
+                self.assume_loop_invariants(&invariants, Some(&(param, Sym::Int(end.clone()))));
+                let facts = self.facts.split_off(facts_len);
+
+                if let Some(empty) = Formula::cmp(&end, CmpOp::Less, &start) {
+                    self.facts.push(Formula::or([empty, Formula::and(facts)]));
+                }
+            }
+        }
+
+        context
+    }
+}
+
+/// `lhs == rhs`, if the verifier can express it.
+pub(crate) fn equate(lhs: &Sym, rhs: &Sym) -> Option<Formula> {
+    match (lhs, rhs) {
+        (Sym::Int(lhs), Sym::Int(rhs)) => Formula::cmp(lhs, CmpOp::Eq, rhs),
+        (Sym::Bool(lhs), Sym::Bool(rhs)) => Some(Formula::iff(lhs.clone(), rhs.clone())),
+        (Sym::Tuple(lhs), Sym::Tuple(rhs)) if lhs.len() == rhs.len() => Some(Formula::and(
+            lhs.iter()
+                .zip(rhs)
+                .map(|(lhs, rhs)| equate(lhs, rhs))
+                .collect::<Option<Vec<_>>>()?,
+        )),
+        (Sym::Range(lhs_start, lhs_end), Sym::Range(rhs_start, rhs_end)) => Some(Formula::and([
+            Formula::cmp(lhs_start, CmpOp::Eq, rhs_start)?,
+            Formula::cmp(lhs_end, CmpOp::Eq, rhs_end)?,
+        ])),
+        (Sym::Array(lhs), Sym::Array(rhs)) if lhs.len() == rhs.len() => Some(Formula::and(
+            lhs.iter()
+                .zip(rhs)
+                .map(|(lhs, rhs)| equate(lhs, rhs))
+                .collect::<Option<Vec<_>>>()?,
+        )),
+        (Sym::Struct(lhs_id, _, lhs), Sym::Struct(rhs_id, _, rhs)) if lhs_id == rhs_id => Some(Formula::and(
+            lhs.iter()
+                .zip(rhs)
+                .map(|(lhs, rhs)| equate(lhs, rhs))
+                .collect::<Option<Vec<_>>>()?,
+        )),
+        (Sym::Enum(lhs_id, lhs_tag, lhs), Sym::Enum(rhs_id, rhs_tag, rhs)) if lhs_id == rhs_id => {
+            let mut output = vec![Formula::cmp(lhs_tag, CmpOp::Eq, rhs_tag)?];
+
+            // Only the payload of the variant the values are matters, and payloads that can't be equated are left out.
+            for (index, (lhs, rhs)) in lhs.iter().zip(rhs).enumerate() {
+                if let Some(payload) = equate(lhs, rhs) {
+                    let other = Formula::cmp(lhs_tag, CmpOp::NotEq, &Linear::constant(index as i128))?;
+                    output.push(Formula::or([other, payload]));
+                }
+            }
+
+            Some(Formula::and(output))
+        }
+        _ => None,
+    }
+}
+
+pub(crate) fn ty_of_sym(value: &Sym) -> Ty {
+    match value {
+        Sym::Int(_) => Ty::Int(None),
+        Sym::Bool(_) => Ty::Bool,
+        Sym::Tuple(values) => Ty::Tuple(values.iter().map(ty_of_sym).collect()),
+        Sym::Struct(id, generics, _) => Ty::Struct(*id, generics.clone()),
+        Sym::Enum(id, ..) => Ty::Enum(*id),
+        Sym::Array(items) => Ty::Array(
+            Box::new(items.first().map_or(Ty::Opaque, ty_of_sym)),
+            Some(Linear::constant(items.len() as i128)),
+        ),
+        Sym::ArrayOf(ty, len, _) => Ty::Array(Box::new(ty.clone()), Some(len.clone())),
+        Sym::Bound(_)
+        | Sym::Variant(..)
+        | Sym::Fn(..)
+        | Sym::Closure(_)
+        | Sym::Type(_)
+        | Sym::Impl(..)
+        | Sym::TraitFn(..)
+        | Sym::ImplFn(..)
+        | Sym::Range(..)
+        | Sym::Opaque => Ty::Opaque,
+    }
+}
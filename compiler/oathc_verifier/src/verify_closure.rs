@@ -0,0 +1,100 @@
+use super::*;
+
+impl<'ctx, 'c> Verifier<'ctx, 'c> {
+    /// Verifies the body of a closure against its own contract, and returns the closure as a value that can be called.
+    ///
+    /// A closure can be called after the locals it captures change, so in its body they're only known by their bounds.
+    pub(crate) fn verify_closure(&mut self, fn_: &'ctx FnExpr) -> Sym {
+        let id = self.closures.len();
+        self.closures.push(fn_);
+
+        let (Try::Success(input), Some(body)) = (&fn_.input, &fn_.body) else {
+            return Sym::Closure(id);
+        };
+        if self.contract_depth > 0 {
+            return Sym::Closure(id);
+        }
+
+        let span = fn_.keyword.span();
+        let captures = self
+            .scopes
+            .iter()
+            .flat_map(|scope| scope.iter())
+            .map(|(name, local)| (*name, local.clone()))
+            .collect::<HashMap<_, _>>();
+
+        let outer_scopes = replace(&mut self.scopes, vec![captures.clone()]);
+        let outer_facts = take(&mut self.facts);
+        let outer_trace = take(&mut self.trace);
+        let outer_fn = self.fn_.take();
+        let outer_out = self.out.take();
+        let outer_reachable = replace(&mut self.reachable, true);
+        let outer_tries = take(&mut self.tries);
+        let outer_exit_contexts = take(&mut self.exit_contexts);
+
+        for (name, local) in &captures {
+            self.forget(*name, local, span);
+        }
+
+        self.scopes.push(HashMap::new());
+        self.enter_fn(input, &fn_.contract, fn_.output.as_deref());
+
+        let (value, span) = self.verify_block(body);
+        if self.reachable {
+            self.check_return(value, span);
+        }
+
+        self.scopes = outer_scopes;
+        self.facts = outer_facts;
+        self.trace = outer_trace;
+        self.fn_ = outer_fn;
+        self.out = outer_out;
+        self.reachable = outer_reachable;
+        self.tries = outer_tries;
+        self.exit_contexts = outer_exit_contexts;
+
+        Sym::Closure(id)
+    }
+
+    /// Checks a call to a closure against the closure's contract, like a call to a function,
+    /// and forgets what's known about the locals the closure may assign.
+    pub(crate) fn call_closure(&mut self, closure: usize, args: Vec<(Sym, Span)>, span: Span) -> Sym {
+        let fn_ = self.closures[closure];
+        let Try::Success(input) = &fn_.input else {
+            return Sym::Opaque;
+        };
+        if self.contract_depth >= MAX_CONTRACT_DEPTH {
+            return Sym::Opaque;
+        }
+
+        // The contract of a closure can refer to the locals it captures.
+        let mut scopes = self.scopes.clone();
+        scopes.push(HashMap::new());
+
+        let outer_scopes = replace(&mut self.scopes, scopes);
+        let outer_out = self.out.take();
+        self.contract_depth += 1;
+
+        let (output, requirements, promises) =
+            self.callee_contract(input, &fn_.contract, fn_.output.as_deref(), None, args, span);
+
+        self.scopes = outer_scopes;
+        self.out = outer_out;
+        self.contract_depth -= 1;
+
+        for (formula, span, text) in requirements {
+            self.obligate(formula, |_, counterexample| {
+                Error::ClosureRequireViolated(span, text, counterexample)
+            });
+        }
+        self.facts.extend(promises);
+
+        if let Some(body) = &fn_.body {
+            let mut assigned = Assigned::default();
+            assigned_in_block(body, &mut assigned);
+            self.havoc(&assigned, span);
+        }
+
+        output
+    }
+}
@@ -0,0 +1,177 @@
+use super::*;
+
+impl<'ctx, 'c> Verifier<'ctx, 'c> {
+    /// The variants of an enum the verifier can track, which excludes generic enums.
+    pub(crate) fn enum_variants(&self, id: ItemId) -> Option<&'ctx [Param]> {
+        match &self.namespace.item(id).core {
+            Try::Success(ItemCore::Enum(Enum {
+                generics: None,
+                variants: Try::Success(variants),
+                ..
+            })) => Some(&variants.items),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn variant_index(&self, id: ItemId, name: StrId) -> Option<usize> {
+        self.enum_variants(id)?.iter().position(|variant| {
+            variant
+                .body
+                .success_ref()
+                .and_then(ParamBody::as_ident)
+                .is_some_and(|ident| ident.str_id() == name)
+        })
+    }
+
+    /// A value that may be any variant, whose payload satisfies the variant's bounds if it's that variant.
+    pub(crate) fn fresh_enum(&mut self, id: ItemId, span: Span) -> Sym {
+        let Some(variants) = self.enum_variants(id) else {
+            return Sym::Opaque;
+        };
+        if self.structs.contains(&id) {
+            return Sym::Opaque;
+        }
+
+        let tag = Linear::var(self.new_var(None, span, false, None));
+        self.facts
+            .extend(Formula::range(&tag, Some(0), Some(variants.len() as i128 - 1)));
+
+        self.structs.push(id);
+        let mut payloads = Vec::with_capacity(variants.len());
+        for (index, variant) in variants.iter().enumerate() {
+            let payload = match variant.type_ {
+                Some(_) => {
+                    let ty = self.param_type(variant);
+                    self.fresh(&ty, None, span)
+                }
+                None => Sym::Tuple(Vec::new()),
+            };
+
+            if let Some(bound) = self.variant_bound(variant, &payload) {
+                let other = Formula::cmp(&tag, CmpOp::NotEq, &Linear::constant(index as i128));
+                self.facts.extend(other.map(|other| Formula::or([other, bound])));
+            }
+
+            payloads.push(payload);
+        }
+        self.structs.pop();
+
+        Sym::Enum(id, tag, payloads)
+    }
+
+    /// `Enum.Variant`, which is a value for variants without a payload and a constructor for the others.
+    pub(crate) fn variant(&mut self, id: ItemId, name: StrId) -> Sym {
+        let (Some(variants), Some(index)) = (self.enum_variants(id), self.variant_index(id, name)) else {
+            return Sym::Opaque;
+        };
+
+        match variants[index].type_ {
+            Some(_) => Sym::Variant(id, index),
+            None => self.enum_value(id, index, Sym::Tuple(Vec::new())),
+        }
+    }
+
+    /// Evaluates `Enum.Variant(payload)`, and checks the payload against the variant's bounds.
+    pub(crate) fn construct_variant(&mut self, id: ItemId, index: usize, args: Vec<(Sym, Span)>, span: Span) -> Sym {
+        let Some(variant) = self.enum_variants(id).map(|variants| &variants[index]) else {
+            return Sym::Opaque;
+        };
+
+        let (payload, payload_span) = match <[_; 1]>::try_from(args) {
+            Ok([(payload, payload_span)]) => (payload, payload_span),
+            Err(args) => (Sym::Tuple(args.into_iter().map(|(arg, _)| arg).collect()), span),
+        };
+        let ty = self.param_type(variant);
+        let payload = self.materialize(payload, &ty, None, payload_span);
+
+        if let Some(bounds) = variant.bounds.as_ref().and_then(|bounds| bounds.expr.tree()) {
+            let name = param_name(variant, self.interner);
+            self.check_bound(&payload, &bounds, |verifier, bounds, counterexample| {
+                let text = format!("{name}: {}", bounds.to_string_interned(verifier.interner));
+                Error::BoundViolated(payload_span, text, counterexample)
+            });
+        }
+
+        self.enum_value(id, index, payload)
+    }
+
+    /// A value of the variant at `index`, where the payloads of the other variants aren't tracked.
+    pub(crate) fn enum_value(&mut self, id: ItemId, index: usize, payload: Sym) -> Sym {
+        let Some(variants) = self.enum_variants(id) else {
+            return Sym::Opaque;
+        };
+
+        let mut payloads = vec![Sym::Opaque; variants.len()];
+        payloads[index] = payload;
+
+        Sym::Enum(id, Linear::constant(index as i128), payloads)
+    }
+
+    /// Evaluates `value is Variant`, which holds when the variant is the active one.
+    pub(crate) fn is_variant(&mut self, value: Sym, variant: &ExprTree<'ctx>, span: Span) -> Sym {
+        let (Some(name), Sym::Enum(id, tag, _)) = (variant_name(variant), value) else {
+            return Sym::Opaque;
+        };
+        let Some(index) = self.variant_index(id, name.str_id()) else {
+            self.error(Error::NoVariant(name.span(), self.item_name(id), name.str_id()));
+            return Sym::Opaque;
+        };
+        self.check_tagged(id, span);
+
+        Formula::cmp(&tag, CmpOp::Eq, &Linear::constant(index as i128)).map_or(Sym::Opaque, Sym::Bool)
+    }
+
+    /// Evaluates `value.Variant`, the payload of a variant, which has to be the active one.
+    pub(crate) fn variant_payload(&mut self, id: ItemId, tag: Linear, mut payloads: Vec<Sym>, name: Ident) -> Sym {
+        let Some(index) = self.variant_index(id, name.str_id()) else {
+            self.error(Error::NoVariant(name.span(), self.item_name(id), name.str_id()));
+            return Sym::Opaque;
+        };
+
+        let enum_name = self.item_name(id);
+        let active = Formula::cmp(&tag, CmpOp::Eq, &Linear::constant(index as i128));
+        self.obligate(active, |_, counterexample| {
+            Error::InactiveVariant(name.span(), name.str_id(), enum_name, counterexample)
+        });
+
+        payloads.swap_remove(index)
+    }
+
+    /// Reports a runtime test of the variant of an untagged enum, which has no tag to test.
+    /// Its variant can only be known from constraints.
+    pub(crate) fn check_tagged(&mut self, id: ItemId, span: Span) {
+        if self.namespace.item(id).is_untagged() {
+            self.error(Error::UntaggedTest(span, self.item_name(id)));
+        }
+    }
+
+    /// The bound of a variant applied to its payload.
+    fn variant_bound(&mut self, variant: &'ctx Param, payload: &Sym) -> Option<Formula> {
+        let bounds = variant.bounds.as_ref().and_then(|bounds| bounds.expr.tree())?;
+
+        let outer_scopes = replace(&mut self.scopes, vec![HashMap::new()]);
+        let formula = self.bound_formula(payload, &bounds);
+        self.scopes = outer_scopes;
+
+        formula
+    }
+}
+
+/// The variant `is` tests for, written as `Variant` or `Enum.Variant`.
+fn variant_name(tree: &ExprTree) -> Option<Ident> {
+    let unary = tree.as_unary().filter(|unary| unary.prefixes.is_empty())?;
+
+    match (unary.core, unary.exts.as_slice()) {
+        (ExprCore::Ident(ident), []) => Some(*ident),
+        (
+            ExprCore::Ident(_),
+            [
+                UnaryExprExt::Member(UnaryExprMemberExt {
+                    member: Try::Success(Member::Named(ident)),
+                    ..
+                }),
+            ],
+        ) => Some(*ident),
+        _ => None,
+    }
+}
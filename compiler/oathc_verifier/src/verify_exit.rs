@@ -0,0 +1,173 @@
+use super::*;
+
+/// A loop or a labeled block being verified, which `break`s leave.
+#[derive(Debug)]
+pub(crate) struct ExitContext<'ctx> {
+    pub label: Option<StrId>,
+    /// Unlabeled `break`s and `continue`s are for the innermost loop, and blocks can't be continued.
+    pub is_loop: bool,
+    /// The type of the first value the loop or block evaluates to, and where that value is.
+    pub value: Option<(Ty, Span)>,
+    /// Whether a reachable `break` leaves it.
+    pub broke: bool,
+    /// The number of scopes around the block.
+    pub depth: usize,
+    pub facts_len: usize,
+    pub trace_len: usize,
+    /// Where the block is left through `break`, with the values it's left with.
+    /// Loops start over from an unknown state instead, so they don't keep these.
+    pub exits: Vec<Branch<'ctx>>,
+    /// The invariants and measures of a loop, which `continue`s check.
+    pub contract: Option<LoopContract<'ctx>>,
+}
+
+impl<'ctx, 'c> Verifier<'ctx, 'c> {
+    pub(crate) fn push_exit_context(&mut self, label: Option<StrId>, is_loop: bool, value: Option<(Ty, Span)>) {
+        self.exit_contexts.push(ExitContext {
+            label,
+            is_loop,
+            value,
+            broke: false,
+            depth: self.scopes.len(),
+            facts_len: self.facts.len(),
+            trace_len: self.trace.len(),
+            exits: Vec::new(),
+            contract: None,
+        });
+    }
+
+    /// Verifies a labeled block, whose value is either the value it evaluates to or one of its `break`s leaves it with.
+    pub(crate) fn verify_labeled_block(&mut self, block: &'ctx Block, label: StrId) -> Sym {
+        let scopes = self.scopes.clone();
+        self.push_exit_context(Some(label), false, None);
+        let (mut value, span) = self.verify_block(block);
+        if self.reachable {
+            self.check_exit_value(self.exit_contexts.len() - 1, &value, span);
+        }
+        let context = self.exit_contexts.pop().unwrap();
+
+        for exit in context.exits {
+            let end = self.take_branch(context.facts_len, context.trace_len, scopes.clone(), value);
+            value = self.join(end, exit, block.span());
+        }
+
+        value
+    }
+
+    pub(crate) fn verify_break(&mut self, stmt: &'ctx BreakStmt) {
+        let (value, span) = match &stmt.value {
+            Some(value) => (self.sym_expr(value), value.option_span().unwrap_or(stmt.keyword.span())),
+            None => (Sym::Tuple(Vec::new()), stmt.keyword.span()),
+        };
+
+        if let Some(index) = self.exit_target(stmt.label.as_ref(), stmt.keyword.span(), "break") {
+            self.check_exit_value(index, &value, span);
+
+            let context = &self.exit_contexts[index];
+            match context.is_loop {
+                true => self.exit_contexts[index].broke = true,
+                false => {
+                    let exit = self.branch_here(context.depth, context.facts_len, context.trace_len, value);
+                    self.exit_contexts[index].exits.push(exit);
+                }
+            }
+        }
+
+        self.reachable = false;
+    }
+
+    pub(crate) fn verify_continue(&mut self, stmt: &'ctx ContinueStmt) {
+        if let Some(index) = self.exit_target(stmt.label.as_ref(), stmt.keyword.span(), "continue") {
+            match self.exit_contexts[index].is_loop {
+                true => self.continue_loop(index, stmt.keyword.span()),
+                false => self.error(Error::Misplaced(stmt.keyword.span(), "continue", "a loop")),
+            }
+        }
+
+        self.reachable = false;
+    }
+
+    /// The loop or block a `break` or a `continue` leaves, which is the innermost loop without a label.
+    fn exit_target(&mut self, label: Option<&Lifetime>, span: Span, keyword: &'static str) -> Option<usize> {
+        let Some(label) = label else {
+            let index = self.exit_contexts.iter().rposition(|context| context.is_loop);
+            if index.is_none() {
+                self.error(Error::Misplaced(span, keyword, "a loop"));
+            }
+
+            return index;
+        };
+        let name = label.str_id()?;
+
+        let index = self.exit_contexts.iter().rposition(|context| context.label == Some(name));
+        if index.is_none() {
+            self.error(Error::UndeclaredLabel(label.span(), name));
+        }
+
+        index
+    }
+
+    /// Checks that a value a loop or block evaluates to has the type of the other values it evaluates to.
+    pub(crate) fn check_exit_value(&mut self, index: usize, value: &Sym, span: Span) {
+        let ty = ty_of_sym(value);
+        let context = &self.exit_contexts[index];
+        let kind = if context.is_loop { "loop" } else { "block" };
+
+        let Some((expected, first)) = &context.value else {
+            if ty != Ty::Opaque {
+                self.exit_contexts[index].value = Some((ty, span));
+            }
+            return;
+        };
+        if ty_agrees(expected, &ty) {
+            return;
+        }
+
+        let expected = self.ty_name(expected);
+        let labels = vec![Label {
+            span: *first,
+            message: format!("the {kind} evaluates to `{expected}` here"),
+        }];
+        self.error(Error::ExitMismatch(span, self.ty_name(&ty), kind, expected, labels));
+    }
+
+    /// How a type is written in diagnostics.
+    fn ty_name(&self, ty: &Ty) -> String {
+        match ty {
+            Ty::Int(Some(int_type)) => format!("{int_type:?}").to_lowercase(),
+            Ty::Int(None) => "{integer}".to_string(),
+            Ty::Bool => "bool".to_string(),
+            Ty::Tuple(items) => format!(
+                "({})",
+                items.iter().map(|item| self.ty_name(item)).collect::<Vec<_>>().join(", ")
+            ),
+            Ty::Struct(id, _) | Ty::Enum(id) => self.item_name(*id),
+            Ty::Array(ty, len) => match len.as_ref().and_then(Linear::as_constant) {
+                Some(len) => format!("[{}; {len}]", self.ty_name(ty)),
+                None => format!("[{}; _]", self.ty_name(ty)),
+            },
+            Ty::Opaque => "_".to_string(),
+        }
+    }
+}
+
+/// Whether two types can be the same, where the parts the verifier doesn't know can be anything.
+fn ty_agrees(lhs: &Ty, rhs: &Ty) -> bool {
+    match (lhs, rhs) {
+        (Ty::Opaque, _) | (_, Ty::Opaque) => true,
+        (Ty::Int(lhs), Ty::Int(rhs)) => lhs.is_none() || rhs.is_none() || lhs == rhs,
+        (Ty::Tuple(lhs), Ty::Tuple(rhs)) => lhs.len() == rhs.len() && lhs.iter().zip(rhs).all(|(lhs, rhs)| ty_agrees(lhs, rhs)),
+        (Ty::Array(lhs, lhs_len), Ty::Array(rhs, rhs_len)) => {
+            let lens_agree = match (
+                lhs_len.as_ref().and_then(Linear::as_constant),
+                rhs_len.as_ref().and_then(Linear::as_constant),
+            ) {
+                (Some(lhs_len), Some(rhs_len)) => lhs_len == rhs_len,
+                _ => true,
+            };
+
+            lens_agree && ty_agrees(lhs, rhs)
+        }
+        _ => lhs == rhs,
+    }
+}
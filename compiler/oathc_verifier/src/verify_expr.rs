@@ -0,0 +1,420 @@
+use super::*;
+
+impl<'ctx, 'c> Verifier<'ctx, 'c> {
+    pub(crate) fn sym_tree(&mut self, tree: &ExprTree<'ctx>) -> Sym {
+        match tree {
+            ExprTree::Unary(unary) => self.sym_unary(unary),
+            ExprTree::BinOp(lhs, BinOp::Is(_), rhs) => {
+                let lhs = self.sym_tree(lhs);
+
+                self.is_variant(lhs, rhs, tree.span())
+            }
+            ExprTree::BinOp(lhs, op, rhs) => {
+                let lhs = self.sym_tree(lhs);
+                let rhs = self.sym_tree(rhs);
+                let in_range = self.check_arithmetic(&lhs, *op, &rhs, tree);
+
+                match self.sym_bin_op(lhs, *op, rhs, tree.span()) {
+                    // The result of arithmetic that always overflows is only known to be of its type.
+                    Sym::Int(value) if !in_range => {
+                        let int_type = self.int_type_of(&value).or(self.width);
+                        self.fresh(&Ty::Int(int_type), None, tree.span())
+                    }
+                    value => value,
+                }
+            }
+        }
+    }
+
+    /// Evaluates an expression that may have failed to parse.
+    pub(crate) fn sym_expr(&mut self, expr: &'ctx impl ToExprTree) -> Sym {
+        match expr.tree() {
+            Some(tree) => self.sym_tree(&tree),
+            None => Sym::Opaque,
+        }
+    }
+
+    /// Evaluates an expression that's bound to a value of the type, whose width arithmetic on literals alone has.
+    pub(crate) fn sym_expr_as(&mut self, expr: &'ctx impl ToExprTree, ty: &Ty) -> Sym {
+        let width = match ty {
+            Ty::Int(int_type) => *int_type,
+            _ => None,
+        };
+        let outer_width = replace(&mut self.width, width);
+        let value = self.sym_expr(expr);
+        self.width = outer_width;
+
+        value
+    }
+
+    fn sym_unary(&mut self, unary: &UnaryExprRef<'ctx>) -> Sym {
+        // Only the parts of the expression that evaluate to its value are bound to what it's bound to.
+        let width = match (unary.core, unary.exts.is_empty()) {
+            (ExprCore::Tuple(_) | ExprCore::Block(_) | ExprCore::If(_) | ExprCore::Match(_), true) => self.width,
+            _ => None,
+        };
+        let outer_width = replace(&mut self.width, width);
+        let value = self.sym_unary_parts(unary);
+        self.width = outer_width;
+
+        value
+    }
+
+    fn sym_unary_parts(&mut self, unary: &UnaryExprRef<'ctx>) -> Sym {
+        let mut span = unary.core.span();
+        let mut value = self.sym_core(unary.core, unary.label, unary.attrs);
+
+        for ext in &unary.exts {
+            span = span.connect(ext.span());
+            if let UnaryExprExt::Call(tuple) = ext {
+                span = span.connect(tuple.items.last().and_then(OptionSpanned::option_span));
+            }
+            value = self.sym_ext(value, ext, span);
+        }
+
+        for prefix in unary.prefixes.iter().rev() {
+            span = prefix.span().connect(span);
+            value = self.sym_prefix(prefix, value, span);
+        }
+        if let Some(borrow) = mut_borrow(unary) {
+            self.borrow_mut(borrow, span);
+        }
+
+        value
+    }
+
+    /// Evaluates the core of a unary expression, which is labeled `label` if it's a loop or a block,
+    /// and has the attributes if it's a loop.
+    fn sym_core(&mut self, core: &'ctx ExprCore, label: Option<&Lifetime>, attrs: &'ctx [Attr]) -> Sym {
+        if let Some(label) = label
+            && !matches!(
+                core,
+                ExprCore::Block(_) | ExprCore::Loop(_) | ExprCore::While(_) | ExprCore::Until(_) | ExprCore::For(_)
+            )
+        {
+            self.error(Error::MisplacedLabel(label.span()));
+        }
+        let label = label.and_then(Lifetime::str_id);
+
+        match core {
+            ExprCore::Ident(ident) => self.sym_ident(*ident),
+            ExprCore::Keyword(ExprKeyword::Out(_)) => self.out.clone().unwrap_or(Sym::Opaque),
+            ExprCore::Keyword(ExprKeyword::Fn(fn_)) if fn_.body.is_some() => self.verify_closure(fn_),
+            ExprCore::Keyword(_) => Sym::Opaque,
+            ExprCore::Literal(Literal::Int(literal)) => match literal.value {
+                Try::Success(value) => i128::try_from(value).map_or(Sym::Opaque, |value| Sym::Int(Linear::constant(value))),
+                Try::Failure(_) => Sym::Opaque,
+            },
+            ExprCore::Literal(_) => Sym::Opaque,
+            ExprCore::Block(block) => match label {
+                Some(label) => self.verify_labeled_block(block, label),
+                None => self.verify_block(block).0,
+            },
+            ExprCore::Tuple(tuple) => {
+                let mut values = tuple.items.iter().map(|item| self.sym_expr(item)).collect::<Vec<_>>();
+
+                if values.len() == 1 {
+                    values.pop().unwrap()
+                } else {
+                    Sym::Tuple(values)
+                }
+            }
+            ExprCore::Array(array) => {
+                let mut items = array.items.iter().map(|item| self.sym_expr(item)).collect::<Vec<_>>();
+                let Some(len) = &array.len else {
+                    return Sym::Array(items);
+                };
+
+                match (self.sym_expr(&len.len), items.len()) {
+                    (Sym::Int(len), 1) => self.array_of(ty_of_sym(&items.pop().unwrap()), len),
+                    _ => Sym::Opaque,
+                }
+            }
+            ExprCore::If(if_) => self.verify_if(if_),
+            ExprCore::Loop(loop_) => self.verify_loop(loop_, label, attrs),
+            ExprCore::While(while_) => self.verify_while(while_, label, attrs),
+            ExprCore::Until(until) => self.verify_until(until, label, attrs),
+            ExprCore::For(for_) => self.verify_for(for_, label, attrs),
+            ExprCore::Quantifier(quantifier) => self.verify_quantifier(quantifier),
+            ExprCore::Match(match_) => self.verify_match(match_),
+            ExprCore::Try(try_) => self.verify_try(try_),
+        }
+    }
+
+    fn sym_ident(&mut self, ident: Ident) -> Sym {
+        let name = ident.str_id();
+
+        if let Some(local) = self.scopes.iter().rev().find_map(|scope| scope.get(&name)) {
+            return local.value.clone();
+        }
+
+        match self.interner.unintern(name).as_str() {
+            "true" => return Sym::Bool(Formula::TRUE),
+            "false" => return Sym::Bool(Formula::FALSE),
+            "bool" => return Sym::Type(Ty::Bool),
+            name => {
+                if let Some(int_type) = IntType::from_name(name) {
+                    return Sym::Type(Ty::Int(Some(int_type)));
+                }
+            }
+        }
+
+        let Some(id) = self.namespace.get(name) else {
+            return Sym::Opaque;
+        };
+        let item = self.namespace.item(id);
+
+        match &item.core {
+            Try::Success(ItemCore::Fn(_)) => Sym::Fn(id, Vec::new()),
+            Try::Success(ItemCore::Struct(_) | ItemCore::Enum(_)) => match self.struct_ty(name) {
+                Ty::Opaque => Sym::Opaque,
+                ty => Sym::Type(ty),
+            },
+            Try::Success(ItemCore::Static(_)) if item.is_comptime() => self.static_value(id, ident.span),
+            Try::Success(ItemCore::Static(static_)) => {
+                let Try::Success(param) = &static_.param else {
+                    return Sym::Opaque;
+                };
+
+                let ty = self.param_type(param);
+                let value = self.fresh(&ty, Some(name), ident.span);
+
+                // The bounds of a static are an invariant, so they hold whenever it's read.
+                if let Some(bounds) = param.bounds.as_ref().and_then(|bounds| bounds.expr.tree()) {
+                    let outer_scopes = replace(&mut self.scopes, vec![HashMap::new()]);
+                    let formula = self.bound_formula(&value, &bounds);
+                    self.scopes = outer_scopes;
+
+                    self.facts.extend(formula);
+                }
+
+                value
+            }
+            _ => Sym::Opaque,
+        }
+    }
+
+    pub(crate) fn static_value(&mut self, id: ItemId, span: Span) -> Sym {
+        match self.comptime.static_value(id, span) {
+            Try::Success(value) => sym_of_value(&value),
+            Try::Failure(error) => {
+                self.errors.extend(error);
+                Sym::Opaque
+            }
+        }
+    }
+
+    fn sym_ext(&mut self, value: Sym, ext: &'ctx UnaryExprExt, span: Span) -> Sym {
+        match ext {
+            UnaryExprExt::Member(member) => match (value, &member.member) {
+                (Sym::Tuple(mut values), Try::Success(Member::Unnamed(index))) => match index.value {
+                    Try::Success(index) if (index as usize) < values.len() => values.swap_remove(index as usize),
+                    _ => Sym::Opaque,
+                },
+                (Sym::Struct(id, generics, fields), Try::Success(Member::Named(ident))) => {
+                    match self.field_index(id, ident.str_id()) {
+                        Some(index) => {
+                            self.assume_invariants(id, &generics, &fields);
+                            fields[index].clone()
+                        }
+                        None => Sym::Opaque,
+                    }
+                }
+                (Sym::Type(Ty::Struct(id, _) | Ty::Enum(id)), Try::Success(Member::Named(ident)))
+                    if let Some(impl_) = self.impl_of(id, ident.str_id()) =>
+                {
+                    Sym::ImplFn(impl_, ident.str_id())
+                }
+                (Sym::Type(Ty::Enum(id)), Try::Success(Member::Named(ident))) => self.variant(id, ident.str_id()),
+                (Sym::Enum(id, tag, payloads), Try::Success(Member::Named(ident))) => {
+                    self.variant_payload(id, tag, payloads, *ident)
+                }
+                (Sym::Impl(id, generics), Try::Success(Member::Named(ident))) => Sym::TraitFn(id, generics, ident.str_id()),
+                _ => Sym::Opaque,
+            },
+            UnaryExprExt::Call(tuple) => {
+                let args = tuple
+                    .items
+                    .iter()
+                    .map(|item| {
+                        let span = item.option_span().unwrap_or(tuple.span());
+                        (self.sym_expr(item), span)
+                    })
+                    .collect::<Vec<_>>();
+
+                // An argument the verifier knows nothing about may be a reference the call writes through.
+                let through_refs = args.iter().any(|(arg, _)| *arg == Sym::Opaque);
+                let output = match value {
+                    // Called with one argument less than it has, a function is a predicate over the missing first one.
+                    Sym::Fn(item, generics) if self.predicate_arity(item) == Some(args.len() + 1) => {
+                        return Sym::Bound(SymBound::Pred(item, generics, args, span));
+                    }
+                    Sym::Variant(id, index) => return self.construct_variant(id, index, args, span),
+                    Sym::Fn(item, generics) => self.call(item, generics, args, span),
+                    Sym::Closure(closure) => self.call_closure(closure, args, span),
+                    Sym::TraitFn(id, generics, member) => self.call_trait_fn(id, generics, member, args, span),
+                    Sym::ImplFn(impl_, member) => match self.impl_fn(impl_, member) {
+                        Some(fn_) => self.call_fn(fn_, None, Vec::new(), args, span),
+                        None => Sym::Opaque,
+                    },
+                    _ => Sym::Opaque,
+                };
+                if through_refs {
+                    self.forget_borrowed(span);
+                }
+
+                output
+            }
+            UnaryExprExt::Index(index) => {
+                let mut items = index.items.iter().map(|item| self.sym_expr(item)).collect::<Vec<_>>();
+
+                match (value, items.pop()) {
+                    (value @ (Sym::Array(_) | Sym::ArrayOf(..)), Some(Sym::Int(item))) if items.is_empty() => {
+                        self.index(value, item, index)
+                    }
+                    _ => Sym::Opaque,
+                }
+            }
+            UnaryExprExt::Generics(generics) => {
+                let args = generics.args.iter().map(|arg| self.sym_expr(arg)).collect();
+
+                match value {
+                    Sym::Fn(item, _) => Sym::Fn(item, args),
+                    Sym::Type(Ty::Struct(id, _)) => Sym::Type(Ty::Struct(id, args)),
+                    _ => Sym::Opaque,
+                }
+            }
+            UnaryExprExt::Construct(construct) => {
+                if let Sym::Type(Ty::Struct(id, generics)) = value {
+                    return self.construct(id, generics, construct, span);
+                }
+
+                for field in &construct.items {
+                    if let Try::Success(set) = &field.set {
+                        self.sym_expr(&set.value);
+                    }
+                }
+
+                Sym::Opaque
+            }
+            UnaryExprExt::Question(_) => self.propagate(value, span),
+        }
+    }
+
+    fn sym_prefix(&mut self, prefix: &UnOp, value: Sym, span: Span) -> Sym {
+        if let Some(op) = CmpOp::from_un_op(prefix) {
+            return Sym::Bound(SymBound::Cmp(op, Box::new(value)));
+        }
+
+        match (prefix, value) {
+            (UnOp::Not(_), Sym::Bool(formula)) => Sym::Bool(formula.not()),
+            (UnOp::Not(_), Sym::Bound(bound)) => Sym::Bound(SymBound::Not(Box::new(bound))),
+            (UnOp::Neg(_), Sym::Int(value)) => match value.scale(-1) {
+                Some(value) => Sym::Int(value),
+                None => self.fresh(&Ty::Int(None), None, span),
+            },
+            (UnOp::Neg(_), _) => self.fresh(&Ty::Int(None), None, span),
+            _ => Sym::Opaque,
+        }
+    }
+
+    fn sym_bin_op(&mut self, lhs: Sym, op: BinOp, rhs: Sym, span: Span) -> Sym {
+        match (lhs, op, rhs) {
+            (lhs, BinOp::Bound(_), Sym::Bound(bound)) => self.apply_bound(&bound, &lhs).map_or(Sym::Opaque, Sym::Bool),
+            (_, BinOp::Bound(_), Sym::Bool(formula)) => Sym::Bool(formula),
+            (_, BinOp::Bound(_), _) => Sym::Opaque,
+
+            (Sym::Bool(lhs), BinOp::And(_), Sym::Bool(rhs)) => Sym::Bool(Formula::and([lhs, rhs])),
+            (Sym::Bool(lhs), BinOp::Or(_), Sym::Bool(rhs)) => Sym::Bool(Formula::or([lhs, rhs])),
+            (Sym::Bool(lhs), BinOp::Xor(_), Sym::Bool(rhs)) => Sym::Bool(Formula::xor(lhs, rhs)),
+
+            (Sym::Bound(lhs), BinOp::And(_), Sym::Bound(rhs)) => Sym::Bound(SymBound::And(Box::new(lhs), Box::new(rhs))),
+            (Sym::Bound(lhs), BinOp::Or(_), Sym::Bound(rhs)) => Sym::Bound(SymBound::Or(Box::new(lhs), Box::new(rhs))),
+
+            (Sym::Int(start), BinOp::RangeExclusive(_), Sym::Int(end)) => Sym::Range(start, end),
+            (Sym::Int(start), BinOp::RangeInclusive(_), Sym::Int(end)) => match end.add(&Linear::constant(1)) {
+                Some(end) => Sym::Range(start, end),
+                None => Sym::Opaque,
+            },
+
+            (Sym::Int(lhs), op, Sym::Int(rhs)) => {
+                let int_type = self.int_type_of(&lhs).or_else(|| self.int_type_of(&rhs));
+                let value = match op {
+                    BinOp::Add(_) | BinOp::WrappingAdd(_) => lhs.add(&rhs),
+                    BinOp::Sub(_) | BinOp::WrappingSub(_) => lhs.sub(&rhs),
+                    BinOp::Mul(_) | BinOp::WrappingMul(_) => match (lhs.as_constant(), rhs.as_constant()) {
+                        (Some(lhs_value), _) => rhs.scale(lhs_value),
+                        (_, Some(rhs_value)) => lhs.scale(rhs_value),
+                        _ => None,
+                    },
+                    _ => match (lhs.as_constant(), rhs.as_constant()) {
+                        (Some(lhs), Some(rhs)) => const_int_op(lhs, op, rhs).map(Linear::constant),
+                        _ => None,
+                    },
+                };
+                // Wrapping arithmetic only keeps its value where it's proven not to wrap.
+                let value = match (op, int_type) {
+                    (BinOp::WrappingAdd(_) | BinOp::WrappingSub(_) | BinOp::WrappingMul(_), Some(int_type)) => {
+                        value.filter(|value| self.proven_in_range(value, int_type))
+                    }
+                    _ => value,
+                };
+
+                match value {
+                    Some(value) => Sym::Int(value),
+                    None => self.fresh(&Ty::Int(int_type), None, span),
+                }
+            }
+            (
+                Sym::Int(_) | Sym::Opaque,
+                BinOp::Add(_)
+                | BinOp::Sub(_)
+                | BinOp::Mul(_)
+                | BinOp::Div(_)
+                | BinOp::Rem(_)
+                | BinOp::WrappingAdd(_)
+                | BinOp::WrappingSub(_)
+                | BinOp::WrappingMul(_),
+                _,
+            ) => self.fresh(&Ty::Int(None), None, span),
+
+            _ => Sym::Opaque,
+        }
+    }
+}
+
+fn const_int_op(lhs: i128, op: BinOp, rhs: i128) -> Option<i128> {
+    match op {
+        BinOp::Div(_) => lhs.checked_div(rhs),
+        BinOp::Rem(_) => lhs.checked_rem(rhs),
+        BinOp::And(_) => Some(lhs & rhs),
+        BinOp::Or(_) => Some(lhs | rhs),
+        BinOp::Xor(_) => Some(lhs ^ rhs),
+        BinOp::Shl(_) => lhs.checked_shl(u32::try_from(rhs).ok()?),
+        BinOp::Shr(_) => lhs.checked_shr(u32::try_from(rhs).ok()?),
+        _ => None,
+    }
+}
+
+pub(crate) fn sym_of_value(value: &Value) -> Sym {
+    match value {
+        Value::Int(value) => Sym::Int(Linear::constant(*value)),
+        Value::Bool(value) => Sym::Bool(Formula::Bool(*value)),
+        Value::Tuple(values) => Sym::Tuple(values.iter().map(sym_of_value).collect()),
+        Value::Type(type_) => Sym::Type(ty_of_type(type_)),
+        _ => Sym::Opaque,
+    }
+}
+
+fn ty_of_type(type_: &Type) -> Ty {
+    match type_ {
+        Type::Int(int_type) => Ty::Int(Some(*int_type)),
+        Type::Bool => Ty::Bool,
+        Type::Tuple(types) => Ty::Tuple(types.iter().map(ty_of_type).collect()),
+        Type::Array(item, len) => Ty::Array(Box::new(ty_of_type(item)), Some(Linear::constant(*len as i128))),
+        Type::Struct(instance) => Ty::Struct(instance.item, instance.args.iter().map(sym_of_value).collect()),
+        Type::Enum(instance) if instance.args.is_empty() => Ty::Enum(instance.item),
+        Type::Refined(base, _) => ty_of_type(base),
+        Type::Enum(_) | Type::Type | Type::Fn(..) => Ty::Opaque,
+    }
+}
@@ -0,0 +1,317 @@
+use super::*;
+
+impl<'ctx, 'c> Verifier<'ctx, 'c> {
+    /// Verifies the functions of an impl at the lint levels of the impl and their own,
+    /// and checks a trait impl against its trait's contract and the contracts of its members.
+    pub(crate) fn verify_impl(&mut self, id: ItemId, item: &'ctx Item, impl_: &'ctx Impl) {
+        let trait_ = self.namespace.impl_trait(impl_);
+        for inner in impl_.items() {
+            if let Try::Success(ItemCore::Fn(fn_)) = &inner.core {
+                self.lints = self.lints_of(id, &[item, inner]);
+                self.verify_fn(inner, fn_, Some(impl_));
+
+                let member = trait_
+                    .as_ref()
+                    .and_then(|(_, trait_, args)| Some((*trait_, trait_fn(trait_, fn_.ident.success_ref()?.str_id())?, args)));
+                if let Some((trait_, expected, args)) = member {
+                    self.check_member_contract(impl_, trait_, args, expected, fn_);
+                }
+            }
+        }
+
+        self.lints = self.lints_of(id, &[item]);
+        self.check_trait_contract(impl_);
+    }
+
+    /// Binds the generic parameters of an impl to fresh values, and assumes its bounds and `require`s.
+    pub(crate) fn bind_impl(&mut self, impl_: &'ctx Impl) {
+        for param in impl_
+            .generics
+            .iter()
+            .flat_map(|generics| &generics.items)
+            .filter(|param| !param.is_lifetime())
+        {
+            self.bind_fresh_param(param);
+        }
+
+        let generics_contract = impl_.generics.as_ref().map(|generics| &generics.contract);
+        for require in requires(
+            &[generics_contract, Some(&impl_.contract)]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>(),
+        ) {
+            if let Some(formula) = self.sym_contract(&require).as_formula() {
+                self.facts.push(formula);
+            }
+        }
+    }
+
+    /// Checks that the trait's generic arguments satisfy the trait's parameter bounds and `require`s,
+    /// for every value of the impl's own generic parameters.
+    fn check_trait_contract(&mut self, impl_: &'ctx Impl) {
+        let Some((_, trait_, args)) = self.namespace.impl_trait(impl_) else {
+            return;
+        };
+        let (Try::Success(name), Some(span)) = (&trait_.ident, impl_.first.option_span()) else {
+            return;
+        };
+
+        self.reset();
+        self.bind_impl(impl_);
+
+        let args = args.iter().map(|arg| (self.sym_tree(arg), arg.span())).collect::<Vec<_>>();
+        let outer_scopes = replace(&mut self.scopes, vec![HashMap::new()]);
+
+        let mut requirements = self.bind_generics(trait_.generics.as_ref().map_or(&[], |generics| &generics.items), args, false);
+
+        let generics_contract = trait_.generics.as_ref().map(|generics| &generics.contract);
+        for require in requires(
+            &[generics_contract, Some(&trait_.contract)]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>(),
+        ) {
+            let formula = self.sym_contract(&require).as_formula();
+            requirements.push((formula, span, require.to_string_interned(self.interner)));
+        }
+
+        self.scopes = outer_scopes;
+
+        for (formula, span, text) in requirements {
+            self.obligate(formula, |_, counterexample| {
+                Error::TraitRequireViolated(span, name.str_id(), text, counterexample)
+            });
+        }
+    }
+
+    /// Checks that a member of a trait impl can be used wherever the trait's member can:
+    /// it may only require what the trait's member requires, and has to promise at least what it promises.
+    ///
+    /// The parameters are fresh values that satisfy the trait's requirements, and the output is a fresh value that
+    /// satisfies the impl's promises, for every value of the impl's generic parameters.
+    fn check_member_contract(
+        &mut self,
+        impl_: &'ctx Impl,
+        trait_: &'ctx Trait,
+        args: &[ExprTree<'ctx>],
+        expected: &'ctx Fn,
+        found: &'ctx Fn,
+    ) {
+        let (Try::Success(name), Try::Success(trait_name)) = (&found.ident, &trait_.ident) else {
+            return;
+        };
+        let (Try::Success(expected_input), Try::Success(found_input)) = (&expected.input, &found.input) else {
+            return;
+        };
+        // Members with their own generic parameters, or that don't match the trait's signature, aren't followed.
+        if expected_input.items.len() != found_input.items.len() || expected.generics.is_some() || found.generics.is_some() {
+            return;
+        }
+
+        self.reset();
+        self.bind_impl(impl_);
+
+        let args = args.iter().map(|arg| (self.sym_tree(arg), arg.span())).collect::<Vec<_>>();
+        let mut values = Vec::new();
+        for param in &found_input.items {
+            let ty = self.param_type(param);
+            let ident = param.body.success_ref().and_then(ParamBody::as_ident);
+            let span = param.option_span().unwrap_or(name.span());
+            values.push((self.fresh(&ty, ident.map(|ident| ident.str_id()), span), ty));
+        }
+
+        // The trait's member, with the trait's generic parameters standing for the impl's arguments.
+        let impl_scopes = replace(&mut self.scopes, vec![HashMap::new()]);
+        self.bind_generics(trait_.generics.as_ref().map_or(&[], |generics| &generics.items), args, false);
+        for (param, (value, ty)) in expected_input.items.iter().zip(&values) {
+            self.bind_param(param, value.clone(), ty.clone());
+            if let Some(bounds) = param.bounds.as_ref().and_then(|bounds| bounds.expr.tree()) {
+                let formula = self.bound_formula(value, &bounds);
+                self.facts.extend(formula);
+            }
+        }
+        for require in requires(&[&expected_input.contract, &expected.contract]) {
+            if let Some(formula) = self.sym_contract(&require).as_formula() {
+                self.facts.push(formula);
+            }
+        }
+        let trait_scopes = replace(&mut self.scopes, impl_scopes);
+
+        self.scopes.push(HashMap::new());
+        for (param, (value, ty)) in found_input.items.iter().zip(&values) {
+            self.bind_param(param, value.clone(), ty.clone());
+        }
+        for (param, (value, _)) in found_input.items.iter().zip(&values) {
+            let Some(bounds) = param.bounds.as_ref().and_then(|bounds| bounds.expr.tree()) else {
+                continue;
+            };
+            let span = bounds.span();
+            let text = format!(
+                "{}: {}",
+                param_name(param, self.interner),
+                bounds.to_string_interned(self.interner)
+            );
+            let formula = self.bound_formula(value, &bounds);
+            self.obligate(formula, |_, counterexample| {
+                Error::ImplRequireStronger(span, name.str_id(), text, trait_name.str_id(), counterexample)
+            });
+        }
+        for require in requires(&[&found_input.contract, &found.contract]) {
+            let formula = self.sym_contract(&require).as_formula();
+            let text = require.to_string_interned(self.interner);
+            self.obligate(formula, |_, counterexample| {
+                Error::ImplRequireStronger(require.span(), name.str_id(), text, trait_name.str_id(), counterexample)
+            });
+        }
+
+        // What the impl's member promises about its output, which has to give what the trait's member promises.
+        let (output, bounds) = match found.output.as_ref().and_then(ToExprTree::tree) {
+            Some(tree) => {
+                let (type_, bounds) = split_bounds(tree);
+                let ty = self.read_type(&type_);
+                (self.fresh(&ty, None, name.span()), bounds)
+            }
+            None => (Sym::Tuple(Vec::new()), Vec::new()),
+        };
+        self.out = Some(output.clone());
+        for bound in &bounds {
+            if let Some(formula) = self.bound_formula(&output, bound) {
+                self.facts.push(formula);
+            }
+        }
+        for promise in promises(&[&found_input.contract, &found.contract]) {
+            if let Some(formula) = self.sym_contract(&promise).as_formula() {
+                self.facts.push(formula);
+            }
+        }
+
+        self.scopes = trait_scopes;
+        let bounds = match expected.output.as_ref().and_then(ToExprTree::tree) {
+            Some(tree) => split_bounds(tree).1,
+            None => Vec::new(),
+        };
+        for bound in &bounds {
+            let text = format!("out: {}", bound.to_string_interned(self.interner));
+            self.check_bound(&output, bound, |_, _, counterexample| {
+                Error::ImplPromiseWeaker(name.span(), name.str_id(), text, trait_name.str_id(), counterexample)
+            });
+        }
+        for promise in promises(&[&expected_input.contract, &expected.contract]) {
+            let formula = self.sym_contract(&promise).as_formula();
+            let text = promise.to_string_interned(self.interner);
+            self.obligate(formula, |_, counterexample| {
+                Error::ImplPromiseWeaker(name.span(), name.str_id(), text, trait_name.str_id(), counterexample)
+            });
+        }
+    }
+
+    /// Checks a call to a member of a trait through a type that's only known to implement the trait,
+    /// like `S.area(shape)` for `S type: Shape`, against the contract of the trait's member,
+    /// which every impl of the trait keeps.
+    pub(crate) fn call_trait_fn(
+        &mut self,
+        trait_id: ItemId,
+        generics: Vec<Sym>,
+        member: StrId,
+        args: Vec<(Sym, Span)>,
+        span: Span,
+    ) -> Sym {
+        let Try::Success(ItemCore::Trait(trait_)) = &self.namespace.item(trait_id).core else {
+            return Sym::Opaque;
+        };
+        let Some(fn_) = trait_fn(trait_, member) else {
+            return Sym::Opaque;
+        };
+        let Try::Success(input) = &fn_.input else {
+            return Sym::Opaque;
+        };
+        if self.contract_depth >= MAX_CONTRACT_DEPTH {
+            return Sym::Opaque;
+        }
+
+        let outer_scopes = replace(&mut self.scopes, vec![HashMap::new()]);
+        let outer_out = self.out.take();
+        self.contract_depth += 1;
+
+        let generics = generics.into_iter().map(|generic| (generic, span));
+        self.bind_generics(
+            trait_.generics.as_ref().map_or(&[], |generics| &generics.items),
+            generics,
+            false,
+        );
+
+        let (output, requirements, promises) =
+            self.callee_contract(input, &fn_.contract, fn_.output.as_ref(), Some(member), args, span);
+        let promised = self.promised(input, &fn_.contract, fn_.output.as_ref());
+
+        self.scopes = outer_scopes;
+        self.out = outer_out;
+        self.contract_depth -= 1;
+
+        self.require_call(member, requirements);
+        self.promise_call(member, promises, &promised, "promises", &output, span);
+
+        output
+    }
+
+    /// The impl of the type that isn't generic and has a function with the name, like the impl of `Square` with `area`.
+    pub(crate) fn impl_of(&self, type_: ItemId, name: StrId) -> Option<ItemId> {
+        self.namespace.items().find_map(|(id, item)| {
+            let Try::Success(ItemCore::Impl(impl_)) = &item.core else {
+                return None;
+            };
+            let target = impl_.target().tree()?;
+            let ident = target.as_unary()?.as_ident()?;
+            let is_target = impl_.generics.is_none() && self.namespace.get(ident.str_id()) == Some(type_);
+
+            (is_target && impl_fn_of(impl_, name).is_some()).then_some(id)
+        })
+    }
+
+    /// The function with the name of an impl `impl_of` found.
+    pub(crate) fn impl_fn(&self, impl_: ItemId, name: StrId) -> Option<&'ctx Fn> {
+        match &self.namespace.item(impl_).core {
+            Try::Success(ItemCore::Impl(impl_)) => impl_fn_of(impl_, name),
+            _ => None,
+        }
+    }
+
+    /// The trait a bound like `Shape` in `S type: Shape` says a type implements, as the type.
+    pub(crate) fn implemented_trait(&mut self, bounds: &ExprTree<'ctx>) -> Option<Sym> {
+        let unary = bounds.as_unary()?;
+        let ExprCore::Ident(ident) = unary.core else {
+            return None;
+        };
+        let id = self.namespace.get(ident.str_id())?;
+        if !matches!(self.namespace.item(id).core, Try::Success(ItemCore::Trait(_))) || !unary.prefixes.is_empty() {
+            return None;
+        }
+
+        let args = match unary.exts.as_slice() {
+            [] => Vec::new(),
+            [UnaryExprExt::Generics(generics)] => generics.args.iter().map(|arg| self.sym_expr(arg)).collect(),
+            _ => return None,
+        };
+
+        Some(Sym::Impl(id, args))
+    }
+}
+
+/// The function of an impl with the name.
+fn impl_fn_of(impl_: &Impl, name: StrId) -> Option<&Fn> {
+    impl_.items().iter().find_map(|item| match &item.core {
+        Try::Success(ItemCore::Fn(fn_)) if fn_.ident.success_ref().map(|ident| ident.str_id()) == Some(name) => Some(fn_),
+        _ => None,
+    })
+}
+
+/// The function of a trait with the name.
+fn trait_fn(trait_: &Trait, name: StrId) -> Option<&Fn> {
+    trait_.items().iter().find_map(|item| match &item.core {
+        Try::Success(ItemCore::Fn(member)) if member.ident.success_ref().map(|ident| ident.str_id()) == Some(name) => {
+            Some(member)
+        }
+        _ => None,
+    })
+}
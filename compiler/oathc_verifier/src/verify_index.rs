@@ -0,0 +1,53 @@
+use super::*;
+
+impl<'ctx, 'c> Verifier<'ctx, 'c> {
+    /// Indexes an array, which requires the index to be below its length at the level of the `index` lint.
+    pub(crate) fn index(&mut self, array: Sym, index: Linear, brackets: &'ctx Array) -> Sym {
+        let item = brackets.items.first();
+        let span = item.and_then(OptionSpanned::option_span).unwrap_or(brackets.span());
+        let len = match &array {
+            Sym::Array(items) => Linear::constant(items.len() as i128),
+            Sym::ArrayOf(_, len, _) => len.clone(),
+            _ => return Sym::Opaque,
+        };
+
+        let in_bounds = Formula::cmp(&Linear::constant(0), CmpOp::LessEq, &index)
+            .zip(Formula::cmp(&index, CmpOp::Less, &len))
+            .map(|(start, end)| Formula::and([start, end]));
+        let text = match item.and_then(ToExprTree::tree) {
+            Some(tree) => tree.to_string_interned(self.interner),
+            None => "_".to_string(),
+        };
+        self.obligate_at(
+            self.lints.index,
+            in_bounds,
+            |_, counterexample| Error::IndexOutOfBounds(span, text, counterexample),
+            || Warning::UnverifiedIndex(span),
+        );
+
+        match array {
+            Sym::Array(mut items) => match index.as_constant() {
+                Some(index) if (0..items.len() as i128).contains(&index) => items.swap_remove(index as usize),
+                _ => {
+                    // One of the items, the one at the index.
+                    let ty = items.first().map_or(Ty::Opaque, ty_of_sym);
+                    let value = self.fresh(&ty, None, span);
+                    let fact = items
+                        .iter()
+                        .enumerate()
+                        .map(|(i, item)| {
+                            let at = Formula::cmp(&index, CmpOp::Eq, &Linear::constant(i as i128))?;
+                            Some(Formula::and([at, equate(&value, item)?]))
+                        })
+                        .collect::<Option<Vec<_>>>()
+                        .map(Formula::or);
+                    self.facts.extend(fact);
+
+                    value
+                }
+            },
+            Sym::ArrayOf(ty, _, array) => self.read(array, &ty, index, span),
+            _ => Sym::Opaque,
+        }
+    }
+}
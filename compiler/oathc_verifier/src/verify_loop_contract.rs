@@ -0,0 +1,203 @@
+use super::*;
+
+/// `#[decreases(..)]` measures, compared in order, with the values they have to decrease from.
+pub(crate) type Measures<'ctx> = Vec<(ExprTree<'ctx>, Sym)>;
+
+/// The `#[invariant(..)]`s and `#[decreases(..)]` of a loop, which every iteration that continues it is checked against.
+#[derive(Debug, Clone)]
+pub(crate) struct LoopContract<'ctx> {
+    pub invariants: Vec<ExprTree<'ctx>>,
+    /// The values are at the head of the iteration being verified.
+    pub measures: Measures<'ctx>,
+    /// The item of a `for` loop over a range with its value in the next iteration, which the contract is checked for.
+    pub next_item: Option<(&'ctx Param, Sym)>,
+}
+
+/// The `#[decreases(..)]` of the function being verified with their values on entry, which its calls to itself decrease.
+#[derive(Debug, Clone)]
+pub(crate) struct Recursion<'ctx> {
+    pub fn_: &'ctx Fn,
+    pub measures: Measures<'ctx>,
+}
+
+impl<'ctx, 'c> Verifier<'ctx, 'c> {
+    /// Evaluates an expression of a loop's contract, with the item of a `for` loop bound to the value if it's given.
+    pub(crate) fn sym_with_item(&mut self, tree: &ExprTree<'ctx>, item: Option<&(&'ctx Param, Sym)>) -> Sym {
+        self.scopes.push(HashMap::new());
+        if let Some((param, value)) = item {
+            let ty = match self.param_type(param) {
+                Ty::Opaque => ty_of_sym(value),
+                ty => ty,
+            };
+            self.bind_param(param, value.clone(), ty);
+        }
+
+        let value = self.sym_tree(tree);
+        self.scopes.pop();
+
+        value
+    }
+
+    /// Checks that the invariants of a loop hold before its first iteration.
+    pub(crate) fn establish_loop_invariants(&mut self, invariants: &[ExprTree<'ctx>], item: Option<&(&'ctx Param, Sym)>) {
+        for invariant in invariants {
+            let formula = self.sym_with_item(invariant, item).as_formula();
+            self.obligate(formula, |verifier, counterexample| {
+                Error::InvariantNotEstablished(
+                    invariant.span(),
+                    invariant.to_string_interned(verifier.interner),
+                    counterexample,
+                )
+            });
+        }
+    }
+
+    pub(crate) fn assume_loop_invariants(&mut self, invariants: &[ExprTree<'ctx>], item: Option<&(&'ctx Param, Sym)>) {
+        for invariant in invariants {
+            if let Some(formula) = self.sym_with_item(invariant, item).as_formula() {
+                self.facts.push(formula);
+            }
+        }
+    }
+
+    /// Checks that an iteration of the loop of the exit context keeps its invariants and decreases its measures,
+    /// where the iteration continues the loop at `span`.
+    pub(crate) fn continue_loop(&mut self, index: usize, span: Span) {
+        let context = &self.exit_contexts[index];
+        let Some(contract) = context.contract.clone() else {
+            return;
+        };
+
+        // The locals of the body and of the loops in it aren't what the contract refers to, even if they shadow them.
+        let scopes = self.scopes[..context.depth].to_vec();
+        let outer_scopes = replace(&mut self.scopes, scopes);
+        let label = Label {
+            span,
+            message: "the iteration continues here".to_string(),
+        };
+
+        for invariant in &contract.invariants {
+            let formula = self.sym_with_item(invariant, contract.next_item.as_ref()).as_formula();
+            self.obligate(formula, |verifier, mut counterexample| {
+                counterexample.trace.push(label.clone());
+                Error::InvariantNotKept(
+                    invariant.span(),
+                    invariant.to_string_interned(verifier.interner),
+                    counterexample,
+                )
+            });
+        }
+
+        let values = contract
+            .measures
+            .iter()
+            .map(|(measure, _)| self.sym_with_item(measure, contract.next_item.as_ref()))
+            .collect();
+        self.decrease(&contract.measures, values, None, Some(label));
+
+        self.scopes = outer_scopes;
+    }
+
+    /// Records the `#[decreases(..)]` of the function being verified, evaluated for its parameters.
+    pub(crate) fn enter_recursion(&mut self, attrs: &'ctx [Attr], fn_: &'ctx Fn) {
+        let measures = attr_exprs(attrs, "decreases", self.interner);
+        if measures.is_empty() {
+            return;
+        }
+
+        let measures = measures
+            .into_iter()
+            .map(|measure| {
+                let value = self.sym_tree(&measure);
+                (measure, value)
+            })
+            .collect();
+        if let Some(context) = &mut self.fn_ {
+            context.recursion = Some(Recursion { fn_, measures });
+        }
+    }
+
+    /// The measures of the function being verified and their values for a call's arguments, if the call is to itself.
+    ///
+    /// The parameters of the callee have to be bound to the arguments.
+    pub(crate) fn recursive_measures(&mut self, callee: &'ctx Fn) -> Option<(Measures<'ctx>, Vec<Sym>)> {
+        let recursion = self.fn_.as_ref()?.recursion.clone()?;
+        if !std::ptr::eq(recursion.fn_, callee) {
+            return None;
+        }
+
+        let values = recursion.measures.iter().map(|(measure, _)| self.sym_tree(measure)).collect();
+
+        Some((recursion.measures, values))
+    }
+
+    /// Requires the measures to be at least zero, and their new values to be less than them when compared in order.
+    ///
+    /// Failures are reported at `span` if it's given, and at the measures otherwise.
+    pub(crate) fn decrease(
+        &mut self,
+        measures: &[(ExprTree<'ctx>, Sym)],
+        values: Vec<Sym>,
+        span: Option<Span>,
+        label: Option<Label>,
+    ) {
+        let (Some((first, _)), Some((last, _))) = (measures.first(), measures.last()) else {
+            return;
+        };
+
+        let mut less = Vec::new();
+        let mut equal = Vec::new();
+        for ((measure, before), after) in measures.iter().zip(&values) {
+            let (Sym::Int(before), Sym::Int(after)) = (before, after) else {
+                return;
+            };
+
+            let formula = Formula::cmp(before, CmpOp::MoreEq, &Linear::constant(0));
+            self.obligate(formula, |verifier, mut counterexample| {
+                counterexample.trace.extend(label.clone());
+                let span = span.unwrap_or(measure.span());
+                Error::NegativeMeasure(span, measure.to_string_interned(verifier.interner), counterexample)
+            });
+
+            let (Some(after_less), Some(after_equal)) = (
+                Formula::cmp(after, CmpOp::Less, before),
+                Formula::cmp(after, CmpOp::Eq, before),
+            ) else {
+                return;
+            };
+            less.push(Formula::and(equal.iter().cloned().chain([after_less])));
+            equal.push(after_equal);
+        }
+
+        let text = measures
+            .iter()
+            .map(|(measure, _)| measure.to_string_interned(self.interner))
+            .collect::<Vec<_>>()
+            .join(", ");
+        self.obligate(Some(Formula::or(less)), |_, mut counterexample| {
+            counterexample.trace.extend(label);
+            let span = span.unwrap_or(first.span().connect(last.span()));
+            Error::NotDecreasing(span, text, counterexample)
+        });
+    }
+}
+
+/// The arguments of the attributes with the name, like the expressions of `#[invariant(a, b)]`.
+pub(crate) fn attr_exprs<'ctx>(attrs: &'ctx [Attr], name: &str, interner: &Interner) -> Vec<ExprTree<'ctx>> {
+    attrs
+        .iter()
+        .filter_map(|attr| match attr.body.success_ref()? {
+            AttrBody {
+                ident: Try::Success(ident),
+                value: Some(AttrInput::Fn(args)),
+                ..
+            } if interner.unintern(ident.str_id()) == name => Some(&args.items),
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|arg| match arg {
+            AttrArg::Expr(expr) => expr.tree(),
+            AttrArg::Keyword(_) => None,
+        })
+        .collect()
+}
@@ -0,0 +1,533 @@
+use super::*;
+
+/// The locals a pattern binds, and the parts of the value they're bound to.
+type Bindings = Vec<(Ident, Sym)>;
+
+impl<'ctx, 'c> Verifier<'ctx, 'c> {
+    /// Verifies every arm as a branch where its patterns match and the arms before it don't.
+    ///
+    /// Checks that the arms cover every value the scrutinee may have, and warns about arms
+    /// that can't match because the arms before them already cover everything they match.
+    pub(crate) fn verify_match(&mut self, match_: &'ctx Match) -> Sym {
+        let Try::Success(body) = &match_.body else {
+            return Sym::Opaque;
+        };
+
+        let value = self.sym_expr(&match_.value);
+        // Nothing is checked inside a callee's contract or in unreachable code.
+        let checked = self.contract_depth == 0 && self.reachable;
+
+        let span = match_.keyword.span().connect(match_.value.option_span());
+        if let Sym::Enum(id, ..) = value {
+            self.check_tagged(id, span);
+        }
+        let facts_len = self.facts.len();
+        let trace_len = self.trace.len();
+        let scopes = self.scopes.clone();
+
+        // The conditions of the arms so far, or `None` for arms the verifier can't follow.
+        let mut covered = Vec::<Option<Formula>>::new();
+        let mut branches = None::<Branch<'ctx>>;
+
+        for arm in &body.arms {
+            let arm_span = arm.pattern.span().connect(arm.alternatives.last().map(Spanned::span));
+
+            let mut alternatives = Vec::new();
+            for pattern in arm.patterns() {
+                let mut bindings = Vec::new();
+                let condition = self.pattern(pattern, &value, &mut bindings);
+                alternatives.push((condition, bindings));
+            }
+            let condition = alternatives
+                .iter()
+                .map(|(condition, _)| condition.clone())
+                .collect::<Option<Vec<_>>>()
+                .map(Formula::or);
+
+            if let Some(condition) = &condition {
+                let earlier = covered.iter().flatten().cloned();
+                let unreachable = Formula::or(earlier.chain([condition.clone().not()]));
+
+                if checked && prove(&self.facts, &unreachable) == Verdict::Proven {
+                    self.errors
+                        .push(self.diagnostics.push_warning(Warning::UnreachableArm(arm_span)));
+                }
+            }
+
+            for earlier in covered.iter().flatten() {
+                self.facts.push(earlier.clone().not());
+            }
+            if let Some(condition) = &condition {
+                self.push_step(arm_span, "when this arm matches".to_string(), &Sym::Bool(condition.clone()));
+                self.facts.push(condition.clone());
+            }
+
+            self.scopes.push(HashMap::new());
+            self.bind_alternatives(alternatives, arm_span);
+
+            let guard = arm.guard.as_ref().map(|guard| {
+                let condition = self.sym_expr(&guard.condition).as_formula();
+                let guard_span = guard.condition.option_span().unwrap_or(guard.keyword.span());
+                self.assume_condition(condition.clone(), guard_span, true);
+
+                condition
+            });
+            let arm_value = self.sym_expr(&arm.value);
+
+            // A guard only narrows down what the arm covers when it's over the scrutinee, which
+            // isn't the case for bindings that are joined from several alternatives.
+            covered.push(match guard {
+                None => condition,
+                Some(guard) if arm.alternatives.is_empty() => condition
+                    .zip(guard)
+                    .map(|(condition, guard)| Formula::and([condition, guard])),
+                Some(_) => None,
+            });
+
+            self.scopes.pop();
+            let branch = self.take_branch(facts_len, trace_len, scopes.clone(), arm_value);
+
+            branches = Some(match branches {
+                Some(branches) => {
+                    let value = self.join(branches, branch, span);
+                    self.take_branch(facts_len, trace_len, scopes.clone(), value)
+                }
+                None => branch,
+            });
+        }
+
+        // Arms the verifier can't follow may cover what the others don't.
+        let exhaustive = Formula::or(covered.iter().flatten().cloned());
+        if checked && covered.iter().all(Option::is_some) && prove(&self.facts, &exhaustive) == Verdict::Unproven {
+            let witness = match refute(&self.facts, &exhaustive).model {
+                Some(model) => format!(", `{}` isn't covered", self.describe(&value, &model)),
+                None => String::new(),
+            };
+
+            self.error(Error::NonExhaustive(span, witness));
+        }
+
+        match branches {
+            Some(branches) => self.resume(branches),
+            None => {
+                self.reachable = false;
+                Sym::Opaque
+            }
+        }
+    }
+
+    /// Binds what the patterns of an arm bind.
+    ///
+    /// A name that's bound by several alternatives gets a fresh value that equals the value from whichever one matched.
+    fn bind_alternatives(&mut self, mut alternatives: Vec<(Option<Formula>, Bindings)>, span: Span) {
+        if alternatives.len() == 1 {
+            let (_, bindings) = alternatives.pop().unwrap();
+            for (ident, value) in bindings {
+                self.bind_local(ident.str_id(), value);
+            }
+
+            return;
+        }
+
+        let Some((_, names)) = alternatives.first() else {
+            return;
+        };
+        let names = names
+            .iter()
+            .map(|(ident, value)| (ident.str_id(), ty_of_sym(value)))
+            .collect::<Vec<_>>();
+
+        let mut cases = Vec::new();
+        let mut values = Vec::new();
+        for (name, ty) in &names {
+            values.push(self.fresh(ty, Some(*name), span));
+        }
+        for (condition, bindings) in &alternatives {
+            let equalities = names.iter().zip(&values).filter_map(|((name, _), value)| {
+                let (_, bound) = bindings.iter().find(|(ident, _)| ident.str_id() == *name)?;
+                equate(value, bound)
+            });
+
+            cases.push(
+                condition
+                    .clone()
+                    .map(|condition| Formula::and([condition].into_iter().chain(equalities))),
+            );
+        }
+        if let Some(cases) = cases.into_iter().collect::<Option<Vec<_>>>() {
+            self.facts.push(Formula::or(cases));
+        }
+
+        for ((name, _), value) in names.into_iter().zip(values) {
+            self.bind_local(name, value);
+        }
+    }
+
+    /// Binds what a pattern in a `let`, a `for` or a parameter binds, after checking that it matches the value.
+    pub(crate) fn bind_pattern(&mut self, pattern: &'ctx Pattern, value: Sym) {
+        let mut bindings = Vec::new();
+        let condition = self.pattern(pattern, &value, &mut bindings);
+
+        // Nothing is checked inside a callee's contract or in unreachable code,
+        // and a pattern that may not match isn't assumed to, since that may contradict the facts.
+        let mut holds = true;
+        if self.contract_depth == 0 && self.reachable {
+            let witness = match &condition {
+                Some(condition) if prove(&self.facts, condition) == Verdict::Unproven => {
+                    match refute(&self.facts, condition).model {
+                        Some(model) => Some(format!(", fails for `{}`", self.describe(&value, &model))),
+                        None => Some(String::new()),
+                    }
+                }
+                Some(_) => None,
+                None => pattern.is_refutable(self.interner).then(String::new),
+            };
+
+            if let Some(witness) = witness {
+                self.error(Error::RefutablePattern(pattern.span(), witness));
+                holds = false;
+            }
+        }
+        if holds {
+            self.facts.extend(condition);
+        }
+
+        for (ident, value) in bindings {
+            self.bind_local(ident.str_id(), value);
+        }
+    }
+
+    fn bind_local(&mut self, name: StrId, value: Sym) {
+        let local = Local {
+            ty: ty_of_sym(&value),
+            value,
+            bounds: None,
+            borrowed_mut: false,
+        };
+
+        self.scopes.last_mut().unwrap().insert(name, local);
+    }
+
+    /// The condition for a value to match a pattern, collecting what the pattern binds.
+    ///
+    /// Returns `None` if the verifier can't tell which values the pattern matches.
+    /// Parts of the value the verifier doesn't track are `Opaque`, so they only match patterns that can't fail.
+    fn pattern(&mut self, pattern: &'ctx Pattern, value: &Sym, bindings: &mut Bindings) -> Option<Formula> {
+        match pattern {
+            Pattern::Path(path) => self.path_pattern(path, value, bindings),
+            Pattern::Literal(literal) => {
+                let Sym::Int(value) = value else {
+                    return None;
+                };
+
+                let start = Linear::constant(pattern_int(&literal.start)?);
+                match &literal.end {
+                    None => Formula::cmp(value, CmpOp::Eq, &start),
+                    Some(end) => Some(Formula::and([
+                        Formula::cmp(value, CmpOp::MoreEq, &start)?,
+                        range_end(value, &end.op, end.end.as_ref())?,
+                    ])),
+                }
+            }
+            Pattern::RangeTo(range) => match (value, &range.end) {
+                (Sym::Int(value), Try::Success(end)) => range_end(value, &range.op, Some(end)),
+                _ => None,
+            },
+            Pattern::Tuple(tuple) => self.tuple_pattern(tuple, value, bindings),
+            Pattern::Array(array) => self.array_pattern(array, value, bindings),
+        }
+    }
+
+    /// An array pattern matches arrays of its length, which the array is required to have, with the items it reads.
+    fn array_pattern(&mut self, array: &'ctx ArrayPattern, value: &Sym, bindings: &mut Bindings) -> Option<Formula> {
+        let len = array.items.len();
+        let span = array.span();
+        let values = match value {
+            Sym::Array(items) if items.len() == len => items.clone(),
+            Sym::Array(items) => {
+                self.error(Error::ArrayPatternLen(span, len, items.len() as i128));
+                vec![Sym::Opaque; len]
+            }
+            Sym::ArrayOf(ty, array_len, id) => match array_len.as_constant() {
+                Some(array_len) if array_len != len as i128 => {
+                    self.error(Error::ArrayPatternLen(span, len, array_len));
+                    vec![Sym::Opaque; len]
+                }
+                _ => {
+                    let same_len = Formula::cmp(array_len, CmpOp::Eq, &Linear::constant(len as i128));
+                    self.obligate(same_len, |_, counterexample| {
+                        Error::ArrayPatternLenUnproven(span, len, counterexample)
+                    });
+
+                    (0..len)
+                        .map(|index| self.read(*id, ty, Linear::constant(index as i128), span))
+                        .collect()
+                }
+            },
+            _ => vec![Sym::Opaque; len],
+        };
+
+        self.patterns(array.items.iter().zip(values).collect(), bindings)
+    }
+
+    fn tuple_pattern(&mut self, tuple: &'ctx TuplePattern, value: &Sym, bindings: &mut Bindings) -> Option<Formula> {
+        if let [item] = tuple.items.as_slice() {
+            return self.pattern(item, value, bindings);
+        }
+
+        let values = match value {
+            Sym::Tuple(values) if values.len() == tuple.items.len() => values.clone(),
+            _ => vec![Sym::Opaque; tuple.items.len()],
+        };
+
+        self.patterns(tuple.items.iter().zip(values).collect(), bindings)
+    }
+
+    /// All of the patterns match their values.
+    fn patterns(&mut self, patterns: Vec<(&'ctx Pattern, Sym)>, bindings: &mut Bindings) -> Option<Formula> {
+        let conditions = patterns
+            .into_iter()
+            .map(|(pattern, value)| self.pattern(pattern, &value, bindings))
+            .collect::<Vec<_>>();
+
+        conditions.into_iter().collect::<Option<Vec<_>>>().map(Formula::and)
+    }
+
+    fn path_pattern(&mut self, path: &'ctx PathPattern, value: &Sym, bindings: &mut Bindings) -> Option<Formula> {
+        if path.is_wildcard(self.interner) {
+            return Some(Formula::TRUE);
+        }
+        if let Some(expected) = path.bool_literal(self.interner) {
+            let Sym::Bool(value) = value else {
+                return None;
+            };
+
+            return Some(if expected { value.clone() } else { value.clone().not() });
+        }
+        if let Some(ident) = path.binding(self.interner) {
+            bindings.push((ident, value.clone()));
+            return Some(Formula::TRUE);
+        }
+
+        match &path.tail {
+            Some(PathPatternTail::Fields(fields)) => self.struct_pattern(path.ident, fields, value, bindings),
+            Some(PathPatternTail::Variant(variant)) => self.variant_pattern(path.ident, variant, value, bindings),
+            None => None,
+        }
+    }
+
+    /// `Struct { field, field = pattern, .. }`.
+    fn struct_pattern(
+        &mut self,
+        ident: Ident,
+        fields: &'ctx FieldsPattern,
+        value: &Sym,
+        bindings: &mut Bindings,
+    ) -> Option<Formula> {
+        let Some((id, params)) = self
+            .namespace
+            .get(ident.str_id())
+            .and_then(|id| Some((id, self.struct_fields(id)?)))
+        else {
+            self.fields_bindings(fields, bindings);
+            return None;
+        };
+
+        let values = match value {
+            Sym::Struct(value_id, generics, values) if *value_id == id => {
+                self.assume_invariants(id, generics, values);
+                values.clone()
+            }
+            _ => vec![Sym::Opaque; params.len()],
+        };
+
+        // A pattern with fields that don't match the struct is only followed for its bindings.
+        let mut is_valid = true;
+        let mut patterns = Vec::new();
+        for field in &fields.fields {
+            let FieldPattern::Field(field) = field else {
+                continue;
+            };
+
+            let Some(index) = self.field_index(id, field.ident.str_id()) else {
+                let error = Error::NoField(
+                    field.ident.span,
+                    ident.to_string_interned(self.interner),
+                    field.ident.str_id(),
+                );
+                self.error(error);
+                is_valid = false;
+
+                if let Some(Try::Success(pattern)) = field.value.as_ref().map(|assign| &assign.pattern) {
+                    patterns.push((&**pattern, Sym::Opaque));
+                }
+                continue;
+            };
+
+            match field.value.as_ref().map(|assign| &assign.pattern) {
+                Some(Try::Success(pattern)) => patterns.push((&**pattern, values[index].clone())),
+                Some(Try::Failure(_)) => {}
+                None => bindings.push((field.ident, values[index].clone())),
+            }
+        }
+
+        let has_rest = fields.fields.iter().any(|field| matches!(field, FieldPattern::Rest(_)));
+        if !has_rest {
+            for name in params.iter().filter_map(|param| param.body.success_ref()?.as_ident()) {
+                let is_listed = fields
+                    .fields
+                    .iter()
+                    .any(|field| matches!(field, FieldPattern::Field(field) if field.ident.str_id() == name.str_id()));
+                if !is_listed {
+                    self.error(Error::MissingField(fields.span(), name.str_id()));
+                    is_valid = false;
+                }
+            }
+        }
+
+        self.patterns(patterns, bindings).filter(|_| is_valid)
+    }
+
+    /// `Enum.Variant` or `Enum.Variant(pattern)`, where leaving out the payload matches any payload.
+    fn variant_pattern(
+        &mut self,
+        ident: Ident,
+        variant: &'ctx VariantPattern,
+        value: &Sym,
+        bindings: &mut Bindings,
+    ) -> Option<Formula> {
+        let payload_items = variant.payload.iter().flat_map(|payload| &payload.items);
+
+        let (Try::Success(name), Some(id)) = (&variant.ident, self.namespace.get(ident.str_id())) else {
+            self.patterns(payload_items.map(|item| (item, Sym::Opaque)).collect(), bindings);
+            return None;
+        };
+        let Some(index) = self.enum_variants(id).and_then(|_| self.variant_index(id, name.str_id())) else {
+            if self.enum_variants(id).is_some() {
+                let error = Error::NoVariant(name.span, ident.to_string_interned(self.interner), name.str_id());
+                self.error(error);
+            }
+
+            self.patterns(payload_items.map(|item| (item, Sym::Opaque)).collect(), bindings);
+            return None;
+        };
+
+        let (tag, payload) = match value {
+            Sym::Enum(value_id, tag, payloads) if *value_id == id => (Some(tag), payloads[index].clone()),
+            _ => (None, Sym::Opaque),
+        };
+
+        let payload = match &variant.payload {
+            Some(tuple) => self.tuple_pattern(tuple, &payload, bindings),
+            None => Some(Formula::TRUE),
+        };
+
+        let tag = Formula::cmp(tag?, CmpOp::Eq, &Linear::constant(index as i128))?;
+        Some(Formula::and([tag, payload?]))
+    }
+
+    /// Collects the bindings of a fields pattern without following its value.
+    fn fields_bindings(&mut self, fields: &'ctx FieldsPattern, bindings: &mut Bindings) {
+        for field in &fields.fields {
+            let FieldPattern::Field(field) = field else {
+                continue;
+            };
+
+            match field.value.as_ref().map(|assign| &assign.pattern) {
+                Some(Try::Success(pattern)) => {
+                    self.pattern(pattern, &Sym::Opaque, bindings);
+                }
+                Some(Try::Failure(_)) => {}
+                None => bindings.push((field.ident, Sym::Opaque)),
+            }
+        }
+    }
+
+    /// Describes a value under a model as a pattern, with `_` for the parts the model doesn't decide.
+    fn describe(&self, value: &Sym, model: &Model) -> String {
+        match value {
+            Sym::Int(linear) if is_decided(linear.vars(), model) => {
+                linear.eval(model).map_or("_".to_string(), |value| value.to_string())
+            }
+            Sym::Bool(formula) => {
+                let mut vars = BTreeSet::new();
+                formula.vars(&mut vars);
+
+                match is_decided(vars, model) {
+                    true => formula.eval(model).map_or("_".to_string(), |value| value.to_string()),
+                    false => "_".to_string(),
+                }
+            }
+            Sym::Tuple(values) => {
+                let values = values.iter().map(|value| self.describe(value, model)).collect::<Vec<_>>();
+                format!("({})", values.join(", "))
+            }
+            Sym::Struct(id, _, values) => {
+                let fields = self.struct_fields(*id).unwrap_or_default();
+                let fields = fields
+                    .iter()
+                    .zip(values)
+                    .map(|(field, value)| format!("{} = {}", param_name(field, self.interner), self.describe(value, model)))
+                    .collect::<Vec<_>>();
+
+                format!("{} {{ {} }}", self.item_name(*id), fields.join(", "))
+            }
+            Sym::Enum(id, tag, payloads) if is_decided(tag.vars(), model) => {
+                let variants = self.enum_variants(*id).unwrap_or_default();
+                let Some(index) = tag
+                    .eval(model)
+                    .and_then(|tag| usize::try_from(tag).ok())
+                    .filter(|tag| *tag < variants.len())
+                else {
+                    return "_".to_string();
+                };
+
+                let variant = &variants[index];
+                let name = format!("{}.{}", self.item_name(*id), param_name(variant, self.interner));
+                match (variant.type_.is_some(), &payloads[index]) {
+                    (false, _) => name,
+                    (true, payload @ Sym::Tuple(_)) => format!("{name}{}", self.describe(payload, model)),
+                    (true, payload) => format!("{name}({})", self.describe(payload, model)),
+                }
+            }
+            _ => "_".to_string(),
+        }
+    }
+
+    pub(crate) fn item_name(&self, id: ItemId) -> String {
+        match self.namespace.item(id).core.success_ref().and_then(ItemCore::ident) {
+            Some(ident) => ident.to_string_interned(self.interner),
+            None => "_".to_string(),
+        }
+    }
+}
+
+fn is_decided(vars: impl IntoIterator<Item = Var>, model: &Model) -> bool {
+    vars.into_iter().all(|var| model.contains_key(&var))
+}
+
+/// `value < end` or `value <= end`, or `true` for a range without an end.
+fn range_end(value: &Linear, op: &RangePatternOp, end: Option<&PatternLiteral>) -> Option<Formula> {
+    let Some(end) = end else {
+        return Some(Formula::TRUE);
+    };
+
+    let end = Linear::constant(pattern_int(end)?);
+    match op {
+        RangePatternOp::Exclusive(_) => Formula::cmp(value, CmpOp::Less, &end),
+        RangePatternOp::Inclusive(_) => Formula::cmp(value, CmpOp::LessEq, &end),
+    }
+}
+
+fn pattern_int(literal: &PatternLiteral) -> Option<i128> {
+    let (literal, negate) = match literal {
+        PatternLiteral::Literal(literal) => (literal, false),
+        PatternLiteral::Neg(neg) => (neg.literal.success_ref()?, true),
+    };
+    let Literal::Int(int) = literal else {
+        return None;
+    };
+
+    let value = i128::try_from(*int.value.success_ref()?).ok()?;
+    Some(if negate { -value } else { value })
+}
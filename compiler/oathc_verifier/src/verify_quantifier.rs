@@ -0,0 +1,239 @@
+use super::*;
+
+/// The most items a range with known ends has for a quantifier over it to be instantiated at every one of them.
+const MAX_INSTANTIATED_RANGE: i128 = 16;
+
+/// An item of an array with unknown items that was read, which reading the array at the same index again gives.
+#[derive(Debug, Clone)]
+pub(crate) struct Read {
+    pub array: usize,
+    pub index: Linear,
+    pub value: Sym,
+}
+
+/// A quantifier over a range or over an array with unknown items, which is instantiated at the index of every later read.
+#[derive(Debug, Clone)]
+pub(crate) struct Quantified<'ctx> {
+    pub quantifier: &'ctx Quantifier,
+    /// What the quantifier is over, a `Sym::Range` or a `Sym::ArrayOf`.
+    pub iter: Sym,
+    /// The locals the block can refer to.
+    pub scopes: Vec<HashMap<StrId, Local<'ctx>>>,
+    /// Holds exactly when the quantifier does.
+    pub holds: Formula,
+}
+
+impl<'ctx, 'c> Verifier<'ctx, 'c> {
+    /// An array of `len` items of the type, that nothing is known about.
+    pub(crate) fn array_of(&mut self, ty: Ty, len: Linear) -> Sym {
+        self.arrays += 1;
+        Sym::ArrayOf(ty, len, self.arrays)
+    }
+
+    /// Reads the item of an array with unknown items at the index, which is the same value for the same index.
+    ///
+    /// Quantifiers over the array and over ranges are instantiated at the index of a new read.
+    pub(crate) fn read(&mut self, array: usize, ty: &Ty, index: Linear, span: Span) -> Sym {
+        if let Some(read) = self.reads.iter().find(|read| read.array == array && read.index == index) {
+            return read.value.clone();
+        }
+
+        let value = self.fresh(ty, None, span);
+        self.reads.push(Read {
+            array,
+            index: index.clone(),
+            value: value.clone(),
+        });
+
+        // Instantiating only at the reads of the code being verified keeps reads in blocks from instantiating forever.
+        if !self.instantiating {
+            for quantified in self.quantifiers.clone() {
+                let item = match &quantified.iter {
+                    Sym::Range(..) => Sym::Int(index.clone()),
+                    Sym::ArrayOf(_, _, other) if *other == array => value.clone(),
+                    _ => continue,
+                };
+                if let Some(cond) = in_domain(&quantified.iter, &index) {
+                    self.instantiate(&quantified, item, cond);
+                }
+            }
+        }
+
+        value
+    }
+
+    /// Verifies `all` or `any`, whose value is a variable that holds exactly when the quantifier does.
+    ///
+    /// The block is verified for an item nothing else is known about, which holding for makes `all` hold,
+    /// and which `any` holding makes hold. The quantifier is then instantiated at the items of arrays with known items,
+    /// of short ranges with known ends, and at the indices that were and will be read, which gives what it means
+    /// for those items.
+    pub(crate) fn verify_quantifier(&mut self, quantifier: &'ctx Quantifier) -> Sym {
+        let iter = match &quantifier.iter {
+            Try::Success(iter) => self.sym_expr(&**iter),
+            Try::Failure(_) => Sym::Opaque,
+        };
+        let Try::Success(item) = &quantifier.item else {
+            return Sym::Opaque;
+        };
+        let all = matches!(quantifier.keyword, QuantifierKeyword::All(_));
+        let span = item.option_span().unwrap_or(quantifier.keyword.span());
+        let reads_len = self.reads.len();
+
+        // The item has no facts of its own, so that it can be any of them. It isn't named after the item,
+        // since its value in counterexamples of anything else is meaningless.
+        let (value, cond) = match &iter {
+            Sym::Range(..) => {
+                let index = Linear::var(self.new_var(None, span, false, None));
+                let Some(cond) = in_domain(&iter, &index) else {
+                    return Sym::Opaque;
+                };
+
+                (Sym::Int(index), cond)
+            }
+            Sym::Array(items) => {
+                let ty = items.first().map_or(Ty::Opaque, ty_of_sym);
+                let value = self.fresh(&ty, None, span);
+                let Some(cond) = items.iter().map(|item| equate(&value, item)).collect::<Option<Vec<_>>>() else {
+                    return Sym::Opaque;
+                };
+
+                (value, Formula::or(cond))
+            }
+            Sym::ArrayOf(ty, _, array) => {
+                let index = Linear::var(self.new_var(None, span, false, None));
+                let Some(cond) = in_domain(&iter, &index) else {
+                    return Sym::Opaque;
+                };
+
+                (self.read(*array, ty, index, span), cond)
+            }
+            _ => return Sym::Opaque,
+        };
+
+        let facts_len = self.facts.len();
+        self.facts.push(cond.clone());
+        let body = self.quantified_block(quantifier, self.scopes.clone(), value);
+        let facts = self.facts.split_off(facts_len + 1);
+        self.facts.truncate(facts_len);
+        self.facts.push(Formula::or([cond.clone().not(), Formula::and(facts)]));
+        let Some(body) = body else {
+            return Sym::Opaque;
+        };
+
+        let holds = self.fresh(&Ty::Bool, None, span).as_formula().unwrap();
+        self.facts.push(match all {
+            true => Formula::or([Formula::or([cond.clone().not(), body]).not(), holds.clone()]),
+            false => Formula::or([holds.clone().not(), Formula::and([cond, body])]),
+        });
+
+        let quantified = Quantified {
+            quantifier,
+            iter: iter.clone(),
+            scopes: self.scopes.clone(),
+            holds: holds.clone(),
+        };
+
+        let mut instances = Vec::new();
+        match &iter {
+            Sym::Array(items) => instances.extend(items.iter().map(|item| (item.clone(), Formula::TRUE))),
+            Sym::Range(start, end) => {
+                if let (Some(start), Some(end)) = (start.as_constant(), end.as_constant())
+                    && end - start <= MAX_INSTANTIATED_RANGE
+                {
+                    instances.extend((start..end).map(|index| (Sym::Int(Linear::constant(index)), Formula::TRUE)));
+                }
+                for read in &self.reads[..reads_len] {
+                    instances.extend(in_domain(&iter, &read.index).map(|cond| (Sym::Int(read.index.clone()), cond)));
+                }
+            }
+            Sym::ArrayOf(_, _, array) => {
+                for read in self.reads[..reads_len].iter().filter(|read| read.array == *array) {
+                    instances.extend(in_domain(&iter, &read.index).map(|cond| (read.value.clone(), cond)));
+                }
+            }
+            _ => {}
+        }
+        for (item, cond) in instances {
+            self.instantiate(&quantified, item, cond);
+        }
+
+        if matches!(iter, Sym::Range(..) | Sym::ArrayOf(..)) {
+            self.quantifiers.push(quantified);
+        }
+
+        Sym::Bool(holds)
+    }
+
+    /// Adds what a quantifier means for an item, which is one of what it's over when `cond` holds.
+    fn instantiate(&mut self, quantified: &Quantified<'ctx>, item: Sym, cond: Formula) {
+        let instantiating = replace(&mut self.instantiating, true);
+        let facts_len = self.facts.len();
+        self.contract_depth += 1;
+
+        let body = self.quantified_block(quantified.quantifier, quantified.scopes.clone(), item);
+
+        self.contract_depth -= 1;
+        self.instantiating = instantiating;
+
+        // Outside of what the quantifier is over, nothing that was checked in the block holds.
+        let facts = self.facts.split_off(facts_len);
+        self.facts.push(Formula::or([cond.clone().not(), Formula::and(facts)]));
+        let Some(body) = body else {
+            return;
+        };
+
+        let holds = quantified.holds.clone();
+        self.facts.push(match quantified.quantifier.keyword {
+            QuantifierKeyword::All(_) => Formula::or([holds.not(), cond.not(), body]),
+            QuantifierKeyword::Any(_) => Formula::or([Formula::and([cond, body]).not(), holds]),
+        });
+    }
+
+    /// Evaluates the block of a quantifier for an item, with the locals in `scopes`.
+    fn quantified_block(
+        &mut self,
+        quantifier: &'ctx Quantifier,
+        scopes: Vec<HashMap<StrId, Local<'ctx>>>,
+        item: Sym,
+    ) -> Option<Formula> {
+        let (Try::Success(param), Try::Success(block)) = (&quantifier.item, &quantifier.block) else {
+            return None;
+        };
+
+        let outer_scopes = replace(&mut self.scopes, scopes);
+        let outer_reachable = replace(&mut self.reachable, true);
+        let outer_tries = take(&mut self.tries);
+        let outer_exit_contexts = take(&mut self.exit_contexts);
+        let trace_len = self.trace.len();
+
+        self.scopes.push(HashMap::new());
+        let ty = match self.param_type(param) {
+            Ty::Opaque => ty_of_sym(&item),
+            ty => ty,
+        };
+        self.bind_param(param, item, ty);
+        let (value, _) = self.verify_block(block);
+
+        self.scopes = outer_scopes;
+        self.reachable = outer_reachable;
+        self.tries = outer_tries;
+        self.exit_contexts = outer_exit_contexts;
+        self.trace.truncate(trace_len);
+
+        value.as_formula()
+    }
+}
+
+/// Whether the index is one of a range, or one of the indices of an array.
+fn in_domain(iter: &Sym, index: &Linear) -> Option<Formula> {
+    let (start, end) = match iter {
+        Sym::Range(start, end) => (start.clone(), end.clone()),
+        Sym::ArrayOf(_, len, _) => (Linear::constant(0), len.clone()),
+        _ => return None,
+    };
+
+    Formula::cmp(&start, CmpOp::LessEq, index)
+        .zip(Formula::cmp(index, CmpOp::Less, &end))
+        .map(|(start, end)| Formula::and([start, end]))
+}
@@ -0,0 +1,311 @@
+use super::*;
+
+/// A constraint a struct value has to satisfy, evaluated over its fields.
+struct Invariant {
+    formula: Option<Formula>,
+    /// The field whose bound this is, or `None` for the struct's `require`s.
+    field: Option<usize>,
+    text: String,
+    /// Where the bound or the `require` is declared.
+    span: Span,
+}
+
+impl<'ctx, 'c> Verifier<'ctx, 'c> {
+    pub(crate) fn struct_fields(&self, id: ItemId) -> Option<&'ctx [Param]> {
+        match &self.namespace.item(id).core {
+            Try::Success(ItemCore::Struct(Struct {
+                fields: Try::Success(fields),
+                ..
+            })) => Some(&fields.items),
+            _ => None,
+        }
+    }
+
+    /// The type a name refers to if it's a struct or an enum the verifier can track,
+    /// without the generic arguments of a generic struct.
+    pub(crate) fn struct_ty(&self, name: StrId) -> Ty {
+        match self.namespace.get(name) {
+            Some(id) if self.struct_fields(id).is_some() => Ty::Struct(id, Vec::new()),
+            Some(id) if self.enum_variants(id).is_some() => Ty::Enum(id),
+            _ => Ty::Opaque,
+        }
+    }
+
+    fn struct_generics(&self, id: ItemId) -> Vec<&'ctx Param> {
+        match &self.namespace.item(id).core {
+            Try::Success(ItemCore::Struct(struct_)) => struct_
+                .generics
+                .iter()
+                .flat_map(|generics| &generics.params)
+                .filter(|param| !param.is_lifetime())
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Whether the verifier knows the generic arguments of a struct, which its field types and invariants depend on.
+    pub(crate) fn knows_generics(&self, id: ItemId, generics: &[Sym]) -> bool {
+        self.struct_generics(id).len() == generics.len() && !generics.contains(&Sym::Opaque)
+    }
+
+    /// Binds the generic parameters of a struct to its generic arguments in the innermost scope.
+    fn bind_struct_generics(&mut self, id: ItemId, generics: &[Sym]) {
+        for (param, generic) in self.struct_generics(id).into_iter().zip(generics) {
+            let ty = self.param_type(param);
+            self.bind_param(param, generic.clone(), ty);
+        }
+    }
+
+    /// The types of a struct's fields, for its generic arguments.
+    pub(crate) fn field_types(&mut self, id: ItemId, generics: &[Sym]) -> Vec<Ty> {
+        let Some(params) = self.struct_fields(id) else {
+            return Vec::new();
+        };
+
+        let outer_scopes = replace(&mut self.scopes, vec![HashMap::new()]);
+        self.bind_struct_generics(id, generics);
+        let tys = params.iter().map(|param| self.param_type(param)).collect();
+        self.scopes = outer_scopes;
+
+        tys
+    }
+
+    pub(crate) fn field_index(&self, id: ItemId, name: StrId) -> Option<usize> {
+        self.struct_fields(id)?.iter().position(|field| {
+            field
+                .body
+                .success_ref()
+                .and_then(ParamBody::as_ident)
+                .is_some_and(|ident| ident.str_id() == name)
+        })
+    }
+
+    pub(crate) fn fresh_struct(&mut self, id: ItemId, generics: Vec<Sym>, name: Option<StrId>, span: Span) -> Sym {
+        let Some(params) = self.struct_fields(id) else {
+            return Sym::Opaque;
+        };
+        if !self.knows_generics(id, &generics) || self.structs.contains(&id) {
+            return Sym::Opaque;
+        }
+
+        self.structs.push(id);
+        let fields = self
+            .field_types(id, &generics)
+            .iter()
+            .zip(params)
+            .map(|(ty, param)| {
+                let name = self.member_name(name, param_name(param, self.interner));
+                self.fresh(ty, name, span)
+            })
+            .collect();
+        self.structs.pop();
+
+        Sym::Struct(id, generics, fields)
+    }
+
+    /// Evaluates a `Struct { field = value, .. }` expression, and checks the struct's invariants against it.
+    ///
+    /// A violated field bound is reported at the field's initializer. The invariants of a generic struct whose generic
+    /// arguments aren't known, like ones that are types, are checked for every instance of the function instead.
+    pub(crate) fn construct(&mut self, id: ItemId, generics: Vec<Sym>, construct: &'ctx Construct, span: Span) -> Sym {
+        let Some(params) = self.struct_fields(id) else {
+            return Sym::Opaque;
+        };
+        if !self.knows_generics(id, &generics) {
+            for field in &construct.items {
+                if let Try::Success(set) = &field.set {
+                    self.sym_expr(&set.value);
+                }
+            }
+            self.instance_sites.insert(span);
+
+            return Sym::Opaque;
+        }
+
+        let mut initializers = vec![None; params.len()];
+        for field in &construct.items {
+            let Try::Success(set) = &field.set else {
+                continue;
+            };
+
+            let value = self.sym_expr(&set.value);
+            let value_span = set.value.option_span().unwrap_or(field.ident.span);
+            if let Some(index) = self.field_index(id, field.ident.str_id()) {
+                initializers[index] = Some((value, value_span));
+            }
+        }
+
+        let fields = self
+            .field_types(id, &generics)
+            .iter()
+            .zip(&initializers)
+            .map(|(ty, initializer)| match initializer {
+                Some((value, value_span)) => self.materialize(value.clone(), ty, None, *value_span),
+                None => self.fresh(ty, None, span),
+            })
+            .collect::<Vec<_>>();
+
+        let holds = self.check_invariants(id, &generics, &fields, span, |field| {
+            field
+                .and_then(|field| initializers[field].as_ref())
+                .map_or(span, |(_, span)| *span)
+        });
+
+        // A value that breaks the invariants would contradict them once they're assumed when it's read.
+        match holds {
+            true => Sym::Struct(id, generics, fields),
+            false => self.fresh_struct(id, generics, None, span),
+        }
+    }
+
+    /// Replaces the part of `target` that `path` leads to, like the `.end` in `range.end = 5`,
+    /// and checks the invariants of every struct on the way.
+    ///
+    /// Returns `None` if the verifier can't follow the path.
+    pub(crate) fn assign_member(&mut self, target: Sym, path: &[&'ctx Member], value: Sym, span: Span) -> Option<Sym> {
+        let Some((member, path)) = path.split_first() else {
+            return Some(value);
+        };
+
+        match (target, member) {
+            (Sym::Tuple(mut values), Member::Unnamed(index)) => {
+                let index = match index.value {
+                    Try::Success(index) if (index as usize) < values.len() => index as usize,
+                    _ => return None,
+                };
+
+                let target = replace(&mut values[index], Sym::Opaque);
+                values[index] = self.assign_member(target, path, value, span)?;
+
+                Some(Sym::Tuple(values))
+            }
+            (Sym::Struct(id, generics, mut fields), Member::Named(ident)) => {
+                let index = self.field_index(id, ident.str_id())?;
+                let ty = self.field_types(id, &generics).swap_remove(index);
+
+                // What's known about the other fields still holds, which is what the new value is checked against.
+                self.assume_invariants(id, &generics, &fields);
+
+                let target = replace(&mut fields[index], Sym::Opaque);
+                let value = self.assign_member(target, path, value, span)?;
+                fields[index] = self.materialize(value, &ty, Some(ident.str_id()), span);
+
+                match self.check_invariants(id, &generics, &fields, span, |_| span) {
+                    true => Some(Sym::Struct(id, generics, fields)),
+                    false => Some(self.fresh_struct(id, generics, None, span)),
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Assumes a struct's invariants, which every value of it satisfies.
+    ///
+    /// Shown with failed constraints on the fields, the bounds of the fields are steps where they're declared.
+    pub(crate) fn assume_invariants(&mut self, id: ItemId, generics: &[Sym], fields: &[Sym]) {
+        for invariant in self.invariants(id, generics, fields) {
+            let Some(formula) = invariant.formula else {
+                continue;
+            };
+
+            let param = invariant
+                .field
+                .zip(self.struct_fields(id))
+                .map(|(field, params)| &params[field]);
+            if let Some(param) = param {
+                let value = Sym::Bool(formula.clone());
+                let mut vars = BTreeSet::new();
+                value.vars(&mut vars);
+
+                // Reading the fields again assumes the same bounds, which are only shown once.
+                if !self.trace.iter().any(|step| step.span == invariant.span && step.vars == vars) {
+                    let message = format!("`{}` is declared as `{}`", param_name(param, self.interner), invariant.text);
+                    self.push_step(invariant.span, message, &value);
+                }
+            }
+            self.facts.push(formula);
+        }
+    }
+
+    /// Checks the invariants of a value of a struct where it's constructed or assigned to, which is `site`,
+    /// and returns whether none of them failed.
+    fn check_invariants(
+        &mut self,
+        id: ItemId,
+        generics: &[Sym],
+        fields: &[Sym],
+        site: Span,
+        span: impl std::ops::Fn(Option<usize>) -> Span,
+    ) -> bool {
+        let reported = self.errors.len();
+        let mut holds = true;
+        for invariant in self.invariants(id, generics, fields) {
+            let span = span(invariant.field);
+            holds &= self.obligate(invariant.formula, |_, counterexample| {
+                Error::BoundViolated(span, invariant.text, counterexample)
+            });
+        }
+
+        // An instance of a function only reports the sites the function's own verification couldn't check,
+        // and each of them only for the first instance it fails for.
+        if let Some(kept) = &mut self.instance
+            && !holds
+            && self.instance_sites.remove(&site)
+        {
+            kept.extend(self.errors.drain(reported..));
+        }
+
+        holds
+    }
+
+    /// Evaluates the bounds of a struct's fields and its `require`s,
+    /// with its generic parameters bound to its generic arguments and the field names bound to the field values.
+    fn invariants(&mut self, id: ItemId, generics: &[Sym], fields: &[Sym]) -> Vec<Invariant> {
+        let (Try::Success(ItemCore::Struct(struct_)), Some(params)) = (&self.namespace.item(id).core, self.struct_fields(id))
+        else {
+            return Vec::new();
+        };
+        let Try::Success(framed_fields) = &struct_.fields else {
+            return Vec::new();
+        };
+
+        let outer_scopes = replace(&mut self.scopes, vec![HashMap::new()]);
+        let outer_out = self.out.take();
+
+        self.bind_struct_generics(id, generics);
+        for (param, value) in params.iter().zip(fields) {
+            let ty = self.param_type(param);
+            self.bind_param(param, value.clone(), ty);
+        }
+
+        let mut invariants = Vec::new();
+        for (index, (param, value)) in params.iter().zip(fields).enumerate() {
+            if let Some(bounds) = param.bounds.as_ref().and_then(|bounds| bounds.expr.tree()) {
+                invariants.push(Invariant {
+                    formula: self.bound_formula(value, &bounds),
+                    field: Some(index),
+                    span: param.option_span().unwrap_or(bounds.span()),
+                    text: format!(
+                        "{}: {}",
+                        param_name(param, self.interner),
+                        bounds.to_string_interned(self.interner)
+                    ),
+                });
+            }
+        }
+
+        for require in requires(&[&struct_.contract, &framed_fields.contract]) {
+            invariants.push(Invariant {
+                formula: self.sym_contract(&require).as_formula(),
+                field: None,
+                span: require.span(),
+                text: require.to_string_interned(self.interner),
+            });
+        }
+
+        self.scopes = outer_scopes;
+        self.out = outer_out;
+
+        invariants
+    }
+}
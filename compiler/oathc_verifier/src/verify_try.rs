@@ -0,0 +1,218 @@
+use super::*;
+
+/// A `try` block being verified, which the `?`s in it propagate errors to.
+#[derive(Debug)]
+pub(crate) struct TryContext<'ctx> {
+    /// The result enum the block evaluates to, which is the one of its first `?`, and where that `?` is.
+    pub target: Option<(ItemId, Span)>,
+    /// The number of scopes around the block.
+    pub depth: usize,
+    pub facts_len: usize,
+    pub trace_len: usize,
+    /// Where the block is left through `?`, with the errors it evaluates to there.
+    pub exits: Vec<Branch<'ctx>>,
+}
+
+impl<'ctx, 'c> Verifier<'ctx, 'c> {
+    /// Verifies a `try` block, whose value is either the value it evaluates to or an error one of its `?`s propagates.
+    pub(crate) fn verify_try(&mut self, try_: &'ctx TryBlock) -> Sym {
+        let Try::Success(block) = &try_.block else {
+            return Sym::Opaque;
+        };
+
+        let scopes = self.scopes.clone();
+        self.tries.push(TryContext {
+            target: None,
+            depth: self.scopes.len(),
+            facts_len: self.facts.len(),
+            trace_len: self.trace.len(),
+            exits: Vec::new(),
+        });
+        let (mut value, span) = self.verify_block(block);
+        let context = self.tries.pop().unwrap();
+
+        if let (Some((target, question)), Sym::Enum(id, ..)) = (context.target, &value)
+            && *id != target
+            && self.reachable
+        {
+            let labels = vec![Label {
+                span: question,
+                message: format!("`?` propagates errors as `{}` here", self.item_name(target)),
+            }];
+            self.error(Error::TryMismatch(span, self.item_name(*id), self.item_name(target), labels));
+        }
+
+        for exit in context.exits {
+            let end = self.take_branch(context.facts_len, context.trace_len, scopes.clone(), value);
+            value = self.join(end, exit, try_.keyword.span());
+        }
+
+        value
+    }
+
+    /// Evaluates `value?`, which is the `Ok` payload of the value.
+    ///
+    /// When the value is `Err`, its payload is propagated to the innermost `try` block,
+    /// or returned from the function as the `Err` of the function's output.
+    pub(crate) fn propagate(&mut self, value: Sym, span: Span) -> Sym {
+        let (id, tag, payloads) = match value {
+            Sym::Enum(id, tag, payloads) => (id, tag, payloads),
+            Sym::Int(_) | Sym::Bool(_) | Sym::Tuple(_) | Sym::Struct(..) => {
+                self.error(Error::NotFallible(span));
+                return Sym::Opaque;
+            }
+            _ => return Sym::Opaque,
+        };
+        let Some((ok, err)) = self.result_variants(id) else {
+            self.error(Error::NotFallible(span));
+            return Sym::Opaque;
+        };
+
+        if self.contract_depth == 0 {
+            let facts_len = self.facts.len();
+            let trace_len = self.trace.len();
+
+            self.push_step(span, "when this is `Err`".to_string(), &Sym::Int(tag.clone()));
+            self.facts
+                .extend(Formula::cmp(&tag, CmpOp::Eq, &Linear::constant(err as i128)));
+
+            let error = payloads[err].clone();
+            match self.tries.is_empty() {
+                false => self.exit_try(id, error, span),
+                true => self.return_error(id, error, span),
+            }
+
+            self.facts.truncate(facts_len);
+            self.trace.truncate(trace_len);
+        }
+
+        self.facts
+            .extend(Formula::cmp(&tag, CmpOp::Eq, &Linear::constant(ok as i128)));
+        payloads[ok].clone()
+    }
+
+    /// Leaves the innermost `try` block with an error of the result enum `id`.
+    fn exit_try(&mut self, id: ItemId, error: Sym, span: Span) {
+        let context = self.tries.last_mut().unwrap();
+        let (target, question) = *context.target.get_or_insert((id, span));
+        let (depth, facts_len, trace_len) = (context.depth, context.facts_len, context.trace_len);
+
+        if target != id {
+            let label = Label {
+                span: question,
+                message: format!(
+                    "the `try` block evaluates to `{}` because of this `?`",
+                    self.item_name(target)
+                ),
+            };
+            if !self.check_error_type(id, target, span, label) {
+                return;
+            }
+        }
+
+        let value = self.error_value(target, error);
+        let exit = self.branch_here(depth, facts_len, trace_len, value);
+        self.tries.last_mut().unwrap().exits.push(exit);
+    }
+
+    /// Returns an error of the result enum `id` from the function, as the `Err` of the function's output.
+    fn return_error(&mut self, id: ItemId, error: Sym, span: Span) {
+        let Some(fn_) = &self.fn_ else {
+            return;
+        };
+        let output_span = fn_.output_span;
+        let label = output_span.map(|span| Label {
+            span,
+            message: "the function returns this".to_string(),
+        });
+
+        let target = match fn_.output {
+            Ty::Enum(target) if self.result_variants(target).is_some() => target,
+            Ty::Opaque => return,
+            _ => {
+                self.error(Error::PropagateOutsideFallible(span, label.into_iter().collect()));
+                return;
+            }
+        };
+        if target != id {
+            let label = label.unwrap_or(Label {
+                span,
+                message: String::new(),
+            });
+            if !self.check_error_type(id, target, span, label) {
+                return;
+            }
+        }
+
+        let value = self.error_value(target, error);
+        self.check_return(value, span);
+    }
+
+    /// Checks that the errors of the result enum `id` can be propagated as errors of `target`, which is when both hold
+    /// errors of the same type.
+    fn check_error_type(&mut self, id: ItemId, target: ItemId, span: Span, label: Label) -> bool {
+        let (ty, text) = self.error_type(id);
+        let (target_ty, target_text) = self.error_type(target);
+
+        let matches = match (&ty, &target_ty) {
+            (Ty::Opaque, _) | (_, Ty::Opaque) => text == target_text,
+            _ => ty == target_ty,
+        };
+        if !matches {
+            let labels = vec![label].into_iter().filter(|label| !label.message.is_empty()).collect();
+            self.error(Error::PropagateMismatch(
+                span,
+                text,
+                self.item_name(target),
+                target_text,
+                labels,
+            ));
+        }
+
+        matches
+    }
+
+    /// The indices of the `Ok` and `Err` variants of an enum, if it has both.
+    pub(crate) fn result_variants(&self, id: ItemId) -> Option<(usize, usize)> {
+        let variants = self.enum_variants(id)?;
+        let index = |name: &str| {
+            variants.iter().position(|variant| {
+                variant
+                    .body
+                    .success_ref()
+                    .and_then(ParamBody::as_ident)
+                    .is_some_and(|ident| self.interner.unintern(ident.str_id()) == name)
+            })
+        };
+
+        Some((index("Ok")?, index("Err")?))
+    }
+
+    /// The type of the payload of the `Err` variant of a result enum, and how it's written.
+    fn error_type(&mut self, id: ItemId) -> (Ty, String) {
+        let variant = self.result_variants(id).map(|(_, err)| &self.enum_variants(id).unwrap()[err]);
+
+        match variant.and_then(|variant| {
+            variant
+                .type_
+                .as_ref()
+                .and_then(AngleUnaryExpr::as_ref_expr)
+                .map(|type_| (variant, type_))
+        }) {
+            Some((variant, type_)) => (
+                self.param_type(variant),
+                ExprTree::Unary(type_).to_string_interned(self.interner),
+            ),
+            None => (Ty::Tuple(Vec::new()), "()".to_string()),
+        }
+    }
+
+    /// The `Err` variant of a result enum with an error as its payload.
+    fn error_value(&mut self, id: ItemId, error: Sym) -> Sym {
+        let Some((_, err)) = self.result_variants(id) else {
+            return Sym::Opaque;
+        };
+
+        self.enum_value(id, err, error)
+    }
+}
@@ -10,10 +10,12 @@ use tower_lsp::lsp_types::{
 use tower_lsp::{
     jsonrpc::Result as LspResult,
     lsp_types::{
-        CompletionItem, CompletionOptions, CompletionParams, CompletionResponse, DocumentFormattingParams, Hover, HoverParams,
-        HoverProviderCapability, InitializeParams, InitializeResult, OneOf, Position, Range, SemanticTokens,
-        SemanticTokensFullOptions, SemanticTokensLegend, SemanticTokensOptions, SemanticTokensParams, SemanticTokensResult,
-        SemanticTokensServerCapabilities, ServerCapabilities, TextDocumentSyncCapability, TextDocumentSyncKind, TextEdit,
+        CodeAction, CodeActionKind, CodeActionOrCommand, CodeActionParams, CodeActionProviderCapability, CodeActionResponse,
+        CompletionItem, CompletionOptions, CompletionParams, CompletionResponse, DocumentFormattingParams, Hover, HoverContents,
+        HoverParams, HoverProviderCapability, InitializeParams, InitializeResult, MarkupContent, MarkupKind, OneOf, Position,
+        Range, SemanticTokens, SemanticTokensFullOptions, SemanticTokensLegend, SemanticTokensOptions, SemanticTokensParams,
+        SemanticTokensResult, SemanticTokensServerCapabilities, ServerCapabilities, TextDocumentSyncCapability,
+        TextDocumentSyncKind, TextEdit, WorkspaceEdit,
     },
     Client, LanguageServer, LspService, Server,
 };
@@ -67,8 +69,45 @@ impl LanguageServer for BackendArc {
         Ok(())
     }
 
-    async fn hover(&self, _: HoverParams) -> tower_lsp::jsonrpc::Result<Option<Hover>> {
-        Ok(None)
+    async fn hover(&self, params: HoverParams) -> tower_lsp::jsonrpc::Result<Option<Hover>> {
+        let params = params.text_document_position_params;
+        let Some(inferred) = self.inferred_contract_at(&params.text_document.uri, params.position) else {
+            return Ok(None);
+        };
+
+        Ok(Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: format!(
+                    "inferred contract of `{}`\n```oath\n{}\n```",
+                    inferred.item,
+                    inferred.promise()
+                ),
+            }),
+            range: Some(convert_span(inferred.span)),
+        }))
+    }
+
+    async fn code_action(&self, params: CodeActionParams) -> tower_lsp::jsonrpc::Result<Option<CodeActionResponse>> {
+        let uri = params.text_document.uri;
+        let Some(inferred) = self.inferred_contract_at(&uri, params.range.start) else {
+            return Ok(None);
+        };
+
+        let edit = TextEdit {
+            range: Range::new(convert_position(inferred.insert_at), convert_position(inferred.insert_at)),
+            new_text: format!("{} ", inferred.promise()),
+        };
+
+        Ok(Some(vec![CodeActionOrCommand::CodeAction(CodeAction {
+            title: format!("Make the inferred contract of `{}` explicit", inferred.item),
+            kind: Some(CodeActionKind::REFACTOR_REWRITE),
+            edit: Some(WorkspaceEdit {
+                changes: Some(HashMap::from([(uri, vec![edit])])),
+                ..Default::default()
+            }),
+            ..Default::default()
+        })]))
     }
 
     async fn completion(&self, _: CompletionParams) -> tower_lsp::jsonrpc::Result<Option<CompletionResponse>> {
@@ -129,10 +168,30 @@ impl LanguageServer for BackendArc {
     }
 }
 
+impl Backend {
+    /// The inferred contract of the function whose signature is at the position, from its name up to its body.
+    fn inferred_contract_at(&self, uri: &Url, position: Position) -> Option<InferredContract> {
+        let path = uri.to_file_path().ok()?;
+        let libs = self.libs.read().unwrap();
+
+        libs.values()
+            .flat_map(|lib| self.oathc.inferred_contracts(*lib))
+            .filter(|inferred| self.oathc.span_path(inferred.span) == path)
+            .find(|inferred| {
+                let start = convert_position(inferred.span.start());
+                let end = convert_position(inferred.insert_at);
+
+                (start.line, start.character) <= (position.line, position.character)
+                    && (position.line, position.character) <= (end.line, end.character)
+            })
+    }
+}
+
 fn capabilities() -> ServerCapabilities {
     ServerCapabilities {
         document_formatting_provider: Some(OneOf::Left(true)),
         hover_provider: Some(HoverProviderCapability::Simple(true)),
+        code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
         completion_provider: Some(CompletionOptions::default()),
         text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
         semantic_tokens_provider: Some(SemanticTokensServerCapabilities::SemanticTokensOptions(
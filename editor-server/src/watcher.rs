@@ -4,7 +4,9 @@ use std::{
     thread::sleep,
 };
 
-use tower_lsp::lsp_types::{request::SemanticTokensRefresh, Diagnostic as LspDiagnostic, DiagnosticSeverity, Url};
+use tower_lsp::lsp_types::{
+    request::SemanticTokensRefresh, Diagnostic as LspDiagnostic, DiagnosticRelatedInformation, DiagnosticSeverity, Location, Url,
+};
 use walkdir::WalkDir;
 
 use super::*;
@@ -27,6 +29,20 @@ async fn watch(backend: Weak<Backend>) {
                     range: convert_span(diagnostic.span()),
                     severity: Some(DiagnosticSeverity::ERROR),
                     message: backend.oathc.format_diagnostic(&diagnostic),
+                    related_information: Some(
+                        diagnostic
+                            .labels()
+                            .iter()
+                            .map(|label| DiagnosticRelatedInformation {
+                                location: Location::new(
+                                    Url::from_file_path(backend.oathc.span_path(label.span)).unwrap(),
+                                    convert_span(label.span),
+                                ),
+                                message: label.message.clone(),
+                            })
+                            .collect(),
+                    )
+                    .filter(|labels: &Vec<_>| !labels.is_empty()),
                     ..Default::default()
                 })
                 .collect();